@@ -2,7 +2,7 @@ use itertools::Itertools;
 use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
 use quote::{quote, quote_spanned};
 use syn::{
-    Block, Expr, ExprClosure, Ident, LitStr, Token, braced,
+    Attribute, Block, Expr, ExprClosure, Ident, LitStr, Token, braced,
     ext::IdentExt,
     parse::{Parse, ParseStream},
     parse_macro_input,
@@ -35,6 +35,34 @@ impl Parse for HtmlCommands {
     }
 }
 
+/// The macro input, optionally starting with `#![tolerant]`.
+#[derive(Debug)]
+struct HtmlTemplate {
+    tolerant: bool,
+    commands: HtmlCommands,
+}
+
+impl Parse for HtmlTemplate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut tolerant = false;
+        for attribute in input.call(Attribute::parse_inner)? {
+            if attribute.path().is_ident("tolerant") {
+                attribute.meta.require_path_only()?;
+                tolerant = true;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    attribute,
+                    "unknown attribute, expected #![tolerant]",
+                ));
+            }
+        }
+        Ok(Self {
+            tolerant,
+            commands: input.parse()?,
+        })
+    }
+}
+
 #[derive(Debug)]
 enum HtmlCommand {
     ElementOpen(HtmlElement),
@@ -688,12 +716,26 @@ impl Parse for HtmlWhile {
     }
 }
 
-fn convert_commands(commands: &HtmlCommands) -> Vec<TokenStream> {
-    commands.commands.iter().map(convert_command).collect()
+fn convert_commands(commands: &HtmlCommands, tolerant: bool) -> Vec<TokenStream> {
+    commands
+        .commands
+        .iter()
+        .map(|command| convert_command(command, tolerant))
+        .collect()
+}
+
+/// In tolerant templates the `*_tolerant` variant of a `html_handler` method
+/// is used, which skips unexpected attributes and nodes with a warning.
+fn handler_method(name: &str, tolerant: bool, span: Span) -> Ident {
+    if tolerant {
+        Ident::new(&format!("{name}_tolerant"), span)
+    } else {
+        Ident::new(name, span)
+    }
 }
 
 #[allow(clippy::too_many_lines)]
-fn convert_command(command: &HtmlCommand) -> TokenStream {
+fn convert_command(command: &HtmlCommand, tolerant: bool) -> TokenStream {
     match command {
         HtmlCommand::ElementOpen(input) => {
             let tag = input.element.to_string();
@@ -714,38 +756,54 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
                 let value = &iter.value;
                 match value {
                     StringLiteralOrVariable::Literal(lit_str) => {
+                        let attribute = handler_method("attribute", tolerant, lit_str.span());
                         quote_spanned! {lit_str.span()=>
                             #[allow(unused_mut)]
-                            let mut html_handler = html_handler.attribute(#name, #lit_str);
+                            let mut html_handler = html_handler.#attribute(#name, #lit_str)?;
                         }
                     }
                     StringLiteralOrVariable::Clousure(_brace, expr) => {
+                        let attribute_value =
+                            handler_method("attribute_value", tolerant, expr.span());
                         quote_spanned! {expr.span()=>
                             #[allow(unused_mut)]
                             let (mut html_handler, tmp_internal_html_extractor_proc_macro_2) =
-                                html_handler.attribute_value(#name);
+                                html_handler.#attribute_value(#name)?;
                             #[allow(clippy::redundant_closure_call)]
-                            (#expr)(tmp_internal_html_extractor_proc_macro_2);
+                            if !(#expr)(tmp_internal_html_extractor_proc_macro_2.as_str()) {
+                                Err::<(), _>(::html_handler::ParseError::invalid_value(
+                                    concat!("a value for which ", stringify!(#expr)),
+                                    tmp_internal_html_extractor_proc_macro_2,
+                                ))?;
+                            }
                         }
                     }
                     StringLiteralOrVariable::Variable(ident) => {
+                        let attribute_value =
+                            handler_method("attribute_value", tolerant, ident.span());
                         quote_spanned! {ident.span()=>
                             #[allow(unused_mut)]
                             let (mut html_handler, #ident) =
-                                html_handler.attribute_value(#name);
+                                html_handler.#attribute_value(#name)?;
                         }
                     }
                 }
             });
 
+            let next_child_tag_open_start = handler_method(
+                "next_child_tag_open_start",
+                tolerant,
+                input.open_start.span(),
+            );
             let open = quote_spanned! {input.open_start.span()=>
                 #[allow(unused_mut)]
-                let mut html_handler = html_handler.next_child_tag_open_start(#tag);
+                let mut html_handler = html_handler.#next_child_tag_open_start(#tag)?;
             };
 
+            let tag_open_end = handler_method("tag_open_end", tolerant, input.open_end.span());
             let close = quote_spanned! {input.open_end.span()=>
                 #[allow(unused_mut)]
-                let mut html_handler = html_handler.tag_open_end();
+                let mut html_handler = html_handler.#tag_open_end()?;
             };
 
             quote! {
@@ -764,9 +822,14 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
         }
         HtmlCommand::ElementClose(html_element_close) => {
             let name = html_element_close.element.to_string();
+            let close_element = handler_method(
+                "close_element",
+                tolerant,
+                html_element_close.close_start.span(),
+            );
             quote_spanned! {html_element_close.close_start.span()=>
                 #[allow(unused_mut)]
-                let mut html_handler = html_handler.close_element(#name);
+                let mut html_handler = html_handler.#close_element(#name)?;
             }
         }
         HtmlCommand::Comment(_html_comment) => {
@@ -774,24 +837,32 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
         }
         HtmlCommand::Text(html_text) => match html_text {
             StringLiteralOrVariable::Literal(lit_str) => {
+                let skip_text = handler_method("skip_text", tolerant, lit_str.span());
                 quote_spanned! {lit_str.span()=>
                     #[allow(unused_mut)]
-                    let mut html_handler = html_handler.skip_text(#lit_str);
+                    let mut html_handler = html_handler.#skip_text(#lit_str)?;
                 }
             }
             StringLiteralOrVariable::Clousure(_brace, expr) => {
+                let text = handler_method("text", tolerant, expr.span());
                 quote_spanned! {expr.span()=>
                     #[allow(unused_mut)]
                     let (mut html_handler, tmp_internal_html_extractor_proc_macro_2) =
-                        html_handler.text();
+                        html_handler.#text()?;
                     #[allow(clippy::redundant_closure_call)]
-                    (#expr)(tmp_internal_html_extractor_proc_macro_2);
+                    if !(#expr)(tmp_internal_html_extractor_proc_macro_2.as_str()) {
+                        Err::<(), _>(::html_handler::ParseError::invalid_value(
+                            concat!("a text for which ", stringify!(#expr)),
+                            tmp_internal_html_extractor_proc_macro_2,
+                        ))?;
+                    }
                 }
             }
             StringLiteralOrVariable::Variable(ident) => {
+                let text = handler_method("text", tolerant, ident.span());
                 quote_spanned! {ident.span()=>
                     #[allow(unused_mut)]
-                    let (mut html_handler, #ident) = html_handler.text();
+                    let (mut html_handler, #ident) = html_handler.#text()?;
                 }
             }
         },
@@ -830,7 +901,7 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
                     result_expr,
                     else_,
                 }) => {
-                    let body_stmts = convert_commands(body);
+                    let body_stmts = convert_commands(body, tolerant);
                     let temp_var = Ident::new("temp_var", Span::mixed_site());
                     else_.as_ref().map_or_else(
                         || {
@@ -852,7 +923,7 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
                              gt: _,
                              result_expr: else_result_expr,
                          }| {
-                            let else_body_stmts = convert_commands(else_body);
+                            let else_body_stmts = convert_commands(else_body, tolerant);
                             let if_inner = quote_spanned! {brace_token.span.span()
                                                             .join(result_expr.span())
                                                             .unwrap_or_else(|| brace_token.span.span())=>
@@ -885,7 +956,7 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
                 }
                 HtmlLetInner::While(html_while) => {
                     let conditional = &html_while.conditional;
-                    let body = convert_commands(&html_while.body);
+                    let body = convert_commands(&html_while.body, tolerant);
                     let result_expr = &html_while.result_expr;
                     let temp_vec = Ident::new("temp_vec", Span::mixed_site());
                     quote_spanned! {html_while.body.span().unwrap_or_else(|| html_while.brace_token.span.span())=>
@@ -912,9 +983,9 @@ fn convert_command(command: &HtmlCommand) -> TokenStream {
 
 #[proc_macro]
 pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as HtmlCommands);
+    let input = parse_macro_input!(input as HtmlTemplate);
 
-    let expanded = convert_commands(&input);
+    let expanded = convert_commands(&input.commands, input.tolerant);
     let result = quote! {
         #(#expanded)*
     };
@@ -1,7 +1,8 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::iter::Peekable;
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::panic::Location;
 
 use data_encoding::BASE64URL_NOPAD;
 use ego_tree::iter::Edge;
@@ -295,6 +296,190 @@ fn convert_children_inner(mut new_parent: NodeMut<'_, MyNode>, old_node: NodeRef
     });
 }
 
+/// A mismatch between an `html_extractor::html!` template and the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Path of the element from the document root, e.g.
+    /// `html > body > div#wrapper > a:nth-of-type(2)`.
+    pub path: String,
+    pub expected: String,
+    pub found: String,
+    /// The part of the template that did not match.
+    pub template: &'static Location<'static>,
+    /// The HTML around the mismatch.
+    pub snippet: String,
+}
+
+impl ParseError {
+    #[track_caller]
+    #[must_use]
+    pub fn new(
+        node: NodeRef<'_, MyNode>,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path(node),
+            expected: expected.into(),
+            found: found.into(),
+            template: Location::caller(),
+            snippet: snippet(node),
+        }
+    }
+
+    /// For text that was extracted but can't be interpreted, e.g. a number.
+    #[track_caller]
+    #[must_use]
+    pub fn invalid_value(expected: impl Into<String>, found: impl Into<String>) -> Self {
+        let found = found.into();
+        Self {
+            path: String::new(),
+            expected: expected.into(),
+            found: format!("{found:?}"),
+            template: Location::caller(),
+            snippet: found,
+        }
+    }
+
+    /// Shows `node` as the snippet instead of its parent.
+    #[must_use]
+    pub fn near(mut self, node: Option<NodeRef<'_, MyNode>>) -> Self {
+        if let Some(node) = node {
+            self.snippet = snippet(node);
+        }
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} but found {} at {}\n  template: {}\n  html: {}",
+            self.expected, self.found, self.path, self.template, self.snippet
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The path of an element from the document root like a CSS selector.
+#[must_use]
+pub fn path(node: NodeRef<'_, MyNode>) -> String {
+    let mut elements: Vec<_> = node
+        .ancestors()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .chain(std::iter::once(node))
+        .filter_map(|node| Some((node, node.value().as_element()?)))
+        .map(|(node, element)| {
+            if let Some(id) = element.id() {
+                return format!("{}#{id}", element.name());
+            }
+            let same_name = |sibling: &NodeRef<'_, MyNode>| {
+                sibling
+                    .value()
+                    .as_element()
+                    .is_some_and(|sibling| sibling.name() == element.name())
+            };
+            if node.next_siblings().any(|sibling| same_name(&sibling))
+                || node.prev_siblings().any(|sibling| same_name(&sibling))
+            {
+                let index = node.prev_siblings().filter(same_name).count() + 1;
+                format!("{}:nth-of-type({index})", element.name())
+            } else {
+                element.name().to_owned()
+            }
+        })
+        .collect();
+    if elements.is_empty() {
+        elements.push("document".to_owned());
+    }
+    elements.join(" > ")
+}
+
+/// A short description of a node for error messages.
+#[must_use]
+pub fn describe(node: Option<NodeRef<'_, MyNode>>) -> String {
+    let Some(node) = node else {
+        return "nothing".to_owned();
+    };
+    match node.value() {
+        MyNode::Document => "document".to_owned(),
+        MyNode::Fragment => "fragment".to_owned(),
+        MyNode::Doctype(_) => "doctype".to_owned(),
+        MyNode::Text(text) => format!("text {:?}", &**text),
+        MyNode::Element(element) => {
+            let attributes: String = element
+                .attrs()
+                .map(|(name, value)| format!(" {name}={value:?}"))
+                .collect();
+            format!("<{}{attributes}>", element.name())
+        }
+        MyNode::ProcessingInstruction(_) => "processing instruction".to_owned(),
+    }
+}
+
+/// The HTML of a node, shortened for error messages.
+#[must_use]
+pub fn snippet(node: NodeRef<'_, MyNode>) -> String {
+    const MAX_CHARS: usize = 300;
+    let html = match node.value() {
+        MyNode::Text(text) => text.to_string(),
+        MyNode::Element(_) => MyElementRef::wrap(node).unwrap().html(),
+        _ => describe(Some(node)),
+    };
+    match html.char_indices().nth(MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &html[..index]),
+        None => html,
+    }
+}
+
+fn describe_attribute(attribute: Option<(&str, &str)>) -> String {
+    attribute.map_or_else(
+        || "no more attributes".to_owned(),
+        |(name, value)| format!("attribute {name}={value:?}"),
+    )
+}
+
+/// Whether the `*_tolerant` methods skip unexpected nodes and attributes.
+/// Disabled by the `strict` feature so tests still notice changes in TUCaN,
+/// run them with `--features html-handler/strict`.
+const TOLERANT: bool = !cfg!(feature = "strict");
+
+/// Skips `<br>`s and text in front of the next expected element. Whitespace
+/// only text is already removed by [`parse_document`].
+fn skip_line_breaks_and_text(
+    mut current_child: Option<NodeRef<'_, MyNode>>,
+) -> Option<NodeRef<'_, MyNode>> {
+    while let Some(child_node) = current_child.filter(|child_node| match child_node.value() {
+        MyNode::Element(element) => element.name() == "br",
+        MyNode::Text(_) => true,
+        _ => false,
+    }) {
+        log::warn!(
+            "skipping unexpected {} at {}",
+            describe(Some(child_node)),
+            path(child_node)
+        );
+        current_child = child_node.next_sibling();
+    }
+    current_child
+}
+
+/// Skips the remaining siblings in front of a closing tag.
+fn skip_trailing_siblings(mut current_child: Option<NodeRef<'_, MyNode>>) {
+    while let Some(child_node) = current_child {
+        log::warn!(
+            "skipping unexpected {} at {}",
+            describe(Some(child_node)),
+            path(child_node)
+        );
+        current_child = child_node.next_sibling();
+    }
+}
+
 pub struct Root<'a> {
     node: NodeRef<'a, MyNode>,
 }
@@ -350,26 +535,34 @@ impl<'a> InRoot<'a, Root<'a>> {
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn doctype(self) -> Self {
-        let child_node = self.current_child.expect("expected child but none left");
-        let Some(_child_element) = child_node.value().as_doctype() else {
-            panic!("expected doctype but got {:?}", child_node.value())
+    pub fn doctype(self) -> Result<Self, ParseError> {
+        let Some(child_node) = self
+            .current_child
+            .filter(|child_node| child_node.value().is_doctype())
+        else {
+            return Err(
+                ParseError::new(self.node, "doctype", describe(self.current_child))
+                    .near(self.current_child),
+            );
         };
-        InRoot {
+        Ok(InRoot {
             node: self.node,
             current_child: child_node.next_sibling(),
             outer_state: self.outer_state,
-        }
+        })
     }
 
     #[track_caller]
-    pub fn end_document(self) {
-        assert_eq!(
-            self.current_child, None,
-            "Expected no remaining children but got {:?}",
-            self.current_child
-        );
+    pub fn end_document(self) -> Result<(), ParseError> {
+        if self.current_child.is_some() {
+            return Err(ParseError::new(
+                self.node,
+                "end of document",
+                describe(self.current_child),
+            )
+            .near(self.current_child));
+        }
+        Ok(())
     }
 }
 
@@ -380,30 +573,41 @@ impl<'a, OuterState> InRoot<'a, OuterState> {
         self
     }
 
-    #[must_use]
-    pub fn next_child_tag_open_start(self, name: &str) -> Open<'a, Self> {
-        let child_node = self.current_child.expect("expected child but none left");
-        let Some(child_element) = child_node.value().as_element() else {
-            panic!("expected element but got {:?}", child_node.value())
+    #[track_caller]
+    pub fn next_child_tag_open_start(self, name: &str) -> Result<Open<'a, Self>, ParseError> {
+        let Some((child_node, child_element)) = self.current_child.and_then(|child_node| {
+            Some((child_node, child_node.value().as_element()?))
+                .filter(|(_, child_element)| child_element.name() == name)
+        }) else {
+            return Err(ParseError::new(
+                self.node,
+                format!("<{name}>"),
+                describe(self.current_child),
+            )
+            .near(self.current_child));
         };
-        assert_eq!(
-            child_element.name(),
-            name,
-            "{}",
-            MyElementRef::wrap(child_node).unwrap().html()
-        );
-        Open {
+        Ok(Open {
             element: child_node,
             attrs: child_element.attrs().peekable(),
             outer_state: PhantomData,
+        })
+    }
+
+    #[track_caller]
+    pub fn next_child_tag_open_start_tolerant(
+        mut self,
+        name: &str,
+    ) -> Result<Open<'a, Self>, ParseError> {
+        if TOLERANT && name != "br" {
+            self.current_child = skip_line_breaks_and_text(self.current_child);
         }
+        self.next_child_tag_open_start(name)
     }
 }
 
 impl<'a, OuterState> Open<'a, OuterState> {
     #[track_caller]
-    #[must_use]
-    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+    pub fn attribute(mut self, name: &str, value: &str) -> Result<Self, ParseError> {
         if name == "xss" {
             while let Some((attr_key, _attr_value)) = self.attrs.peek() {
                 if *attr_key == value {
@@ -411,48 +615,94 @@ impl<'a, OuterState> Open<'a, OuterState> {
                 }
                 self.attrs.next().unwrap();
             }
-            return self;
+            return Ok(self);
         }
-        assert_eq!(
-            self.attrs.next().expect("expected attribute but none left"),
-            (name, value),
-            "{}",
-            MyElementRef::wrap(self.element).unwrap().html()
-        );
-        self
+        let attribute = self.attrs.next();
+        if attribute != Some((name, value)) {
+            return Err(ParseError::new(
+                self.element,
+                format!("attribute {name}={value:?}"),
+                describe_attribute(attribute),
+            ));
+        }
+        Ok(self)
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn attribute_value(mut self, expected_name: &str) -> (Self, String) {
-        let (name, value) = self.attrs.next().expect("expected attribute but none left");
-        assert_eq!(
-            name,
-            expected_name,
-            "{}",
-            MyElementRef::wrap(self.element).unwrap().html()
-        );
-        (self, value.to_owned())
+    pub fn attribute_value(mut self, expected_name: &str) -> Result<(Self, String), ParseError> {
+        let attribute = self.attrs.next();
+        let Some((_name, value)) = attribute.filter(|(name, _value)| *name == expected_name) else {
+            return Err(ParseError::new(
+                self.element,
+                format!("attribute {expected_name}"),
+                describe_attribute(attribute),
+            ));
+        };
+        Ok((self, value.to_owned()))
+    }
+
+    /// Skips unknown attributes in front of `name`.
+    fn skip_attributes_until(&mut self, name: &str) {
+        if !self.attrs.clone().any(|(attr_name, _)| attr_name == name) {
+            return;
+        }
+        while let Some((attr_name, attr_value)) =
+            self.attrs.next_if(|(attr_name, _)| *attr_name != name)
+        {
+            log::warn!(
+                "skipping unexpected attribute {attr_name}={attr_value:?} at {}",
+                path(self.element)
+            );
+        }
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn tag_open_end(mut self) -> InElement<'a, OuterState> {
-        let Some(_child_element) = self.element.value().as_element() else {
-            panic!("expected element but got {:?}", self.element.value())
-        };
-        let attr = self.attrs.next();
-        assert_eq!(
-            attr,
-            None,
-            "expected no remaining attributes but got {attr:?} in {}",
-            MyElementRef::wrap(self.element).unwrap().html()
-        );
-        InElement {
+    pub fn attribute_tolerant(mut self, name: &str, value: &str) -> Result<Self, ParseError> {
+        if TOLERANT && name != "xss" {
+            self.skip_attributes_until(name);
+        }
+        self.attribute(name, value)
+    }
+
+    #[track_caller]
+    pub fn attribute_value_tolerant(
+        mut self,
+        expected_name: &str,
+    ) -> Result<(Self, String), ParseError> {
+        if TOLERANT {
+            self.skip_attributes_until(expected_name);
+        }
+        self.attribute_value(expected_name)
+    }
+
+    #[track_caller]
+    pub fn tag_open_end(mut self) -> Result<InElement<'a, OuterState>, ParseError> {
+        let attribute = self.attrs.next();
+        if attribute.is_some() {
+            return Err(ParseError::new(
+                self.element,
+                "no more attributes",
+                describe_attribute(attribute),
+            ));
+        }
+        Ok(InElement {
             element: self.element,
             current_child: self.element.children().next(),
             outer_state: self.outer_state,
+        })
+    }
+
+    #[track_caller]
+    pub fn tag_open_end_tolerant(mut self) -> Result<InElement<'a, OuterState>, ParseError> {
+        if TOLERANT {
+            for (attr_name, attr_value) in self.attrs.by_ref() {
+                log::warn!(
+                    "skipping unexpected attribute {attr_name}={attr_value:?} at {}",
+                    path(self.element)
+                );
+            }
         }
+        self.tag_open_end()
     }
 }
 
@@ -462,12 +712,13 @@ impl<'a, OuterState> InElement<'a, OuterState> {
         self.current_child.as_ref()
     }
 
-    #[must_use]
     #[track_caller]
-    pub fn next_any_child(mut self) -> (Self, NodeRef<'a, MyNode>) {
-        let current_child = self.current_child.expect("expected child but none left");
+    pub fn next_any_child(mut self) -> Result<(Self, NodeRef<'a, MyNode>), ParseError> {
+        let Some(current_child) = self.current_child else {
+            return Err(ParseError::new(self.element, "any child", "nothing"));
+        };
         self.current_child = current_child.next_sibling();
-        (self, current_child)
+        Ok((self, current_child))
     }
 
     #[track_caller]
@@ -477,43 +728,88 @@ impl<'a, OuterState> InElement<'a, OuterState> {
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn text(mut self) -> (Self, String) {
-        let child_node = self
+    pub fn text(mut self) -> Result<(Self, String), ParseError> {
+        let Some((child_node, text)) = self
             .current_child
-            .expect("expected child with text but got no children. maybe there is a closing tag?");
-        let Some(child_element) = child_node.value().as_text() else {
-            panic!("expected text but got {:?}", child_node.value())
+            .and_then(|child_node| Some((child_node, child_node.value().as_text()?)))
+        else {
+            return Err(
+                ParseError::new(self.element, "text", describe(self.current_child))
+                    .near(self.current_child),
+            );
         };
         self.current_child = child_node.next_sibling();
-        (self, child_element.to_string())
+        Ok((self, text.to_string()))
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn skip_text(mut self, text: &str) -> Self {
-        let child_node = self
+    pub fn skip_text(mut self, text: &str) -> Result<Self, ParseError> {
+        let Some((child_node, child_text)) = self
             .current_child
-            .expect("expected child with text but got no children. maybe there is a closing tag?");
-        let Some(child_element) = child_node.value().as_text() else {
-            panic!("expected text but got {:?}", child_node.value())
+            .and_then(|child_node| Some((child_node, child_node.value().as_text()?)))
+        else {
+            return Err(ParseError::new(
+                self.element,
+                format!("text {text:?}"),
+                describe(self.current_child),
+            )
+            .near(self.current_child));
         };
-        match BASE64URL_NOPAD.decode(text.as_bytes()) {
+        let matches = match BASE64URL_NOPAD.decode(text.as_bytes()) {
             Ok(value) if value.len() == 32 => {
-                let actual_hash = BASE64URL_NOPAD.encode(&Sha3_256::digest(&**child_element));
-                assert_eq!(actual_hash, text);
-            }
-            _ => {
-                assert_eq!(
-                    &**child_element,
-                    text,
-                    "{}",
-                    BASE64URL_NOPAD.encode(&Sha3_256::digest(&**child_element))
-                );
+                BASE64URL_NOPAD.encode(&Sha3_256::digest(&**child_text)) == text
             }
+            _ => &**child_text == text,
+        };
+        if !matches {
+            return Err(ParseError::new(
+                self.element,
+                format!("text {text:?}"),
+                format!(
+                    "text {:?} with hash {}",
+                    &**child_text,
+                    BASE64URL_NOPAD.encode(&Sha3_256::digest(&**child_text))
+                ),
+            ));
         }
         self.current_child = child_node.next_sibling();
-        self
+        Ok(self)
+    }
+
+    /// Returns an empty string instead of failing if there is no text, e.g.
+    /// for a cell that became empty.
+    #[track_caller]
+    pub fn text_tolerant(self) -> Result<(Self, String), ParseError> {
+        let is_text = self
+            .current_child
+            .is_some_and(|child_node| child_node.value().is_text());
+        if TOLERANT && !is_text {
+            log::warn!(
+                "expected text but found {} in {}",
+                describe(self.current_child),
+                path(self.element)
+            );
+            return Ok((self, String::new()));
+        }
+        self.text()
+    }
+
+    /// Ignores differences in whitespace like line breaks within the text.
+    #[track_caller]
+    pub fn skip_text_tolerant(self, text: &str) -> Result<Self, ParseError> {
+        let normalized = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let child_text = self
+            .current_child
+            .and_then(|child_node| child_node.value().as_text());
+        match child_text {
+            Some(child_text) if TOLERANT && normalized(child_text) == normalized(text) => {
+                Ok(InElement {
+                    current_child: self.current_child.and_then(|node| node.next_sibling()),
+                    ..self
+                })
+            }
+            _ => self.skip_text(text),
+        }
     }
 
     #[track_caller]
@@ -523,85 +819,189 @@ impl<'a, OuterState> InElement<'a, OuterState> {
     }
 
     #[track_caller]
-    #[must_use]
-    pub fn next_child_tag_open_start(self, name: &str) -> Open<'a, Self> {
-        let Some(_child_element) = self.element.value().as_element() else {
-            panic!("expected element but got {:?}", self.element.value())
-        };
-        let Some(child_node) = self.current_child else {
-            panic!(
-                "expected one more child in {}",
-                MyElementRef::wrap(self.element).unwrap().html()
+    pub fn next_child_tag_open_start(self, name: &str) -> Result<Open<'a, Self>, ParseError> {
+        let Some((child_node, child_element)) = self.current_child.and_then(|child_node| {
+            Some((child_node, child_node.value().as_element()?))
+                .filter(|(_, child_element)| child_element.name() == name)
+        }) else {
+            return Err(ParseError::new(
+                self.element,
+                format!("<{name}>"),
+                describe(self.current_child),
             )
+            .near(self.current_child));
         };
-        let Some(child_element) = child_node.value().as_element() else {
-            panic!("expected element but got {:?}", child_node.value())
-        };
-        assert_eq!(
-            child_element.name(),
-            name,
-            "{}",
-            MyElementRef::wrap(child_node).unwrap().html()
-        );
-        Open {
+        Ok(Open {
             element: child_node,
             attrs: child_element.attrs().peekable(),
             outer_state: PhantomData,
+        })
+    }
+
+    #[track_caller]
+    pub fn next_child_tag_open_start_tolerant(
+        mut self,
+        name: &str,
+    ) -> Result<Open<'a, Self>, ParseError> {
+        if TOLERANT && name != "br" {
+            self.current_child = skip_line_breaks_and_text(self.current_child);
+        }
+        self.next_child_tag_open_start(name)
+    }
+
+    #[track_caller]
+    fn check_close(&self, name: &str) -> Result<(), ParseError> {
+        if self.current_child.is_some() {
+            return Err(ParseError::new(
+                self.element,
+                format!("</{name}>"),
+                describe(self.current_child),
+            )
+            .near(self.current_child));
         }
+        let is_expected_element = self
+            .element
+            .value()
+            .as_element()
+            .is_some_and(|element| element.name() == name);
+        if !is_expected_element {
+            return Err(ParseError::new(
+                self.element,
+                format!("</{name}>"),
+                describe(Some(self.element)),
+            ));
+        }
+        Ok(())
     }
 }
 
 impl<'a, OuterState> InElement<'a, InElement<'a, OuterState>> {
     #[track_caller]
-    #[must_use]
-    pub fn close_element(self, name: &str) -> InElement<'a, OuterState> {
-        assert_eq!(
-            self.current_child.map(|child| child.value()),
-            None,
-            "expected there to be no more children"
-        );
-        let Some(element) = self.element.value().as_element() else {
-            panic!("expected element but got {:?}", self.element.value())
-        };
-        assert_eq!(
-            element.name(),
-            name,
-            "Expected tag to be {name} but got {} for {}",
-            element.name(),
-            MyElementRef::wrap(self.element).unwrap().html()
-        );
-        InElement {
+    pub fn close_element(self, name: &str) -> Result<InElement<'a, OuterState>, ParseError> {
+        self.check_close(name)?;
+        Ok(InElement {
             element: self.element.parent().unwrap(),
             current_child: self.element.next_sibling(),
             outer_state: PhantomData,
+        })
+    }
+
+    #[track_caller]
+    pub fn close_element_tolerant(
+        mut self,
+        name: &str,
+    ) -> Result<InElement<'a, OuterState>, ParseError> {
+        if TOLERANT {
+            skip_trailing_siblings(self.current_child.take());
         }
+        self.close_element(name)
     }
 }
 
 impl<'a, OuterState> InElement<'a, InRoot<'a, OuterState>> {
     #[track_caller]
-    #[must_use]
-    pub fn close_element(self, name: &str) -> InRoot<'a, OuterState> {
-        assert_eq!(
-            self.current_child
-                .map(|child| MyElementRef::wrap(child).unwrap().html()),
-            None,
-            "expected there to be no more children"
-        );
-        let Some(element) = self.element.value().as_element() else {
-            panic!("expected element but got {:?}", self.element.value())
-        };
-        assert_eq!(
-            element.name(),
-            name,
-            "Expected tag to be {name} but got {} for {}",
-            element.name(),
-            MyElementRef::wrap(self.element).unwrap().html()
-        );
-        InRoot {
+    pub fn close_element(self, name: &str) -> Result<InRoot<'a, OuterState>, ParseError> {
+        self.check_close(name)?;
+        Ok(InRoot {
             node: self.element.parent().unwrap(),
             current_child: self.element.next_sibling(),
             outer_state: PhantomData,
+        })
+    }
+
+    #[track_caller]
+    pub fn close_element_tolerant(
+        mut self,
+        name: &str,
+    ) -> Result<InRoot<'a, OuterState>, ParseError> {
+        if TOLERANT {
+            skip_trailing_siblings(self.current_child.take());
         }
+        self.close_element(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseError, Root, TOLERANT, parse_document};
+
+    fn parse_link(content: &str, tolerant: bool) -> Result<String, ParseError> {
+        let document = parse_document(content);
+        let html_handler = Root::new(document.root()).document_start().doctype()?;
+        let html_handler = html_handler
+            .next_child_tag_open_start("html")?
+            .tag_open_end()?;
+        let html_handler = html_handler
+            .next_child_tag_open_start("head")?
+            .tag_open_end()?
+            .close_element("head")?;
+        let html_handler = html_handler
+            .next_child_tag_open_start("body")?
+            .tag_open_end()?;
+        let (html_handler, href) = if tolerant {
+            let (html_handler, href) = html_handler
+                .next_child_tag_open_start_tolerant("a")?
+                .attribute_value_tolerant("href")?;
+            let html_handler = html_handler
+                .tag_open_end_tolerant()?
+                .close_element_tolerant("a")?
+                .close_element_tolerant("body")?;
+            (html_handler, href)
+        } else {
+            let (html_handler, href) = html_handler
+                .next_child_tag_open_start("a")?
+                .attribute_value("href")?;
+            let html_handler = html_handler
+                .tag_open_end()?
+                .close_element("a")?
+                .close_element("body")?;
+            (html_handler, href)
+        };
+        html_handler.close_element("html")?.end_document()?;
+        Ok(href)
+    }
+
+    #[test]
+    fn tolerant_skips_unexpected_nodes() {
+        let content = r#"<!DOCTYPE html><html><head></head><body><br>new<a class="new" href="/x" title="new">x</a><p>new</p></body></html>"#;
+        let error = parse_link(content, false).unwrap_err();
+        assert_eq!(error.expected, "<a>");
+        assert_eq!(error.path, "html > body");
+        assert_eq!(
+            parse_link(content, true).ok(),
+            TOLERANT.then(|| "/x".to_owned())
+        );
+    }
+
+    #[test]
+    fn tolerant_text_ignores_whitespace_and_missing_text() {
+        let content = "<!DOCTYPE html><html><head></head><body><p>Sprech-\n    stunde</p><p></p></body></html>";
+        let document = parse_document(content);
+        let parse = || -> Result<String, ParseError> {
+            let html_handler = Root::new(document.root()).document_start().doctype()?;
+            let html_handler = html_handler
+                .next_child_tag_open_start("html")?
+                .tag_open_end()?
+                .next_child_tag_open_start("head")?
+                .tag_open_end()?
+                .close_element("head")?
+                .next_child_tag_open_start("body")?
+                .tag_open_end()?
+                .next_child_tag_open_start("p")?
+                .tag_open_end()?
+                .skip_text_tolerant("Sprech- stunde")?
+                .close_element("p")?;
+            let (html_handler, text) = html_handler
+                .next_child_tag_open_start("p")?
+                .tag_open_end()?
+                .text_tolerant()?;
+            html_handler
+                .close_element("p")?
+                .close_element("body")?
+                .close_element("html")?
+                .end_document()?;
+            Ok(text)
+        };
+        assert_eq!(parse().ok(), TOLERANT.then(String::new));
     }
 }
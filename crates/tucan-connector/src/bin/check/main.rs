@@ -0,0 +1,45 @@
+//! Checks saved pages against the templates, e.g.
+//! `cargo run --bin check -- test-data/fixtures/unparsed_mlsstart.html`.
+//! Pages are named after their cache key like the ones written in record mode.
+//!
+//! This runs after the build instead of reporting mismatches as compile errors
+//! of the `html!` macro, as the macro only sees the template and not which
+//! parser a page belongs to. Mismatches show the template location and the
+//! HTML around them.
+
+use std::{path::Path, process::ExitCode};
+
+use tucan_connector::snapshot::{SCRUBBED_SESSION_ID, parse_page};
+use tucan_types::LoginResponse;
+
+pub fn main() -> ExitCode {
+    let login_response = LoginResponse {
+        id: SCRUBBED_SESSION_ID,
+        cookie_cnsc: String::new(),
+    };
+    let mut result = ExitCode::SUCCESS;
+    for path in std::env::args().skip(1) {
+        let path = Path::new(&path);
+        let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            println!("{}: not a page named after its cache key", path.display());
+            result = ExitCode::FAILURE;
+            continue;
+        };
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("{}: {err}", path.display());
+                result = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        match parse_page(&login_response, key, &content) {
+            Ok(_) => println!("{key}: ok"),
+            Err(err) => {
+                println!("{}", err.in_page(key));
+                result = ExitCode::FAILURE;
+            }
+        }
+    }
+    result
+}
@@ -1,47 +1,204 @@
-use std::fs;
+//! Generates a `html_extractor::html!` skeleton for a saved page:
+//!
+//! `cargo run --bin converter -- input.html` prints the skeleton and
+//! `cargo run --bin converter -- input.html --diff src/month.rs` compares it
+//! with the `html!` blocks of an existing parser.
+
+use std::{fs, process::ExitCode, sync::LazyLock};
 
 use ego_tree::NodeRef;
 use html_handler::{MyNode, parse_document};
 use itertools::Itertools;
+use regex::Regex;
+use tucan_connector::h;
+
+#[derive(Default)]
+struct Generator {
+    texts: usize,
+    hrefs: usize,
+}
+
+impl Generator {
+    fn line(output: &mut String, depth: usize, line: &str) {
+        output.push_str(&" ".repeat(depth));
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    fn generate(&mut self, output: &mut String, node: NodeRef<MyNode>, depth: usize) {
+        match node.value() {
+            // `html_handler.doctype()?` is called before the `html!` block
+            MyNode::Doctype(_) | MyNode::ProcessingInstruction(_) => {}
+            MyNode::Document | MyNode::Fragment => {
+                for child in node.children() {
+                    self.generate(output, child, depth);
+                }
+            }
+            MyNode::Text(text) => {
+                let is_script = node.parent().is_some_and(|parent| {
+                    parent
+                        .value()
+                        .as_element()
+                        .is_some_and(|element| ["script", "style"].contains(&element.name()))
+                });
+                if is_script {
+                    Self::line(output, depth, &format!("{:?}", h(text)));
+                } else {
+                    self.texts += 1;
+                    Self::line(output, depth, &format!("_text_{}", self.texts));
+                }
+            }
+            MyNode::Element(element) => {
+                let attributes = element
+                    .attrs()
+                    .map(|(name, value)| {
+                        if name == "href" {
+                            self.hrefs += 1;
+                            format!(" href=_href_{}", self.hrefs)
+                        } else {
+                            format!(" {name}={value:?}")
+                        }
+                    })
+                    .join("");
+                Self::line(output, depth, &format!("<{}{attributes}>", element.name()));
+                for child in node.children() {
+                    self.generate(output, child, depth + 4);
+                }
+                Self::line(output, depth, &format!("</{}>", element.name()));
+            }
+        }
+    }
+}
 
 #[must_use]
-pub fn to_string(node: NodeRef<MyNode>, depth: usize) -> String {
-    match node.value() {
-        MyNode::Document => node.children().map(|child| to_string(child, 0)).join(""),
-        MyNode::Fragment => todo!(),
-        MyNode::Doctype(_doctype) => "<!doctype html>".to_owned(),
-        MyNode::Text(text) => {
-            "\"".to_owned()
-                + &text
-                    .replace('\n', "\\n")
-                    .replace('\t', "\\t")
-                    .replace('"', "\\\"")
-                + "\""
+pub fn skeleton(content: &str) -> String {
+    let document = parse_document(content);
+    let mut output = "html_extractor::html! {\n".to_owned();
+    Generator::default().generate(&mut output, document.root(), 4);
+    output.push_str("};\n");
+    output
+}
+
+/// The `html!` blocks of a parser and the lines they start at.
+fn html_blocks(source: &str) -> Result<Vec<(usize, &str)>, String> {
+    let mut blocks = Vec::new();
+    let mut rest = 0;
+    while let Some(offset) = source[rest..].find("html_extractor::html! {") {
+        let start = rest + offset;
+        let mut depth = 0;
+        let end = source[start..]
+            .char_indices()
+            .find_map(|(index, char)| {
+                match char {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => return None,
+                }
+                (depth == 0).then_some(start + index)
+            })
+            .ok_or_else(|| {
+                let line = source[..start].lines().count() + 1;
+                format!("unterminated html! block at line {line}")
+            })?;
+        let first_line = source[..start].lines().count() + 1;
+        blocks.push((first_line, &source[start..=end]));
+        rest = end + 1;
+    }
+    if blocks.is_empty() {
+        return Err("no html! block found".to_owned());
+    }
+    Ok(blocks)
+}
+
+/// Only keeps the structure, so bindings and attribute values don't show up
+/// in the diff.
+fn normalize(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.starts_with("</") {
+        return Some(line.to_owned());
+    }
+    if let Some(tag) = line.strip_prefix('<') {
+        static ATTRIBUTE_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"\s([\w:-]+)=").unwrap());
+        let name = tag.split([' ', '>']).next().unwrap_or_default();
+        let attributes = ATTRIBUTE_REGEX
+            .captures_iter(tag)
+            .map(|captures| format!(" {}", &captures[1]))
+            .join("");
+        return Some(format!("<{name}{attributes}>"));
+    }
+    if line.starts_with('"') || line.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Some("text".to_owned());
+    }
+    Some(line.to_owned())
+}
+
+/// The normalized lines with their line numbers.
+fn normalized_lines(first_line: usize, source: &str) -> Vec<(usize, String)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| Some((first_line + index, normalize(line)?)))
+        .collect()
+}
+
+fn diff(old: &[(usize, String)], new: &[(usize, String)]) {
+    // longest common subsequence of the lines
+    let mut lengths = vec![vec![0_u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i].1 == new[j].1 {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
         }
-        MyNode::Element(element) => {
-            "\n".to_owned()
-                + &" ".repeat(depth)
-                + "<"
-                + element.name()
-                + &element
-                    .attrs
-                    .iter()
-                    .map(|(key, value)| format!(" {}=\"{}\"", key.local, value))
-                    .join("")
-                + ">"
-                + &node
-                    .children()
-                    .map(|child| to_string(child, depth + 2))
-                    .join("")
-                + "</"
-                + element.name()
-                + ">"
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].1 == new[j].1 {
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            println!("+{:5} {}", new[j].0, new[j].1);
+            j += 1;
+        } else {
+            println!("-{:5} {}", old[i].0, old[i].1);
+            i += 1;
         }
-        MyNode::ProcessingInstruction(_processing_instruction) => todo!(),
     }
 }
 
-pub fn main() {
-    let document = parse_document(&fs::read_to_string("input.html").unwrap());
-    println!("{}", to_string(document.root(), 0));
+fn run(args: &[String]) -> Result<(), String> {
+    let input = args.first().map_or("input.html", String::as_str);
+    let content = fs::read_to_string(input).map_err(|err| format!("{input}: {err}"))?;
+    let skeleton = skeleton(&content);
+    match args.get(1).map(String::as_str) {
+        None => print!("{skeleton}"),
+        Some("--diff") => {
+            let path = args.get(2).ok_or("missing parser file")?;
+            let parser = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+            let old = html_blocks(&parser)?
+                .into_iter()
+                .flat_map(|(first_line, html_block)| normalized_lines(first_line, html_block))
+                .collect::<Vec<_>>();
+            diff(&old, &normalized_lines(1, &skeleton));
+        }
+        Some(arg) => return Err(format!("unknown argument {arg}")),
+    }
+    Ok(())
+}
+
+pub fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,49 @@
+//! Exports the courses and exams of a semester as iCalendar, e.g.
+//! `cargo run --bin ical -- 000000015166000 > schedule.ics`.
+//! Without a semester the current one is exported.
+
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_connector::{TucanConnector, ical::export_semester};
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_plus_worker::MyDatabase;
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_types::{LoginRequest, RevalidationStrategy, SemesterId, Tucan, TucanError};
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<(), TucanError> {
+    dotenvy::dotenv().unwrap();
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async_main())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn async_main() -> Result<(), TucanError> {
+    let login_request = LoginRequest {
+        username: std::env::var("TUCAN_USERNAME").expect("env variable TUCAN_USERNAME missing"),
+        password: std::env::var("TUCAN_PASSWORD").expect("env variable TUCAN_PASSWORD missing"),
+    };
+    let semester = std::env::args()
+        .nth(1)
+        .map_or_else(SemesterId::current, |semester| semester.parse().unwrap());
+
+    let tucan = TucanConnector::new(MyDatabase::wait_for_worker())
+        .await?
+        .with_credentials(login_request.clone());
+    let login_response = tucan.login(login_request).await?;
+
+    let calendar = export_semester(
+        &tucan,
+        &login_response,
+        RevalidationStrategy::default(),
+        semester,
+    )
+    .await?;
+    print!("{calendar}");
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn main() {}
@@ -39,20 +39,21 @@ fn main() -> Result<(), TucanError> {
 
 #[cfg(not(target_arch = "wasm32"))]
 async fn async_main() -> Result<(), TucanError> {
-    let tucan = TucanConnector::new(MyDatabase::wait_for_worker()).await?;
+    let login_request = LoginRequest {
+        username: std::env::var("TUCAN_USERNAME").expect("env variable TUCAN_USERNAME missing"),
+        password: std::env::var("TUCAN_PASSWORD").expect("env variable TUCAN_PASSWORD missing"),
+    };
+
+    let tucan = TucanConnector::new(MyDatabase::wait_for_worker())
+        .await?
+        .with_credentials(login_request.clone());
 
     /*let login_response = LoginResponse {
         id: std::env::var("SESSION_ID").unwrap().parse().unwrap(),
         cookie_cnsc: std::env::var("SESSION_KEY").unwrap(),
     };*/
 
-    let login_response = tucan
-        .login(LoginRequest {
-            username: std::env::var("TUCAN_USERNAME").expect("env variable TUCAN_USERNAME missing"),
-            password: std::env::var("TUCAN_PASSWORD").expect("env variable TUCAN_PASSWORD missing"),
-        })
-        .await
-        .unwrap();
+    let login_response = tucan.login(login_request).await.unwrap();
 
     let fetcher = Arc::new(Fetcher::new());
 
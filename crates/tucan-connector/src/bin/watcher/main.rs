@@ -0,0 +1,56 @@
+//! Registers for courses as soon as their registration opens, e.g.
+//! `cargo run --bin watcher -- watch.json` with a JSON list of
+//! [`WatchRequest`]s.
+
+#[cfg(not(target_arch = "wasm32"))]
+use futures_util::StreamExt as _;
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_connector::{
+    TucanConnector,
+    watcher::{WatchRequest, watch_all},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_plus_worker::MyDatabase;
+#[cfg(not(target_arch = "wasm32"))]
+use tucan_types::{LoginRequest, Tucan, TucanError};
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<(), TucanError> {
+    dotenvy::dotenv().unwrap();
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async_main())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn async_main() -> Result<(), TucanError> {
+    let login_request = LoginRequest {
+        username: std::env::var("TUCAN_USERNAME").expect("env variable TUCAN_USERNAME missing"),
+        password: std::env::var("TUCAN_PASSWORD").expect("env variable TUCAN_PASSWORD missing"),
+    };
+    let path = std::env::args().nth(1).expect("missing watch file");
+    let requests: Vec<WatchRequest> = match serde_json::from_str(&std::fs::read_to_string(&path)?) {
+        Ok(requests) => requests,
+        Err(error) => {
+            eprintln!("invalid watch file {path}: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    // the session is renewed automatically while waiting
+    let tucan = TucanConnector::new(MyDatabase::wait_for_worker())
+        .await?
+        .with_credentials(login_request.clone());
+    let login_response = tucan.login(login_request).await?;
+
+    let mut outcomes = watch_all(&tucan, &login_response, requests);
+    while let Some((request, outcome)) = outcomes.next().await {
+        println!("{}: {outcome:?}", request.course.inner());
+    }
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn main() {}
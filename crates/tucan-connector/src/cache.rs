@@ -0,0 +1,291 @@
+//! Pages are stored brotli compressed and deduplicated by their hash, see
+//! [`h`]. The parsed results are stored too, so cached pages do not need to be
+//! parsed again.
+
+use async_compression::{
+    Level,
+    tokio::write::{BrotliDecoder, BrotliEncoder},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt as _;
+use tucan_plus_worker::{
+    CacheContentRequest, CacheRequest, MyDatabase, ParsedCacheRequest, ReplaceCacheContentRequest,
+    StoreCacheRequest, StoreParsedCacheRequest, UncompressedCacheRequest,
+    cachekey::CacheKey,
+    models::{CacheContent, CacheEntry, ParsedCacheEntry},
+};
+use tucan_types::TucanError;
+
+use crate::{TucanConnector, h};
+
+/// Bump when a parser or a response type changes, so the stored results are
+/// parsed again from the cached pages. A test fails when a response type
+/// changes, but it can not notice a parser that reads the same page
+/// differently. Such a change needs a bump by hand, which repeats the last
+/// hash in the test.
+pub const PARSER_VERSION: i32 = 1;
+
+/// The entry of the page cached under `key`. Its content is only read when
+/// there is no result parsed from it, see [`parse_cached`].
+pub(crate) async fn read_cache(tucan: &TucanConnector, key: CacheKey) -> Option<CacheEntry> {
+    tucan.database.send_message(CacheRequest { key }).await
+}
+
+/// A page that can not be decompressed is treated as not cached.
+async fn read_content(tucan: &TucanConnector, entry: &CacheEntry) -> Option<String> {
+    let content = tucan
+        .database
+        .send_message(CacheContentRequest {
+            hash: entry.hash.clone(),
+        })
+        .await?;
+    let value = if content.compressed {
+        decompress(&content.value).await.ok()?
+    } else {
+        content.value
+    };
+    String::from_utf8(value).ok()
+}
+
+/// Only compresses `content` if it differs from the `old` page. `hash` is the
+/// one of `content`, see [`h`].
+pub(crate) async fn write_cache(
+    tucan: &TucanConnector,
+    key: CacheKey,
+    old: Option<&CacheEntry>,
+    hash: &str,
+    content: &str,
+    updated: OffsetDateTime,
+) {
+    let content = if old.is_some_and(|old| old.hash == hash) {
+        None
+    } else {
+        Some(CacheContent {
+            hash: hash.to_owned(),
+            // writing into memory can not fail
+            value: compress(content.as_bytes()).await.unwrap(),
+            compressed: true,
+        })
+    };
+    tucan
+        .database
+        .send_message(StoreCacheRequest {
+            key,
+            hash: hash.to_owned(),
+            updated,
+            content,
+        })
+        .await;
+}
+
+/// Uses the result stored for the cached page `entry` if it was parsed by the
+/// current [`PARSER_VERSION`]. Otherwise reads the page, parses it and stores
+/// the result. Fails with [`TucanError::NotCached`] if the page can not be
+/// read.
+pub(crate) async fn parse_cached<Response: Serialize + DeserializeOwned>(
+    tucan: &TucanConnector,
+    cache_key: CacheKey,
+    entry: &CacheEntry,
+    parser: impl FnOnce(&str) -> Result<Response, TucanError>,
+) -> Result<Response, TucanError> {
+    let key = cache_key.to_string();
+    if let Some(response) = read_parsed(tucan, cache_key, &entry.hash).await {
+        return Ok(response);
+    }
+    let content = read_content(tucan, entry)
+        .await
+        .ok_or(TucanError::NotCached)?;
+    let response = parser(&content).map_err(|error| error.in_page(&key))?;
+    write_parsed(tucan, key, entry.hash.clone(), &response).await;
+    Ok(response)
+}
+
+/// The result parsed from the page with `hash` if it was parsed by the
+/// current [`PARSER_VERSION`].
+async fn read_parsed<Response: DeserializeOwned>(
+    tucan: &TucanConnector,
+    key: CacheKey,
+    hash: &str,
+) -> Option<Response> {
+    let parsed = tucan
+        .database
+        .send_message(ParsedCacheRequest {
+            key,
+            version: PARSER_VERSION,
+        })
+        .await
+        .filter(|parsed| parsed.hash == hash)?;
+    // e.g. a response type changed without bumping the version
+    serde_json::from_str(&parsed.value).ok()
+}
+
+/// Stores the result parsed from the page with `hash`.
+pub(crate) async fn write_parsed<Response: Serialize>(
+    tucan: &TucanConnector,
+    key: String,
+    hash: String,
+    response: &Response,
+) {
+    tucan
+        .database
+        .send_message(StoreParsedCacheRequest(ParsedCacheEntry {
+            key,
+            hash,
+            version: PARSER_VERSION,
+            value: serde_json::to_string(response).unwrap(),
+        }))
+        .await;
+}
+
+/// Brotli compresses `in_data` as the cache stores pages. Every fetched page
+/// is compressed on the UI thread of the extension, so a low quality is used
+/// that is many times faster than the high ones and compresses HTML nearly as
+/// well.
+pub async fn compress(in_data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = BrotliEncoder::with_quality(Vec::new(), Level::Precise(4));
+    encoder.write_all(in_data).await?;
+    encoder.shutdown().await?;
+    Ok(encoder.into_inner())
+}
+
+pub async fn decompress(in_data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = BrotliDecoder::new(Vec::new());
+    decoder.write_all(in_data).await?;
+    decoder.shutdown().await?;
+    Ok(decoder.into_inner())
+}
+
+/// How many pages [`recompress_cache`] reads at once.
+const RECOMPRESS_BATCH: i64 = 20;
+
+/// Compresses the pages cached before compression was introduced, which the
+/// migration could only keep uncompressed under their key. Works in batches
+/// so the database is not blocked while compressing, and pages with the same
+/// content are stored once afterwards.
+pub async fn recompress_cache(database: &MyDatabase) {
+    loop {
+        let batch = database
+            .send_message(UncompressedCacheRequest {
+                limit: RECOMPRESS_BATCH,
+            })
+            .await;
+        if batch.is_empty() {
+            break;
+        }
+        for old in batch {
+            let content = String::from_utf8_lossy(&old.value);
+            database
+                .send_message(ReplaceCacheContentRequest {
+                    old_hash: old.hash,
+                    content: CacheContent {
+                        hash: h(&content),
+                        value: compress(content.as_bytes()).await.unwrap(),
+                        compressed: true,
+                    },
+                })
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tucan_types::{
+        coursedetails::CourseDetailsResponse,
+        courseprep::CoursePrepResponse,
+        courseresults::ModuleResultsResponse,
+        examdetails::ExamDetailsResponse,
+        examregistration::ExamRegistrationResponse,
+        examresults::ExamResultsResponse,
+        gradeoverview::GradeOverviewResponse,
+        messages::{MessageDetailResponse, MessagesResponse},
+        mlsstart::MlsStart,
+        moduledetails::ModuleDetailsResponse,
+        month::MonthResponse,
+        mycourses::MyCoursesResponse,
+        mydocuments::MyDocumentsResponse,
+        myexams::MyExamsResponse,
+        mymodules::MyModulesResponse,
+        registration::AnmeldungResponse,
+        rooms::RoomDetailsResponse,
+        student_result::StudentResultResponse,
+        vv::Vorlesungsverzeichnis,
+    };
+    use utoipa::{
+        ToSchema,
+        openapi::{RefOr, schema::Schema},
+    };
+
+    use super::PARSER_VERSION;
+    use crate::h;
+
+    /// The hash of the schemas of the stored responses for each
+    /// [`PARSER_VERSION`], see [`response_schemas`].
+    const RESPONSE_SCHEMAS: [&str; 1] = ["hcFcRRoFT4_qkE9O4uJ-_fTdsiD3SmR5toUwqS5W_Rs"];
+
+    fn add<T: ToSchema>(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        schemas.push((T::name().into_owned(), T::schema()));
+        T::schemas(schemas);
+    }
+
+    /// Hashes the schemas of all responses stored in the parsed cache
+    /// without their descriptions, so only documentation changes do not
+    /// need a new version.
+    fn response_schemas() -> String {
+        fn strip(value: &mut Value) {
+            match value {
+                Value::Object(object) => {
+                    object.remove("description");
+                    object.values_mut().for_each(strip);
+                }
+                Value::Array(array) => array.iter_mut().for_each(strip),
+                _ => {}
+            }
+        }
+
+        let mut schemas = Vec::new();
+        add::<MlsStart>(&mut schemas);
+        add::<MyModulesResponse>(&mut schemas);
+        add::<MyCoursesResponse>(&mut schemas);
+        add::<MyExamsResponse>(&mut schemas);
+        add::<ExamRegistrationResponse>(&mut schemas);
+        add::<ExamResultsResponse>(&mut schemas);
+        add::<ModuleResultsResponse>(&mut schemas);
+        add::<MyDocumentsResponse>(&mut schemas);
+        add::<MessagesResponse>(&mut schemas);
+        add::<MessageDetailResponse>(&mut schemas);
+        add::<AnmeldungResponse>(&mut schemas);
+        add::<ModuleDetailsResponse>(&mut schemas);
+        add::<CourseDetailsResponse>(&mut schemas);
+        add::<CoursePrepResponse>(&mut schemas);
+        add::<ExamDetailsResponse>(&mut schemas);
+        add::<RoomDetailsResponse>(&mut schemas);
+        add::<StudentResultResponse>(&mut schemas);
+        add::<GradeOverviewResponse>(&mut schemas);
+        add::<MonthResponse>(&mut schemas);
+        add::<Vorlesungsverzeichnis>(&mut schemas);
+        schemas.sort_by(|a, b| a.0.cmp(&b.0));
+        schemas.dedup_by(|a, b| a.0 == b.0);
+        let mut value = serde_json::to_value(&schemas).unwrap();
+        strip(&mut value);
+        h(&value.to_string())
+    }
+
+    /// Fails when a stored response type changes, so [`PARSER_VERSION`] is
+    /// bumped and old results are not deserialized into the new types.
+    #[test]
+    fn parser_version_matches_responses() {
+        assert_eq!(
+            RESPONSE_SCHEMAS.len(),
+            usize::try_from(PARSER_VERSION).unwrap(),
+            "add one hash per parser version"
+        );
+        assert_eq!(
+            RESPONSE_SCHEMAS.last().copied(),
+            Some(response_schemas().as_str()),
+            "a stored response type changed, bump PARSER_VERSION and add the new hash"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     COURSEDETAILS_REGEX, h,
     head::{footer, html_head, logged_in_head, logged_out_head},
 };
-use html_handler::{MyElementRef, MyNode, Root, parse_document};
+use html_handler::{MyElementRef, MyNode, ParseError, Root, parse_document};
 use itertools::{Either, Itertools};
 use scraper::CaseSensitivity;
 use tucan_types::{
@@ -26,7 +26,7 @@ pub(crate) fn course_details_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -40,9 +40,9 @@ pub(crate) fn course_details_internal(
                 </head>
                 <body class="coursedetails">
                     use if login_response.id == 1 {
-                        logged_out_head(html_handler).0
+                        logged_out_head(html_handler)?.0
                     } else {
-                        logged_in_head(html_handler, login_response.id).0
+                        logged_in_head(html_handler, login_response.id)?.0
                     };
                     <script type="text/javascript">
                     </script>
@@ -186,7 +186,9 @@ pub(crate) fn course_details_internal(
                                                     <input type="hidden" name="sws" value=sws></input>
                                                 </p>
                                             } => {
-                                                assert_eq!(sws_text, sws);
+                                                if sws_text != sws {
+                                                    return Err(ParseError::invalid_value(sws, sws_text).into());
+                                                }
                                                 sws
                                             };
                                             let credits = if html_handler.peek().unwrap().value().as_element().unwrap().name() == "input" {
@@ -200,7 +202,9 @@ pub(crate) fn course_details_internal(
                                                     <input type="hidden" name="credits" value=credits></input>
                                                 </p>
                                             } => {
-                                                assert_eq!(credits_text, credits.trim());
+                                                if credits_text != credits.trim() {
+                                                    return Err(ParseError::invalid_value(credits.trim(), credits_text).into());
+                                                }
                                                 credits_text
                                             };
                                             <input type="hidden" name="location" value="327576461398991"></input>
@@ -231,11 +235,11 @@ pub(crate) fn course_details_internal(
                                                 </p>
                                             } => (teilnehmer_range, teilnehmer_min, teilnehmer_max);
                                             let description = while html_handler.peek().is_some() {
-                                                let child = html_handler.next_any_child();
+                                                let child = html_handler.next_any_child()?;
                                             } => match child.value() {
                                                 MyNode::Text(text) => text.to_string(),
                                                 MyNode::Element(_element) => MyElementRef::wrap(child).unwrap().html(),
-                                                _ => panic!(),
+                                                _ => return Err(ParseError::new(child, "text or element", format!("{:?}", child.value())).into()),
                                             };
                                         </td>
                                     </tr>
@@ -745,7 +749,7 @@ pub(crate) fn course_details_internal(
             </div>
         </div>
     }
-    let html_handler = footer(html_handler, login_response.id, 311);
+    let html_handler = footer(html_handler, login_response.id, 311)?;
     let course_anmeldefristen = course_anmeldefristen.map_or_else(Vec::new, |anmeldefristen| {
         if anmeldefristen.is_left() {
             anmeldefristen.unwrap_left()
@@ -753,11 +757,17 @@ pub(crate) fn course_details_internal(
             anmeldefristen.unwrap_right()
         }
     });
-    html_handler.end_document();
+    html_handler.end_document()?;
 
     let instructors = instructors.unwrap_or_default();
     match dozent.as_deref() {
-        None | Some("N.N.") => assert!(instructors.is_empty()),
+        None | Some("N.N.") => {
+            if let Some((instructor, _)) = instructors.first() {
+                return Err(
+                    ParseError::invalid_value("no instructors", instructor.as_str()).into(),
+                );
+            }
+        }
         Some(dozent)
             if h(dozent) == "fRArPBELwQcLhe4KzBODOZ7RNkKzNttCYuicWPUNx4w"
                 && instructors.iter().map(|m| h(&m.0)).eq([
@@ -769,26 +779,25 @@ pub(crate) fn course_details_internal(
             // hack
         }
         Some(dozent) => {
-            assert_eq!(
-                dozent.split("; ").sorted().collect::<Vec<_>>(),
-                instructors
-                    .iter()
-                    .map(|m| &m.0)
-                    .sorted()
-                    .collect::<Vec<_>>()
-            )
+            let expected = instructors.iter().map(|m| &m.0).sorted().join("; ");
+            if dozent.split("; ").sorted().join("; ") != expected {
+                return Err(ParseError::invalid_value(expected, dozent).into());
+            }
         }
     }
-    assert_eq!(
-        anzeige_im_stundenplan.clone().unwrap_or_default(),
-        shortname.trim()
-    );
+    if anzeige_im_stundenplan.as_deref().unwrap_or_default() != shortname.trim() {
+        return Err(ParseError::invalid_value(
+            shortname.trim(),
+            anzeige_im_stundenplan.clone().unwrap_or_default(),
+        )
+        .into());
+    }
 
     let (teilnehmer_range, teilnehmer_min, teilnehmer_max) = teilnehmer.either_into();
-    assert_eq!(
-        teilnehmer_range,
-        format!("{teilnehmer_min} | {teilnehmer_max}")
-    );
+    let expected_range = format!("{teilnehmer_min} | {teilnehmer_max}");
+    if teilnehmer_range != expected_range {
+        return Err(ParseError::invalid_value(expected_range, teilnehmer_range).into());
+    }
 
     let id_and_name: String = id_and_name.either_into();
     let (id, name) = id_and_name.split_once('\n').unwrap();
@@ -826,10 +835,21 @@ pub(crate) fn course_details_internal(
         courselevel: courselevel.parse().unwrap(),
         sws: sws
             .right()
-            .map(|sws| sws.replace(',', ".").parse().expect(&sws)),
+            .map(|sws| {
+                sws.replace(',', ".")
+                    .parse()
+                    .map_err(|_| ParseError::invalid_value("a number", sws))
+            })
+            .transpose()?,
         credits: credits
             .right()
-            .map(|credits| credits.trim_end_matches(",0").parse().expect(&credits)),
+            .map(|credits| {
+                credits
+                    .trim_end_matches(",0")
+                    .parse()
+                    .map_err(|_| ParseError::invalid_value("a number", credits))
+            })
+            .transpose()?,
         language: language_and_id.clone().either_into::<(String, String)>().0,
         language_id: language_and_id
             .either_into::<(String, String)>()
@@ -4,130 +4,327 @@
 // number in the coursedetails url seems like there is access control. if you
 // are not in a course it does not work. though you could easily register and
 // unregister again
-use crate::head::{html_head, logged_in_head, logged_out_head};
-use html_handler::{Root, parse_document};
-use tucan_types::{LoginResponse, TucanError};
-/*
-    assert_eq!(request.r#type, CoursePrepType::Course);
-    let key = format!(
-        "unparsed_course_prep.{}.{}",
-        login_response.id, request.course_id
-    );
+use crate::head::{footer, html_head, logged_in_head, logged_out_head};
+use html_handler::{InElement, Root, parse_document};
+use tucan_types::{
+    LoginResponse, TucanError,
+    coursedetails::Room,
+    courseprep::{CoursePrepRequest, CoursePrepResponse, CoursePrepType, SeriesAppointment},
+};
 
-    let url = format!(
-        "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=COURSEPREP&ARGUMENTS=-N{:015},-N000268,{}",
-        login_response.id, request
-    );
-*/
-
-#[allow(clippy::too_many_lines)]
-#[allow(unused)]
 pub(crate) fn course_prep_internal(
     login_response: &LoginResponse,
     content: &str,
-) -> Result<String, TucanError> {
+    request: &CoursePrepRequest,
+) -> Result<CoursePrepResponse, TucanError> {
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
+        #![tolerant]
         <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
             <head>
                 use html_head(html_handler)?;
                 <style type="text/css">
-                    "7eLX2t1Uo0IA6vL9eg7XnOUPvbqtOEwtcyPy3J7000g"
+                    _style
                 </style>
             </head>
             <body class="courseprep">
                 use if login_response.id == 1 {
-                    logged_out_head(html_handler).0
+                    logged_out_head(html_handler)?.0
                 } else {
-                    logged_in_head(html_handler, login_response.id).0
+                    logged_in_head(html_handler, login_response.id)?.0
                 };
-                <script type="text/javascript">
-                </script>
-                <script language="JavaScript">
-                    _trash
-                </script>
-                <h1>
-                    title
-                </h1>
-                let _kleingruppe = if html_handler
-                    .peek()
-                    .unwrap()
-                    .value()
-                    .as_element()
-                    .unwrap()
-                    .name()
-                    == "h2" {
-                    <h2>
-                        _kleingruppe
-                    </h2>
-                } => ();
-                <p>
-                    <span name="appointmentDate">
-                        _date
-                    </span>
-                    <span name="appointmentTimeFrom">
-                        _start
-                    </span>
-                    "-"
-                    <span name="appointmentTimeTo">
-                        _end
+                let response = course_prep_content(html_handler, &request.r#type)?;
+            </div>
+        </div>
+    </div>
+    };
+    let html_handler = footer(html_handler, login_response.id, 271)?;
+    html_handler.end_document()?;
+    Ok(response)
+}
+
+/// The appointment below the menu. Only courses have material and only
+/// courses and modules have a series of appointments.
+#[expect(clippy::too_many_lines)]
+fn course_prep_content<'a, T>(
+    html_handler: InElement<'a, T>,
+    r#type: &CoursePrepType,
+) -> Result<(InElement<'a, T>, CoursePrepResponse), TucanError> {
+    html_extractor::html! {
+        #![tolerant]
+        <script type="text/javascript">
+        </script>
+        <script language="JavaScript">
+            _trash
+        </script>
+        <h1>
+            title
+        </h1>
+        let kleingruppe = if html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == "h2") && html_handler.peek().and_then(|node| node.first_child()).and_then(|text| text.value().as_text()).is_none_or(|text| &**text != "Räume:") {
+            <h2>
+                kleingruppe
+            </h2>
+        } => kleingruppe;
+        <p>
+            <span name="appointmentDate">
+                date
+            </span>
+            <span name="appointmentTimeFrom">
+                time_start
+            </span>
+            "-"
+            <span name="appointmentTimeTo">
+                time_end
+            </span>
+        </p>
+        let rooms = if html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == "h2") {
+            <h2>
+                "Räume:"
+            </h2>
+            let rooms = while html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() != "div") {
+                let room = if html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == "a") {
+                    <a name="appoinmentRooms" class="arrow" href=room_url>
+                        room
+                    </a>
+                } => Room { name: room, url: Some(room_url) } else {
+                    <span name="appoinmentRooms">
+                        room
                     </span>
-                </p>
-                let _raeume = if html_handler
-                    .peek()
-                    .unwrap()
-                    .value()
-                    .as_element()
-                    .unwrap()
-                    .name()
-                    == "h2" {
-                    <h2>
-                        "Räume:"
-                    </h2>
-                    let _room = if html_handler
-                        .peek()
-                        .unwrap()
-                        .value()
-                        .as_element()
-                        .unwrap()
-                        .name()
-                        == "span" {
-                        <span name="appoinmentRooms">
-                            _room
-                        </span>
-                    } => () else {
-                        let _rooms = while html_handler
-                            .peek()
-                            .unwrap()
-                            .value()
-                            .as_element()
-                            .unwrap()
-                            .name()
-                            == "a" {
-                            <a name="appoinmentRooms" class="arrow" href=_room_href>
-                                _room
-                            </a>
-                        } => ();
-                    } => ();
-                    <div style="clear:both;">
-                    </div>
-                } => ();
-                <div class="contentlayoutleft" id="contentlayoutleft">
-                    <table class="tb">
-                        <tbody>
+                } => Room { name: room, url: None };
+            } => room.either_into::<Room>();
+            <div style="clear:both;">
+            </div>
+        } => rooms;
+        <div class="contentlayoutleft" id="contentlayoutleft">
+            let _material = if *r#type == CoursePrepType::Course {
+                <table class="tb">
+                    <tbody>
+                        <tr>
+                            <td class="tbhead" colspan="2">
+                                "Material zu einzelnen Terminen"
+                            </td>
+                        </tr>
+                        <tr>
+                            <td class="tbcontrol" colspan="2">
+                                <a id="Popup_link" href=_message_url class="arrow">
+                                    "Neue Nachricht"
+                                </a>
+                            </td>
+                        </tr>
+                        let _material = while html_handler.peek().is_some() {
                             <tr>
-                                <td class="tbhead" colspan="2">
-                                    "Material zu einzelnen Terminen"
+                                <td class="tbdata" colspan="2">
+                                    _material
                                 </td>
                             </tr>
+                        } => ();
+                    </tbody>
+                </table>
+            } => ();
+            <table class="tb rw-table">
+                <caption>
+                    "Termindetails"
+                </caption>
+                <tbody>
+                    let instructors = if html_handler.peek().and_then(|row| row.first_child()).and_then(|cell| cell.first_child()).and_then(|text| text.value().as_text()).is_some_and(|text| &**text == "Lehrende:") {
+                        <tr>
+                            <td class="tbsubhead">
+                                "Lehrende:"
+                            </td>
+                            <td class="tbdata">
+                                instructors
+                            </td>
+                        </tr>
+                    } => instructors;
+                    let notes = if html_handler.peek().is_some() {
+                        <tr>
+                            <td class="tbsubhead">
+                                "Bemerkung:"
+                            </td>
+                            <td class="tbdata">
+                                notes
+                            </td>
+                        </tr>
+                    } => notes;
+                </tbody>
+            </table>
+        </div>
+        let series = if *r#type != CoursePrepType::PersonalAppointment {
+            <div class="contentlayoutright" id="contentlayoutright">
+                <table class="tb rw-table">
+                    <caption>
+                        "Termine der Serie"
+                    </caption>
+                    <tbody>
+                        <tr class="rw-hide">
+                            <td class="tbsubhead">
+                                "Datum"
+                            </td>
+                            <td class="tbsubhead">
+                                "Von"
+                            </td>
+                            <td class="tbsubhead">
+                                "Bis"
+                            </td>
+                            <td class="tbsubhead">
+                                "Raum"
+                            </td>
+                        </tr>
+                        let series = while html_handler.peek().is_some() {
                             <tr>
-                                <td class="tbcontrol" colspan="2">
-                                    <a id="Popup_link" href=_message_url class="arrow">
-                                        "Neue Nachricht"
+                                <td class="tbdata">
+                                    <span name="appointmentDate">
+                                        date
+                                    </span>
+                                </td>
+                                <td class="tbdata">
+                                    <span name="appointmentTimeFrom">
+                                        time_start
+                                    </span>
+                                </td>
+                                <td class="tbdata">
+                                    <span name="appointmentTimeTo">
+                                        time_end
+                                    </span>
+                                </td>
+                                <td class="tbdata">
+                                    let rooms = while html_handler.peek().is_some() {
+                                        let room = if html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == "a") {
+                                            <a name="appointmentRooms" href=room_url>
+                                                room
+                                            </a>
+                                        } => Room { name: room, url: Some(room_url) } else {
+                                            <span name="appointmentRooms">
+                                                room
+                                            </span>
+                                        } => Room { name: room, url: None };
+                                    } => room.either_into::<Room>();
+                                </td>
+                            </tr>
+                        } => SeriesAppointment {
+                            date,
+                            time_start,
+                            time_end,
+                            rooms
+                        };
+                    </tbody>
+                </table>
+            </div>
+        } => series;
+    }
+    Ok((
+        html_handler,
+        CoursePrepResponse {
+            title,
+            kleingruppe,
+            date,
+            time_start,
+            time_end,
+            rooms: rooms.unwrap_or_default(),
+            instructors,
+            notes,
+            series: series.unwrap_or_default(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::head::parse_fragment;
+
+    fn parse(fragment: &str, r#type: &CoursePrepType) -> CoursePrepResponse {
+        parse_fragment(fragment, |html_handler| {
+            course_prep_content(html_handler, r#type)
+        })
+        .unwrap()
+    }
+
+    fn room(name: &str, id: Option<&str>) -> Room {
+        Room {
+            name: name.to_owned(),
+            url: id.map(|id| {
+                format!(
+                    "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ROOMDETAILS&ARGUMENTS=-N000000000000001,-N000271,-N{id}"
+                )
+            }),
+        }
+    }
+
+    #[test]
+    fn course() {
+        let response = parse(
+            include_str!("../test-data/fragments/courseprep_course.html"),
+            &CoursePrepType::Course,
+        );
+        assert_eq!(
+            response,
+            CoursePrepResponse {
+                title: "20-00-0004-iv Funktionale und objektorientierte Programmierkonzepte"
+                    .to_owned(),
+                kleingruppe: Some("Übungsgruppe 07".to_owned()),
+                date: "Mo, 14. Okt. 2024".to_owned(),
+                time_start: "13:30".to_owned(),
+                time_end: "15:10".to_owned(),
+                rooms: vec![
+                    room("S1|01/A1", Some("392125")),
+                    room("S1|01/A2", Some("392126"))
+                ],
+                instructors: Some("Prof. Dr. Karsten Weihe".to_owned()),
+                notes: Some("Bitte Laptop mitbringen".to_owned()),
+                series: vec![
+                    SeriesAppointment {
+                        date: "Mo, 14. Okt. 2024".to_owned(),
+                        time_start: "13:30".to_owned(),
+                        time_end: "15:10".to_owned(),
+                        rooms: vec![room("S1|01/A1", Some("392125"))],
+                    },
+                    SeriesAppointment {
+                        date: "Mo, 21. Okt. 2024".to_owned(),
+                        time_start: "13:30".to_owned(),
+                        time_end: "15:10".to_owned(),
+                        rooms: Vec::new(),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn module() {
+        let response = parse(
+            include_str!("../test-data/fragments/courseprep_module.html"),
+            &CoursePrepType::Module,
+        );
+        assert_eq!(response.kleingruppe, None);
+        assert_eq!(response.rooms, [room("Online", None)]);
+        assert_eq!(
+            response.instructors.as_deref(),
+            Some("Prof. Stefan Roth, Ph.D.")
+        );
+        assert_eq!(response.notes, None);
+        assert_eq!(response.series.len(), 1);
+        assert_eq!(response.series[0].rooms, [room("Online", None)]);
+    }
+
+    #[test]
+    fn personal_appointment() {
+        let fragment = include_str!("../test-data/fragments/courseprep_personal.html");
+        let response = parse(fragment, &CoursePrepType::PersonalAppointment);
+        assert_eq!(response.title, "Lerngruppe");
+        assert!(response.rooms.is_empty());
+        assert_eq!(response.instructors, None);
+        assert_eq!(response.notes.as_deref(), Some("Bibliothek"));
+        assert!(response.series.is_empty());
+        // a course has material, so the page does not match the template
+        assert!(
+            parse_fragment(fragment, |html_handler| {
+                course_prep_content(html_handler, &CoursePrepType::Course)
+            })
+            .is_err()
+        );
     }
-    let _ = html_handler;
-    Ok(title)
 }
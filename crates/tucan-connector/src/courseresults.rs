@@ -21,7 +21,7 @@ pub(crate) fn course_results_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -37,7 +37,7 @@ pub(crate) fn course_results_internal(
                     </style>
                 </head>
                 <body class="course_results">
-                    use logged_in_head(html_handler, login_response.id).0;
+                    use logged_in_head(html_handler, login_response.id)?.0;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -157,7 +157,7 @@ pub(crate) fn course_results_internal(
                                 } => ModuleResult {
                                     nr,
                                     name,
-                                    grade: ModuleGrade::from((grade.as_deref(), status.as_deref())),
+                                    grade: ModuleGrade::try_from((grade.as_deref(), status.as_deref()))?,
                                     credits,
                                     pruefungen_url,
                                     average_url
@@ -183,9 +183,9 @@ pub(crate) fn course_results_internal(
                 </div>
             </div>
         </div>
-        use footer(html_handler, login_response.id, 326);
+        use footer(html_handler, login_response.id, 326)?;
     }
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(ModuleResultsResponse {
         semester,
         results,
@@ -0,0 +1,377 @@
+use std::str::FromStr as _;
+
+use ego_tree::NodeRef;
+use html_handler::{InElement, MyNode, Root, parse_document};
+use tucan_types::{
+    LoginResponse, RevalidationStrategy, SemesterId, Semesterauswahl, Tucan as _, TucanError,
+    coursedetails::CourseDetailsRequest,
+    coursesearch::{CourseSearchRequest, CourseSearchResponse, SearchOption},
+    vv::Veranstaltung,
+};
+
+use crate::{
+    COURSEDETAILS_REGEX, TucanConnector, authenticated_retryable_get,
+    externalpages::welcome::welcome,
+    form::Form,
+    head::{footer, html_head, logged_in_or_out_head},
+    registration::is_timeout,
+    retryable_get,
+};
+
+/// Used as page in parse errors, search results are not cached.
+const PAGE: &str = "course_search";
+
+/// Searches logged out if there is no `login_response`.
+pub(crate) async fn search_courses(
+    tucan: &TucanConnector,
+    login_response: Option<&LoginResponse>,
+    request: &CourseSearchRequest,
+) -> Result<CourseSearchResponse, TucanError> {
+    let Some(login_response) = login_response else {
+        return post_and_parse(tucan, None, request).await;
+    };
+    let login_response = tucan.current_session(login_response).await;
+    match post_and_parse(tucan, Some(&login_response), request).await {
+        Err(TucanError::Timeout) if tucan.credentials.is_some() => {
+            let login_response = tucan.renew_session(&login_response).await?;
+            post_and_parse(tucan, Some(&login_response), request).await
+        }
+        result => result,
+    }
+}
+
+/// Opens the Lehrveranstaltungssuche linked in the menu and submits its form
+/// with the fields of `request`.
+async fn post_and_parse(
+    tucan: &TucanConnector,
+    login_response: Option<&LoginResponse>,
+    request: &CourseSearchRequest,
+) -> Result<CourseSearchResponse, TucanError> {
+    let url = format!(
+        "https://www.tucan.tu-darmstadt.de{}",
+        search_page_url(tucan, login_response).await?
+    );
+    let (page, _date) = match login_response {
+        Some(login_response) => {
+            authenticated_retryable_get(tucan, &url, &login_response.cookie_cnsc).await?
+        }
+        None => retryable_get(tucan, &url).await?,
+    };
+    if is_timeout(&page) {
+        return Err(TucanError::Timeout);
+    }
+    let mut form = Form::all(parse_document(&page).root())
+        .find(|form| form.has("course_name"))
+        .ok_or_else(|| {
+            TucanError::UnexpectedResponse(
+                "Die Lehrveranstaltungssuche hat kein Formular".to_owned(),
+            )
+        })?;
+    fill(&mut form, request)?;
+    let mut builder = tucan.client.post(form.url());
+    if let Some(login_response) = login_response {
+        builder = builder.header("Cookie", format!("cnsc={}", login_response.cookie_cnsc));
+    }
+    let permit = tucan.semaphore.acquire().await.unwrap();
+    let content = builder
+        .form(&form.fields)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    drop(permit);
+    course_search_internal(login_response, &content).map_err(|error| error.in_page(PAGE))
+}
+
+/// The link to the Lehrveranstaltungssuche in the menu of the start page.
+async fn search_page_url(
+    tucan: &TucanConnector,
+    login_response: Option<&LoginResponse>,
+) -> Result<String, TucanError> {
+    Ok(match login_response {
+        Some(login_response) => {
+            tucan
+                .after_login(login_response, RevalidationStrategy::cache())
+                .await?
+                .logged_in_head
+                .vv
+                .lehrveranstaltungssuche_url
+        }
+        None => welcome(tucan).await?.vv.lehrveranstaltungssuche_url,
+    })
+}
+
+/// Sets the fields of `request` in the search `form` as read from the page
+/// and clicks its submit button. Empty fields keep the value of the page.
+fn fill(form: &mut Form, request: &CourseSearchRequest) -> Result<(), TucanError> {
+    let semester = request
+        .semester
+        .as_ref()
+        .filter(|semester| **semester != SemesterId::current())
+        .map_or("", |semester| semester.inner());
+    for (name, value) in [
+        ("semester", semester),
+        ("course_name", request.title.as_str()),
+        ("instructor", request.lecturer.as_str()),
+        ("course_type", request.course_type.as_str()),
+        ("course_language", request.language.as_str()),
+        ("faculty", request.fachbereich.as_str()),
+    ] {
+        if !value.is_empty() && !form.set(name, value) {
+            return Err(TucanError::UnexpectedResponse(format!(
+                "Die Lehrveranstaltungssuche hat kein Feld {name}"
+            )));
+        }
+    }
+    form.click();
+    Ok(())
+}
+
+/// The options of the `<select>` called `name` in `form`.
+fn select_options(form: NodeRef<'_, MyNode>, name: &str) -> Vec<SearchOption> {
+    let text = |node: NodeRef<'_, MyNode>| {
+        node.descendants()
+            .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+            .collect::<String>()
+            .trim()
+            .to_owned()
+    };
+    form.descendants()
+        .find(|node| {
+            node.value().as_element().is_some_and(|element| {
+                element.name() == "select" && element.attr("name") == Some(name)
+            })
+        })
+        .into_iter()
+        .flat_map(|select| select.children())
+        .filter_map(|option| {
+            let element = option.value().as_element()?;
+            (element.name() == "option").then(|| SearchOption {
+                name: text(option),
+                value: element.attr("value").unwrap_or_default().to_owned(),
+                selected: element.attr("selected").is_some(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn course_search_internal(
+    login_response: Option<&LoginResponse>,
+    content: &str,
+) -> Result<CourseSearchResponse, TucanError> {
+    let document = parse_document(content);
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        #![tolerant]
+        <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
+            <head>
+                use html_head(html_handler)?;
+                <style type="text/css">
+                    _style
+                </style>
+            </head>
+            <body class=_body_class>
+                let _head = logged_in_or_out_head(html_handler, login_response)?;
+                let response = course_search_content(html_handler)?;
+            </div>
+        </div>
+    </div>
+    }
+    let html_handler = footer(html_handler, login_response.map_or(1, |l| l.id), 326)?;
+    html_handler.end_document()?;
+    Ok(response)
+}
+
+/// The search form and the results below the menu.
+fn course_search_content<'a, T>(
+    html_handler: InElement<'a, T>,
+) -> Result<(InElement<'a, T>, CourseSearchResponse), TucanError> {
+    html_extractor::html! {
+        #![tolerant]
+        <script type="text/javascript">
+        </script>
+        <h1>
+            _title
+        </h1>
+        // the fields depend on the semester, so only the options are read
+        let form = html_handler.next_any_child()?;
+        let results = if html_handler.peek().is_some() {
+            <div class="tb">
+                <div class="tbhead">
+                    _heading
+                </div>
+                let results = if html_handler.peek().and_then(|node| node.value().as_element()).is_some_and(|element| element.name() == "table") {
+                    <table class="nb eventTable">
+                        <tbody>
+                            <tr class="tbsubhead">
+                                <th>
+                                </th>
+                                <th>
+                                    "Veranstaltung / Modul"
+                                    <br></br>
+                                    "Dozenten / Modulverantwortliche"
+                                    <br></br>
+                                    "Zeitraum"
+                                </th>
+                                <th>
+                                </th>
+                                <th colspan="2">
+                                    "Veranstaltungsart"
+                                    <br></br>
+                                    "Raum"
+                                </th>
+                            </tr>
+                            let results = while html_handler.peek().is_some() {
+                                <tr class="tbdata">
+                                    <td>
+                                        let gefaehrdung_schwangere = if html_handler.peek().is_some() {
+                                            <img src="../../gfx/_default/icons/eventIcon.gif" title="Gefährdungspotential für Schwangere"></img>
+                                        } => ();
+                                    </td>
+                                    <td>
+                                        <a name="eventLink" href=coursedetails_url class="eventTitle">
+                                            title
+                                        </a>
+                                        <br></br>
+                                        let lecturer_name = if html_handler.peek().is_some() {
+                                            lecturer_name
+                                        } => lecturer_name;
+                                        let date_range = if html_handler.peek().is_some() {
+                                            <br></br>
+                                            date_range
+                                        } => date_range;
+                                    </td>
+                                    <td>
+                                    </td>
+                                    <td colspan="2">
+                                        course_type
+                                    </td>
+                                </tr>
+                            } => Veranstaltung {
+                                title,
+                                coursedetails_url: CourseDetailsRequest::parse(&COURSEDETAILS_REGEX.replace(&coursedetails_url, "")),
+                                lecturer_name,
+                                date_range,
+                                course_type,
+                                gefaehrdung_schwangere: gefaehrdung_schwangere.is_some()
+                            };
+                        </tbody>
+                    </table>
+                } => results else {
+                    <div class="tbdata" colspan="3">
+                        _no_results
+                    </div>
+                } => Vec::<Veranstaltung>::new();
+            </div>
+        } => results.either_into();
+    }
+    Ok((
+        html_handler,
+        CourseSearchResponse {
+            semester: select_options(form, "semester")
+                .into_iter()
+                .map(|option| Semesterauswahl {
+                    value: SemesterId::from_str(&option.value).unwrap(),
+                    name: option.name,
+                    selected: option.selected,
+                })
+                .collect(),
+            course_types: select_options(form, "course_type"),
+            languages: select_options(form, "course_language"),
+            fachbereiche: select_options(form, "faculty"),
+            results: results.unwrap_or_default(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::head::parse_fragment;
+
+    const FRAGMENT: &str = include_str!("../test-data/fragments/course_search.html");
+
+    #[test]
+    fn parses_options_and_results() {
+        let response =
+            parse_fragment(FRAGMENT, |html_handler| course_search_content(html_handler)).unwrap();
+        assert_eq!(
+            response
+                .semester
+                .iter()
+                .map(|semester| (semester.value.inner().as_str(), semester.selected))
+                .collect::<Vec<_>>(),
+            [
+                ("all", false),
+                ("000000015166000", true),
+                ("000000015176000", false)
+            ]
+        );
+        assert_eq!(response.course_types.len(), 3);
+        assert_eq!(response.languages[2].name, "Englisch");
+        assert_eq!(response.fachbereiche[1].value, "000000000000020");
+        assert_eq!(
+            response.results,
+            [
+                Veranstaltung {
+                    title: "20-00-0004 Funktionale und objektorientierte Programmierkonzepte"
+                        .to_owned(),
+                    coursedetails_url: CourseDetailsRequest::parse(
+                        "-N0,-N389955196524935,-N389955196524935,-N0,-N0,-N0"
+                    ),
+                    lecturer_name: Some("Prof. Dr. Karsten Weihe".to_owned()),
+                    date_range: Some("14.10.2024 - 14.02.2025".to_owned()),
+                    course_type: "Vorlesung".to_owned(),
+                    gefaehrdung_schwangere: false,
+                },
+                Veranstaltung {
+                    title: "07-01-0001 Allgemeine Chemie".to_owned(),
+                    coursedetails_url: CourseDetailsRequest::parse(
+                        "-N0,-N389955196162847,-N389955196162847,-N0,-N0,-N0"
+                    ),
+                    lecturer_name: None,
+                    date_range: None,
+                    course_type: "Praktikum".to_owned(),
+                    gefaehrdung_schwangere: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fills_the_form_of_the_page() {
+        let document = parse_document(FRAGMENT);
+        let mut form = Form::all(document.root()).next().unwrap();
+        fill(
+            &mut form,
+            &CourseSearchRequest {
+                title: "Programmierkonzepte".to_owned(),
+                semester: Some(SemesterId::current()),
+                fachbereich: "000000000000020".to_owned(),
+                ..CourseSearchRequest::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(form.get("course_name"), Some("Programmierkonzepte"));
+        assert_eq!(form.get("semester"), Some("000000015166000"));
+        assert_eq!(form.get("faculty"), Some("000000000000020"));
+        assert_eq!(form.get("PRGNAME"), Some("ACTION"));
+        assert_eq!(form.get("submit_search"), Some("Suche"));
+
+        let mut form = Form::all(document.root()).next().unwrap();
+        form.fields.retain(|(name, _value)| name != "instructor");
+        assert!(
+            fill(
+                &mut form,
+                &CourseSearchRequest {
+                    lecturer: "Weihe".to_owned(),
+                    ..CourseSearchRequest::default()
+                },
+            )
+            .is_err()
+        );
+    }
+}
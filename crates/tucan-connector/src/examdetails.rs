@@ -0,0 +1,187 @@
+use ego_tree::NodeRef;
+use html_handler::{InElement, MyNode, Root, parse_document};
+use tucan_types::{
+    LoginResponse, TucanError, coursedetails::Room, examdetails::ExamDetailsResponse,
+};
+
+use crate::head::{footer, html_head, logged_in_head};
+
+fn text(nodes: &[NodeRef<'_, MyNode>]) -> String {
+    nodes
+        .iter()
+        .flat_map(|node| node.descendants())
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Rooms are either links to their details or plain text separated by
+/// commas.
+fn rooms(nodes: &[NodeRef<'_, MyNode>]) -> Vec<Room> {
+    nodes
+        .iter()
+        .flat_map(|node| match node.value() {
+            MyNode::Element(element) if element.name() == "a" => vec![Room {
+                name: text(&[*node]),
+                url: element.attr("href").map(str::to_owned),
+            }],
+            MyNode::Text(text) => text
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| Room {
+                    name: name.to_owned(),
+                    url: None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+pub(crate) fn exam_details_internal(
+    login_response: &LoginResponse,
+    content: &str,
+    _nothing: &(),
+) -> Result<ExamDetailsResponse, TucanError> {
+    let document = parse_document(content);
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        #![tolerant]
+        <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
+            <head>
+                use html_head(html_handler)?;
+                <style type="text/css">
+                    _style
+                </style>
+            </head>
+            <body class=_body_class>
+                use logged_in_head(html_handler, login_response.id)?.0;
+                let response = exam_details_content(html_handler)?;
+            </div>
+        </div>
+    </div>
+    };
+    let html_handler = footer(html_handler, login_response.id, 318)?;
+    html_handler.end_document()?;
+    Ok(response)
+}
+
+/// The details below the menu. Fails if none of the labels is known, so a
+/// changed page does not silently yield empty details.
+fn exam_details_content<'a, T>(
+    html_handler: InElement<'a, T>,
+) -> Result<(InElement<'a, T>, ExamDetailsResponse), TucanError> {
+    html_extractor::html! {
+        #![tolerant]
+        <script type="text/javascript">
+        </script>
+        <h1>
+            name
+        </h1>
+        <table class="tb rw-table">
+            <caption>
+                _details
+            </caption>
+            <tbody>
+                let properties = while html_handler.peek().is_some() {
+                    <tr>
+                        <td class="tbsubhead">
+                            label
+                        </td>
+                        <td class="tbdata">
+                            let value = while html_handler.peek().is_some() {
+                                let node = html_handler.next_any_child()?;
+                            } => node;
+                        </td>
+                    </tr>
+                } => (label, value);
+            </tbody>
+        </table>
+    }
+    let mut response = ExamDetailsResponse {
+        name,
+        examiner: None,
+        rooms: Vec::new(),
+        date: None,
+        registration_range: None,
+        unregistration_range: None,
+        aids: None,
+    };
+    let mut unknown = Vec::new();
+    for (label, value) in &properties {
+        let text = Some(text(value)).filter(|text| !text.is_empty());
+        match label.trim().trim_end_matches(':') {
+            "Prüfer" | "Prüfer/-in" | "Prüfende" => response.examiner = text,
+            "Raum" | "Räume" => response.rooms = rooms(value),
+            "Datum" | "Termin" => response.date = text,
+            "Anmeldezeitraum" => response.registration_range = text,
+            "Abmeldezeitraum" => response.unregistration_range = text,
+            "Hilfsmittel" | "Zugelassene Hilfsmittel" => response.aids = text,
+            _ => unknown.push(label.as_str()),
+        }
+    }
+    if unknown.len() == properties.len() {
+        return Err(TucanError::UnexpectedResponse(format!(
+            "Die Prüfungsdetails enthalten keine bekannte Angabe: {}",
+            unknown.join(", ")
+        )));
+    }
+    Ok((html_handler, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::head::parse_fragment;
+
+    #[test]
+    fn reads_labels() {
+        let response = parse_fragment(
+            include_str!("../test-data/fragments/exam_details.html"),
+            |html_handler| exam_details_content(html_handler),
+        )
+        .unwrap();
+        assert_eq!(
+            response,
+            ExamDetailsResponse {
+                name: "20-00-0004 Funktionale und objektorientierte Programmierkonzepte".to_owned(),
+                examiner: Some("Prof. Dr. Karsten Weihe".to_owned()),
+                rooms: vec![
+                    Room {
+                        name: "S1|01/A1".to_owned(),
+                        url: Some(
+                            "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ROOMDETAILS&ARGUMENTS=-N000000000000001,-N000318,-N392125"
+                                .to_owned()
+                        ),
+                    },
+                    Room {
+                        name: "S1|03/283".to_owned(),
+                        url: None,
+                    },
+                ],
+                date: Some("Mo, 17. Feb. 2025 09:00-11:00".to_owned()),
+                registration_range: Some("01.12.2024 00:00 - 15.01.2025 23:59".to_owned()),
+                unregistration_range: Some("01.12.2024 00:00 - 10.02.2025 23:59".to_owned()),
+                aids: None,
+            }
+        );
+    }
+
+    #[test]
+    fn fails_without_known_labels() {
+        let fragment = r#"<script type="text/javascript"></script><h1>Klausur</h1>
+            <table class="tb rw-table"><caption>Details</caption><tbody>
+            <tr><td class="tbsubhead">Examiner:</td><td class="tbdata">Weihe</td></tr>
+            </tbody></table>"#;
+        assert!(matches!(
+            parse_fragment(fragment, |html_handler| exam_details_content(html_handler)),
+            Err(TucanError::UnexpectedResponse(_))
+        ));
+    }
+}
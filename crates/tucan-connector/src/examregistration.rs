@@ -20,7 +20,7 @@ pub(crate) fn exam_registration_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -33,7 +33,7 @@ pub(crate) fn exam_registration_internal(
                     </style>
                 </head>
                 <body class="exam_registration">
-                    use logged_in_head(html_handler, login_response.id).0;
+                    use logged_in_head(html_handler, login_response.id)?.0;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -149,9 +149,9 @@ pub(crate) fn exam_registration_internal(
                 </div>
             </div>
         </div>
-        use footer(html_handler, login_response.id, 326);
+        use footer(html_handler, login_response.id, 326)?;
     }
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(ExamRegistrationResponse {
         semester,
         exam_registrations,
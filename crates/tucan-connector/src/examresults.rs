@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use html_handler::{Root, parse_document};
+use html_handler::{ParseError, Root, parse_document};
 use tucan_types::{
     ExamResultsGrade, LoginResponse, SemesterId, Semesterauswahl, TucanError,
     examresults::{ExamResult, ExamResultsResponse},
@@ -21,7 +21,7 @@ pub(crate) fn exam_results_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -37,7 +37,7 @@ pub(crate) fn exam_results_internal(
                     </style>
                 </head>
                 <body class="exam_results">
-                    use logged_in_head(html_handler, login_response.id).0;
+                    use logged_in_head(html_handler, login_response.id)?.0;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -137,13 +137,15 @@ pub(crate) fn exam_results_internal(
                                             grade
                                         </td>
                                         <td style="vertical-align:top;">
-                                            let actual_grade = if let ExamResultsGrade::Grade(grade) = ExamResultsGrade::from_str(&grade).unwrap() {
+                                            let actual_grade = if let ExamResultsGrade::Grade(grade) = ExamResultsGrade::from_str(&grade)? {
                                                 grade_text
                                             } => {
-                                                assert_eq!(grade.long_text(), grade_text);
+                                                if grade.long_text() != grade_text {
+                                                    return Err(ParseError::invalid_value(grade.long_text(), grade_text).into());
+                                                }
                                                 ExamResultsGrade::Grade(grade)
                                             } else {
-                                            } => ExamResultsGrade::from_str(&grade).unwrap();
+                                            } => ExamResultsGrade::from_str(&grade)?;
                                         </td>
                                         <td style="vertical-align:top;">
                                             let average_url = if html_handler.peek().is_some() {
@@ -180,8 +182,8 @@ pub(crate) fn exam_results_internal(
                 </div>
             </div>
         </div>
-        use footer(html_handler, login_response.id, 326);
+        use footer(html_handler, login_response.id, 326)?;
     }
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(ExamResultsResponse { semester, results })
 }
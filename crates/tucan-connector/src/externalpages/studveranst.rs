@@ -23,7 +23,7 @@ pub async fn veranstaltungen(
     let document = parse_document(&content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
         <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
             <head>
@@ -33,7 +33,7 @@ pub async fn veranstaltungen(
                 </style>
             </head>
             <body class="external_pages">
-                use logged_in_head(html_handler, login_response.id).0;
+                use logged_in_head(html_handler, login_response.id)?.0;
                 <script type="text/javascript">
                 </script>
                 <div id="inhalt">
@@ -15,7 +15,7 @@ pub async fn welcome(connector: &TucanConnector) -> Result<LoggedOutHead, TucanE
     let document = parse_document(&content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html
                 xmlns="http://www.w3.org/1999/xhtml"
@@ -31,7 +31,7 @@ pub async fn welcome(connector: &TucanConnector) -> Result<LoggedOutHead, TucanE
                     </style>
                 </head>
                 <body class="external_pages">
-                    let vv = logged_out_head(html_handler);
+                    let vv = logged_out_head(html_handler)?;
                     <script type="text/javascript">
                     </script>
                     <meta http-equiv="content-type" content="text/html; charset=windows-1252"></meta>
@@ -40,14 +40,14 @@ pub async fn welcome(connector: &TucanConnector) -> Result<LoggedOutHead, TucanE
                         style="padding:0px; width:650px; margin:0px; background-color:#ffffff;"
                     >
                         let _unused = while html_handler.peek().is_some() {
-                            let any_child = html_handler.next_any_child();
+                            let any_child = html_handler.next_any_child()?;
                         } => any_child;
                     </div>
                 </div>
             </div>
         </div>
-        use footer(html_handler, 1, 344);
+        use footer(html_handler, 1, 344)?;
     }
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(vv)
 }
@@ -0,0 +1,169 @@
+//! Forms read from a page, so a submission uses the action and the fields
+//! TUCaN put into the page instead of hard-coded ones.
+
+use ego_tree::NodeRef;
+use html_handler::MyNode;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Form {
+    /// Relative to `https://www.tucan.tu-darmstadt.de`.
+    pub action: String,
+    /// The fields in document order. Submit buttons are only included if
+    /// [`Form::click`] was called, like a browser only sends the clicked one.
+    pub fields: Vec<(String, String)>,
+    submits: Vec<(String, String)>,
+}
+
+impl Form {
+    /// Reads the named inputs, selects and textareas of a `<form>` element.
+    /// Selects use their selected option or the first one.
+    #[must_use]
+    pub fn read(form: NodeRef<'_, MyNode>) -> Option<Self> {
+        let element = form.value().as_element()?;
+        if element.name() != "form" {
+            return None;
+        }
+        let mut fields = Vec::new();
+        let mut submits = Vec::new();
+        for node in form.descendants() {
+            let Some(element) = node.value().as_element() else {
+                continue;
+            };
+            let Some(name) = element.attr("name") else {
+                continue;
+            };
+            let value = match element.name() {
+                "input" => match element.attr("type").unwrap_or("text") {
+                    "submit" => {
+                        submits.push((
+                            name.to_owned(),
+                            element.attr("value").unwrap_or_default().to_owned(),
+                        ));
+                        continue;
+                    }
+                    "checkbox" | "radio" if element.attr("checked").is_none() => continue,
+                    _ => element.attr("value").unwrap_or_default().to_owned(),
+                },
+                "select" => {
+                    let options = node.descendants().filter_map(|option| {
+                        option
+                            .value()
+                            .as_element()
+                            .filter(|option| option.name() == "option")
+                    });
+                    options
+                        .clone()
+                        .find(|option| option.attr("selected").is_some())
+                        .or_else(|| options.clone().next())
+                        .and_then(|option| option.attr("value"))
+                        .unwrap_or_default()
+                        .to_owned()
+                }
+                "textarea" => text(node),
+                _ => continue,
+            };
+            fields.push((name.to_owned(), value));
+        }
+        Some(Self {
+            action: element.attr("action").unwrap_or_default().to_owned(),
+            fields,
+            submits,
+        })
+    }
+
+    /// All forms of a page in document order.
+    pub fn all(root: NodeRef<'_, MyNode>) -> impl Iterator<Item = Self> {
+        root.descendants().filter_map(Self::read)
+    }
+
+    /// The value of the field `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _value)| field == name)
+            .map(|(_field, value)| value.as_str())
+    }
+
+    /// Whether the form has a field called `name`.
+    #[must_use]
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Replaces the value of the field `name`, returns `false` if the form
+    /// has no such field.
+    pub fn set(&mut self, name: &str, value: &str) -> bool {
+        let Some((_field, old)) = self.fields.iter_mut().find(|(field, _value)| field == name)
+        else {
+            return false;
+        };
+        value.clone_into(old);
+        true
+    }
+
+    /// Adds the first submit button to the fields, returns `false` if there
+    /// is none.
+    pub fn click(&mut self) -> bool {
+        if self.submits.is_empty() {
+            return false;
+        }
+        let submit = self.submits.remove(0);
+        self.fields.push(submit);
+        true
+    }
+
+    /// The absolute URL the form is submitted to.
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("https://www.tucan.tu-darmstadt.de{}", self.action)
+    }
+}
+
+fn text(node: NodeRef<'_, MyNode>) -> String {
+    node.descendants()
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use html_handler::parse_document;
+
+    use super::Form;
+
+    #[test]
+    fn reads_fields() {
+        let document = parse_document(
+            r#"<!DOCTYPE html><html><head></head><body>
+            <form name="form" action="/scripts/mgrqispi.dll" method="post">
+                <select name="semester"><option value="1">WiSe</option><option value="2" selected="selected">SoSe</option></select>
+                <select name="faculty"><option value="">Alle</option><option value="20">Informatik</option></select>
+                <input type="text" name="course_name" value="">
+                <input type="checkbox" name="extended">
+                <input type="submit" name="submit_search" value="Suche">
+                <input name="PRGNAME" type="hidden" value="ACTION">
+            </form>
+            </body></html>"#,
+        );
+        let mut form = Form::all(document.root()).next().unwrap();
+        assert_eq!(
+            form.url(),
+            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll"
+        );
+        assert_eq!(
+            form.fields,
+            [
+                ("semester".to_owned(), "2".to_owned()),
+                ("faculty".to_owned(), String::new()),
+                ("course_name".to_owned(), String::new()),
+                ("PRGNAME".to_owned(), "ACTION".to_owned()),
+            ]
+        );
+        assert!(form.set("course_name", "Analysis"));
+        assert!(!form.set("lecturer", "Schmidt"));
+        assert!(form.click());
+        assert_eq!(form.get("course_name"), Some("Analysis"));
+        assert_eq!(form.get("submit_search"), Some("Suche"));
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     TucanError,
     head::{footer, html_head, logged_in_head},
 };
-use html_handler::{Root, parse_document};
+use html_handler::{ParseError, Root, parse_document};
 
 pub static GRADEOVERVIEW_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
@@ -28,7 +28,7 @@ pub(crate) fn gradeoverview_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -44,7 +44,7 @@ pub(crate) fn gradeoverview_internal(
                     </style>
                 </head>
                 <body class="students_grades_diagramm_BFW">
-                    let _head = logged_in_head(html_handler, login_response.id);
+                    let _head = logged_in_head(html_handler, login_response.id)?;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -122,7 +122,7 @@ pub(crate) fn gradeoverview_internal(
                                         } => if value == "---" {
                                             0
                                         } else {
-                                            value.parse().expect(&value)
+                                            value.parse().map_err(|_| ParseError::invalid_value("a number", value))?
                                         };
                                     </tr>
                                 </tbody>
@@ -145,8 +145,8 @@ pub(crate) fn gradeoverview_internal(
             </div>
         </div>
     };
-    let html_handler = footer(html_handler, login_response.id, 19);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.id, 19)?;
+    html_handler.end_document()?;
     Ok(GradeOverviewResponse {
         module_and_semester,
         modulangebot,
@@ -8,7 +8,7 @@ use tucan_types::{
     vv::ActionRequest,
 };
 
-use html_handler::{InElement, InRoot, Root};
+use html_handler::{InElement, InRoot, ParseError, Root};
 
 use crate::InElement5;
 
@@ -19,10 +19,9 @@ pub(crate) static ACTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new("^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS=").unwrap()
 });
 
-#[must_use]
 pub fn html_head_2<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-) -> InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>> {
+) -> Result<InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>, TucanError> {
     html_extractor::html! {
         <script type="text/javascript">
         </script>
@@ -40,7 +39,7 @@ pub fn html_head_2<'a>(
         <link href="/css/styles.css" rel="stylesheet" type="text/css"></link>
         <link href="/css/colors.css" rel="stylesheet" type="text/css"></link>
     };
-    html_handler
+    Ok(html_handler)
 }
 
 #[expect(unreachable_code)]
@@ -49,6 +48,7 @@ pub fn html_head<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
 ) -> Result<InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>, TucanError> {
     html_extractor::html! {
+        #![tolerant]
         <title>
             "Technische Universität Darmstadt"
         </title>
@@ -138,7 +138,13 @@ pub fn html_head<'a>(
                     </body>
                 } => () else {
                     extern {
-                        panic!();
+                        let body = html_handler.peek();
+                        return Err(ParseError::invalid_value(
+                            "a body with the class timeout or access_denied",
+                            body.and_then(|body| body.value().as_element()?.attr("class")).unwrap_or_default(),
+                        )
+                        .near(body.copied())
+                        .into());
                     }
                 } => ();
             } => ();
@@ -148,11 +154,11 @@ pub fn html_head<'a>(
     Ok(html_handler)
 }
 
-#[must_use]
 pub fn page_start<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-) -> InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>> {
+) -> Result<InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>, TucanError> {
     html_extractor::html! {
+        #![tolerant]
         <div id="Cn-system-desc">
         </div>
         <script type="text/javascript">
@@ -237,16 +243,19 @@ pub fn page_start<'a>(
                     </a>
                     <ul class="nav depth_1 linkItemContainer">
     };
-    html_handler
+    Ok(html_handler)
 }
 
-#[must_use]
+#[expect(clippy::type_complexity)]
 pub fn vv_something<'a>(
     html_handler: InElement5<'a, InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>>,
-) -> (
-    InElement5<'a, InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>>,
-    VorlesungsverzeichnisUrls,
-) {
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>>,
+        VorlesungsverzeichnisUrls,
+    ),
+    TucanError,
+> {
     // these link ids are incrementing so they are different if used from different
     // contexts. could in theory be calculated based on some starting number
     html_extractor::html! {
@@ -310,43 +319,54 @@ pub fn vv_something<'a>(
             </li>
         </ul>
     };
-    (
+    Ok((
         html_handler,
         VorlesungsverzeichnisUrls {
             lehrveranstaltungssuche_url,
             vvs,
             archiv_links,
         },
-    )
+    ))
 }
 
-#[must_use]
+#[expect(clippy::type_complexity)]
 pub fn logged_in_head<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
     id: u64,
-) -> (
-    InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-    LoggedInHead,
-) {
-    assert_ne!(id, 1);
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+        LoggedInHead,
+    ),
+    TucanError,
+> {
+    if id == 1 {
+        return Err(TucanError::LoginRequired);
+    }
     html_extractor::html! {
-        use page_start(html_handler);
-        let result = logged_in_head_internal(html_handler, id);
+        use page_start(html_handler)?;
+        let result = logged_in_head_internal(html_handler, id)?;
     }
-    (html_handler, result)
+    Ok((html_handler, result))
 }
 
 #[expect(clippy::too_many_lines)]
-#[must_use]
+#[expect(clippy::type_complexity)]
 fn logged_in_head_internal<'a>(
     html_handler: InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
     id: u64,
-) -> (
-    InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-    LoggedInHead,
-) {
-    assert_ne!(id, 1);
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+        LoggedInHead,
+    ),
+    TucanError,
+> {
+    if id == 1 {
+        return Err(TucanError::LoginRequired);
+    }
     html_extractor::html! {
+                    #![tolerant]
                     <li class="tree depth_1 linkItem branchLinkItem " title="Aktuelles" id="link000019">
                         <a class="depth_1 link000019 navLink branchLink " href=_aktuelles_url>
                             "Aktuelles"
@@ -366,7 +386,7 @@ fn logged_in_head_internal<'a>(
                         >
                             "VV"
                         </a>
-                        let vv = vv_something(html_handler);
+                        let vv = vv_something(html_handler)?;
                     </li>
                     <li
                         class="tree depth_1 linkItem branchLinkItem "
@@ -375,7 +395,7 @@ fn logged_in_head_internal<'a>(
                     >
                         <a
                             class="depth_1 link000268 navLink branchLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -383,7 +403,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Stundenplan"
@@ -392,7 +412,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Tagesansicht" id="link000269">
                                 <a
                                     class="depth_2 link000269 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -401,7 +421,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Tagesansicht"
@@ -410,7 +430,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Wochenansicht" id="link000270">
                                 <a
                                     class="depth_2 link000270 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -419,7 +439,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Wochenansicht"
@@ -428,7 +448,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Monatsansicht" id="link000271">
                                 <a
                                     class="depth_2 link000271 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -436,7 +456,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Monatsansicht"
@@ -445,7 +465,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Export" id="link000272">
                                 <a
                                     class="depth_2 link000272 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -454,7 +474,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Export"
@@ -469,7 +489,7 @@ fn logged_in_head_internal<'a>(
                     >
                         <a
                             class="depth_1 link000273 navLink branchLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -478,7 +498,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Veranstaltungen"
@@ -487,7 +507,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Meine Module" id="link000275">
                                 <a
                                     class="depth_2 link000275 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -495,7 +515,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Module"
@@ -508,7 +528,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000274 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -516,7 +536,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Veranstaltungen"
@@ -529,7 +549,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000307 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -538,7 +558,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Wahlbereiche"
@@ -547,7 +567,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Anmeldung" id="link000311">
                                 <a
                                     class="depth_2 link000311 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -556,7 +576,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Anmeldung"
@@ -569,7 +589,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000308 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -578,7 +598,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Mein aktueller Anmeldestatus"
@@ -589,7 +609,7 @@ fn logged_in_head_internal<'a>(
                     <li class="tree depth_1 linkItem branchLinkItem " title="Prüfungen" id="link000280">
                         <a
                             class="depth_1 link000280 navLink branchLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -598,7 +618,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Prüfungen"
@@ -611,7 +631,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000318 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -619,7 +639,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Prüfungen"
@@ -632,7 +652,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000389 navLink branchLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -640,7 +660,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Mein Prüfungsplan"
@@ -653,7 +673,7 @@ fn logged_in_head_internal<'a>(
                                     >
                                         <a
                                             class="depth_3 link000391 navLink "
-                                            href={|v: String| {
+                                            href={|v: &str| {
                                                 static REGEX: LazyLock<Regex> =
                                                     LazyLock::new(|| {
                                                         Regex::new(
@@ -664,7 +684,7 @@ fn logged_in_head_internal<'a>(
                                                         )
                                                         .unwrap()
                                                     });
-                                                assert!(REGEX.is_match(&v), "{v}");
+                                                REGEX.is_match(v)
                                             }}
                                         >
                                             "Wichtige Hinweise"
@@ -679,7 +699,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000323 navLink branchLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -688,7 +708,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Semesterergebnisse"
@@ -701,7 +721,7 @@ fn logged_in_head_internal<'a>(
                                     >
                                         <a
                                             class="depth_3 link000324 navLink "
-                                            href={|v: String| {
+                                            href={|v: &str| {
                                                 static REGEX: LazyLock<Regex> =
                                                     LazyLock::new(|| {
                                                         Regex::new(
@@ -712,7 +732,7 @@ fn logged_in_head_internal<'a>(
                                                         )
                                                         .unwrap()
                                                     });
-                                                assert!(REGEX.is_match(&v), "{v}");
+                                                REGEX.is_match(v)
                                             }}
                                         >
                                             "Modulergebnisse"
@@ -725,7 +745,7 @@ fn logged_in_head_internal<'a>(
                                     >
                                         <a
                                             class="depth_3 link000325 navLink "
-                                            href={|v: String| {
+                                            href={|v: &str| {
                                                 static REGEX: LazyLock<Regex> =
                                                     LazyLock::new(|| {
                                                         Regex::new(
@@ -736,7 +756,7 @@ fn logged_in_head_internal<'a>(
                                                         )
                                                         .unwrap()
                                                     });
-                                                assert!(REGEX.is_match(&v), "{v}");
+                                                REGEX.is_match(v)
                                             }}
                                         >
                                             "Prüfungsergebnisse"
@@ -751,7 +771,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000316 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -761,7 +781,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Leistungsspiegel"
@@ -772,7 +792,7 @@ fn logged_in_head_internal<'a>(
                     <li class="tree depth_1 linkItem branchLinkItem " title="Service" id="link000337">
                         <a
                             class="depth_1 link000337 navLink branchLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -781,7 +801,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Service"
@@ -794,7 +814,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000339 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -802,7 +822,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Persönliche Daten"
@@ -815,7 +835,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000557 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -824,7 +844,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Dokumente"
@@ -838,7 +858,7 @@ fn logged_in_head_internal<'a>(
                             <li class="intern depth_2 linkItem " title="Sperren" id="link000652">
                                 <a
                                     class="depth_2 link000652 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -846,7 +866,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Sperren"
@@ -857,7 +877,7 @@ fn logged_in_head_internal<'a>(
                     <li class="tree depth_1 linkItem branchLinkItem " title="Bewerbung" id="link000441">
                         <a
                             class="depth_1 link000441 navLink branchLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -866,7 +886,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Bewerbung"
@@ -879,7 +899,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000442 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -888,7 +908,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Herzlich Willkommen"
@@ -910,7 +930,7 @@ fn logged_in_head_internal<'a>(
                             >
                                 <a
                                     class="depth_2 link000444 navLink "
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -919,7 +939,7 @@ fn logged_in_head_internal<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                 >
                                     "Meine Dokumente"
@@ -930,7 +950,7 @@ fn logged_in_head_internal<'a>(
                     <li class="intern depth_1 linkItem " title="Hilfe" id="link000340">
                         <a
                             class="depth_1 link000340 navLink "
-                            href={|v: String| {
+                            href={|v: &str| {
                                 static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                     Regex::new(
                                         "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&\
@@ -939,7 +959,7 @@ fn logged_in_head_internal<'a>(
                                     )
                                     .unwrap()
                                 });
-                                assert!(REGEX.is_match(&v), "{v}");
+                                REGEX.is_match(v)
                             }}
                         >
                             "Hilfe"
@@ -953,7 +973,7 @@ fn logged_in_head_internal<'a>(
                         "English"
                     </a>
                     <a
-                        href={|v: String| {
+                        href={|v: &str| {
                             static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                 Regex::new(
                                     "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=LOGOUT&\
@@ -961,7 +981,7 @@ fn logged_in_head_internal<'a>(
                                 )
                                 .unwrap()
                             });
-                            assert!(REGEX.is_match(&v), "{v}");
+                            REGEX.is_match(v)
                         }}
                         id="logoutButton"
                         class="img img_arrowLogout logout"
@@ -1023,7 +1043,7 @@ fn logged_in_head_internal<'a>(
                 </div>
                 <div id="contentSpacer_IE" class="pageElementTop">
     };
-    (
+    Ok((
         html_handler,
         LoggedInHead {
             messages_url,
@@ -1034,31 +1054,38 @@ fn logged_in_head_internal<'a>(
             antraege_url,
             meine_bewerbung_url,
         },
-    )
+    ))
 }
 
-#[must_use]
+#[expect(clippy::type_complexity)]
 pub fn logged_out_head<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-) -> (
-    InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-    LoggedOutHead,
-) {
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+        LoggedOutHead,
+    ),
+    TucanError,
+> {
     html_extractor::html! {
-        use page_start(html_handler);
-        let result = logged_out_head_internal(html_handler);
+        use page_start(html_handler)?;
+        let result = logged_out_head_internal(html_handler)?;
     }
-    (html_handler, result)
+    Ok((html_handler, result))
 }
 
-#[must_use]
+#[expect(clippy::type_complexity)]
 fn logged_out_head_internal<'a>(
     html_handler: InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-) -> (
-    InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-    LoggedOutHead,
-) {
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+        LoggedOutHead,
+    ),
+    TucanError,
+> {
     html_extractor::html! {
+                    #![tolerant]
                     <li class="intern depth_1 linkItem " title="Startseite" id="link000344">
                         <a
                             class="depth_1 link000344 navLink "
@@ -1079,7 +1106,7 @@ fn logged_out_head_internal<'a>(
                         >
                             "Vorlesungsverzeichnis (VV)"
                         </a>
-                        let vv = vv_something(html_handler);
+                        let vv = vv_something(html_handler)?;
                     </li>
                     <li
                         class="tree depth_1 linkItem branchLinkItem "
@@ -1167,7 +1194,7 @@ fn logged_out_head_internal<'a>(
                 </div>
                 <div id="contentSpacer_IE" class="pageElementTop">
     }
-    (
+    Ok((
         html_handler,
         LoggedOutHead {
             vorlesungsverzeichnis_url: ActionRequest::parse(
@@ -1175,47 +1202,50 @@ fn logged_out_head_internal<'a>(
             ),
             vv,
         },
-    )
+    ))
 }
 
-#[must_use]
+#[expect(clippy::type_complexity)]
 pub fn logged_in_or_out_head<'a>(
     html_handler: InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
     login_response: Option<&LoginResponse>,
-) -> (
-    InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
-    Either<LoggedOutHead, LoggedInHead>,
-) {
+) -> Result<
+    (
+        InElement5<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+        Either<LoggedOutHead, LoggedInHead>,
+    ),
+    TucanError,
+> {
     html_extractor::html! {
-        use page_start(html_handler);
+        use page_start(html_handler)?;
         let result = if html_handler
             .peek()
-            .unwrap()
-            .value()
-            .as_element()
-            .unwrap()
-            .attr("title")
+            .and_then(|node| node.value().as_element())
+            .and_then(|element| element.attr("title"))
             == Some("Startseite") {
-            let a = logged_out_head_internal(html_handler);
+            let a = logged_out_head_internal(html_handler)?;
         } => a else {
-            let b = logged_in_head_internal(html_handler, login_response.unwrap().id);
+            let b = logged_in_head_internal(
+                html_handler,
+                login_response.ok_or(TucanError::LoginRequired)?.id
+            )?;
         } => b;
     }
-    (html_handler, result)
+    Ok((html_handler, result))
 }
 
-#[must_use]
 pub fn footer<'a>(
     html_handler: InElement<'a, InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>>,
     _id: u64,
     _subid: u64,
-) -> InRoot<'a, Root<'a>> {
+) -> Result<InRoot<'a, Root<'a>>, TucanError> {
     html_extractor::html! {
+                    #![tolerant]
                     <div id="pageFoot" class="pageElementTop">
                         <div id="pageFootControls" class="pageElementTop">
                             <div id="pageFootControlsLeft">
                                 <a
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^\\?APPNAME=CampusNet&PRGNAME=EXTERNALPAGES&\
@@ -1223,7 +1253,7 @@ pub fn footer<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                     class="img img_arrowImprint pageElementLeft"
                                     id="pageFootControl_imp"
@@ -1231,7 +1261,7 @@ pub fn footer<'a>(
                                     "Impressum"
                                 </a>
                                 <a
-                                    href={|v: String| {
+                                    href={|v: &str| {
                                         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
                                             Regex::new(
                                                 "^\\?APPNAME=CampusNet&PRGNAME=EXTERNALPAGES&\
@@ -1239,7 +1269,7 @@ pub fn footer<'a>(
                                             )
                                             .unwrap()
                                         });
-                                        assert!(REGEX.is_match(&v), "{v}");
+                                        REGEX.is_match(v)
                                     }}
                                     class="img img_arrowContact pageElementLeft"
                                     id="pageFootControl_con"
@@ -1275,5 +1305,72 @@ pub fn footer<'a>(
             </body>
         </html>
     };
-    html_handler
+    Ok(html_handler)
+}
+
+/// Parses `content` as the body of an otherwise empty page with `parser`, so
+/// tests can check the part of a page below the menu without the full page.
+#[cfg(test)]
+pub(crate) fn parse_fragment<R>(
+    content: &str,
+    parser: impl for<'a> FnOnce(
+        InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>,
+    ) -> Result<
+        (InElement<'a, InElement<'a, InRoot<'a, Root<'a>>>>, R),
+        TucanError,
+    >,
+) -> Result<R, TucanError> {
+    let document = html_handler::parse_document(&format!(
+        "<!DOCTYPE html><html><head></head><body>{content}</body></html>"
+    ));
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        <html>
+            <head>
+            </head>
+            <body>
+                let result = parser(html_handler)?;
+            </body>
+        </html>
+    }
+    html_handler.end_document()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use html_handler::{InElement, ParseError};
+    use tucan_types::TucanError;
+
+    use super::parse_fragment;
+
+    fn link<'a, T>(html_handler: InElement<'a, T>) -> Result<(InElement<'a, T>, ()), TucanError> {
+        html_extractor::html! {
+            <a href={|url: &str| url == "/expected"}>
+                "Link"
+            </a>
+        }
+        Ok((html_handler, ()))
+    }
+
+    #[test]
+    fn failed_check_is_parse_error_with_value() {
+        let result = parse_fragment(r#"<a href="/expected">Link</a>"#, |html_handler| {
+            link(html_handler)
+        });
+        assert!(result.is_ok(), "{result:?}");
+        let result = parse_fragment(r#"<a href="/other">Link</a>"#, |html_handler| {
+            link(html_handler)
+        });
+        let Err(TucanError::Parse { page: _, error }) = result else {
+            panic!("{result:?}");
+        };
+        let error = error
+            .downcast_ref::<ParseError>()
+            .expect("parse error from the template");
+        assert_eq!(error.found, r#""/other""#);
+        assert!(error.template.file().ends_with("head.rs"), "{error}");
+    }
 }
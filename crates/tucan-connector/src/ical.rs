@@ -0,0 +1,349 @@
+//! iCalendar ([RFC 5545](https://www.rfc-editor.org/rfc/rfc5545)) export of
+//! the appointments of all courses and exams of a semester.
+
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use regex::Regex;
+use time::{
+    Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time,
+    macros::{format_description, offset, time},
+};
+use tucan_types::{
+    LoginResponse, RevalidationStrategy, SemesterId, Tucan, TucanError,
+    coursedetails::{CourseDetailsResponse, Termin},
+    myexams::Exam,
+};
+
+const DOMAIN: &str = "tucan.tu-darmstadt.de";
+
+/// Europe/Berlin, referenced by all times in the calendar.
+const VTIMEZONE: &str = "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+TZNAME:CEST\r
+DTSTART:19700329T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+END:DAYLIGHT\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+TZNAME:CET\r
+DTSTART:19701025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+END:STANDARD\r
+END:VTIMEZONE\r
+";
+
+/// Fetches all courses and exams of the semester and exports them.
+pub async fn export_semester<T: Tucan + ?Sized>(
+    tucan: &T,
+    login_response: &LoginResponse,
+    revalidation_strategy: RevalidationStrategy,
+    semester: SemesterId,
+) -> Result<String, TucanError> {
+    let my_courses = tucan
+        .my_courses(login_response, revalidation_strategy, semester.clone())
+        .await?;
+    let mut courses = Vec::new();
+    for (_section, section_courses) in my_courses.sections {
+        for course in section_courses {
+            courses.push(
+                tucan
+                    .course_details(login_response, revalidation_strategy, course.url)
+                    .await?,
+            );
+        }
+    }
+    let my_exams = tucan
+        .my_exams(login_response, revalidation_strategy, semester)
+        .await?;
+    Ok(calendar(
+        &courses,
+        &my_exams.exams,
+        OffsetDateTime::now_utc(),
+    ))
+}
+
+/// Builds the calendar. Appointments without a parseable date are skipped.
+#[must_use]
+pub fn calendar(
+    courses: &[CourseDetailsResponse],
+    exams: &[Exam],
+    timestamp: OffsetDateTime,
+) -> String {
+    let dtstamp = timestamp
+        .to_offset(time::UtcOffset::UTC)
+        .format(format_description!(
+            "[year][month][day]T[hour][minute][second]Z"
+        ))
+        .unwrap();
+    // keyed by UID so courses listed in multiple sections appear only once
+    let mut events = BTreeMap::new();
+    for course in courses {
+        for termin in course.termine.iter().chain(&course.termine_kleingruppe) {
+            if let Some(event) = course_event(course, termin) {
+                events.insert(format!("{}@{DOMAIN}", termin.id), event);
+            }
+        }
+    }
+    for exam in exams {
+        if let Some(event) = exam_event(exam) {
+            events.insert(format!("exam-{}@{DOMAIN}", exam.id), event);
+        }
+    }
+
+    let mut output = String::new();
+    output.push_str("BEGIN:VCALENDAR\r\n");
+    output.push_str("VERSION:2.0\r\n");
+    output.push_str("PRODID:-//TUCaN Plus//Schedule Export//DE\r\n");
+    output.push_str("CALSCALE:GREGORIAN\r\n");
+    output.push_str(VTIMEZONE);
+    for (uid, event) in events {
+        output.push_str("BEGIN:VEVENT\r\n");
+        content_line(&mut output, "UID", &uid);
+        content_line(&mut output, "DTSTAMP", &dtstamp);
+        output.push_str(&event);
+        output.push_str("END:VEVENT\r\n");
+    }
+    output.push_str("END:VCALENDAR\r\n");
+    output
+}
+
+fn course_event(course: &CourseDetailsResponse, termin: &Termin) -> Option<String> {
+    let date = parse_date(&termin.date)?;
+    let mut event = String::new();
+    times(
+        &mut event,
+        date,
+        parse_time(&termin.time_start),
+        parse_time(&termin.time_end),
+    );
+    content_line(&mut event, "SUMMARY", &escape(&course.name));
+    let rooms = termin.rooms.iter().map(|room| room.name.as_str());
+    let location = rooms.collect::<Vec<_>>().join(", ");
+    if !location.is_empty() {
+        content_line(&mut event, "LOCATION", &escape(&location));
+    }
+    let instructors = termin.instructors.clone().unwrap_or_else(|| {
+        course
+            .instructors
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    });
+    if !instructors.is_empty() {
+        content_line(
+            &mut event,
+            "DESCRIPTION",
+            &escape(&format!("Lehrende: {instructors}")),
+        );
+    }
+    Some(event)
+}
+
+fn exam_event(exam: &Exam) -> Option<String> {
+    static TIME_RANGE_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(\d{1,2}:\d{2})\s*-\s*(\d{1,2}:\d{2})").unwrap());
+    let date = parse_date(&exam.date)?;
+    let (start, end) = TIME_RANGE_REGEX
+        .captures(&exam.date)
+        .map_or((None, None), |captures| {
+            (parse_time(&captures[1]), parse_time(&captures[2]))
+        });
+    let mut event = String::new();
+    times(&mut event, date, start, end);
+    content_line(
+        &mut event,
+        "SUMMARY",
+        &escape(&format!("{} {}", exam.pruefungsart, exam.name)),
+    );
+    if let Some(courses) = &exam.tuple_of_courses {
+        content_line(&mut event, "DESCRIPTION", &escape(courses));
+    }
+    Some(event)
+}
+
+/// Dates like `Mo, 14. Okt. 2024`, optionally followed by a time.
+pub(crate) fn parse_date(input: &str) -> Option<Date> {
+    static DATE_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(\d{1,2})\. ([A-Za-zä]{3,4})\.? (\d{4})").unwrap());
+    let captures = DATE_REGEX.captures(input)?;
+    let month = match &captures[2] {
+        "Jan" => Month::January,
+        "Feb" => Month::February,
+        "Mär" | "Mrz" => Month::March,
+        "Apr" => Month::April,
+        "Mai" => Month::May,
+        "Jun" | "Juni" => Month::June,
+        "Jul" | "Juli" => Month::July,
+        "Aug" => Month::August,
+        "Sep" | "Sept" => Month::September,
+        "Okt" => Month::October,
+        "Nov" => Month::November,
+        "Dez" => Month::December,
+        _ => return None,
+    };
+    Date::from_calendar_date(captures[3].parse().ok()?, month, captures[1].parse().ok()?).ok()
+}
+
+pub(crate) fn parse_time(input: &str) -> Option<Time> {
+    let (hour, minute) = input.trim().split_once(':')?;
+    Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, 0).ok()
+}
+
+/// The German local time `datetime`, summer time is from the last Sunday of
+/// March 02:00 to the last Sunday of October 03:00 like in [`VTIMEZONE`].
+pub(crate) fn assume_berlin(datetime: PrimitiveDateTime) -> OffsetDateTime {
+    let year = datetime.year();
+    let last_sunday = |month: Month| {
+        let last = Date::from_calendar_date(year, month, month.length(year)).unwrap();
+        last - Duration::days(last.weekday().number_days_from_sunday().into())
+    };
+    let summer_time = last_sunday(Month::March).with_time(time!(2:00)) <= datetime
+        && datetime < last_sunday(Month::October).with_time(time!(3:00));
+    datetime.assume_offset(if summer_time {
+        offset!(+2)
+    } else {
+        offset!(+1)
+    })
+}
+
+/// All-day event if the times are unknown.
+fn times(event: &mut String, date: Date, start: Option<Time>, end: Option<Time>) {
+    let format = format_description!("[year][month][day]T[hour][minute][second]");
+    if let (Some(start), Some(end)) = (start, end) {
+        let start = date.with_time(start).format(format).unwrap();
+        let end = date.with_time(end).format(format).unwrap();
+        content_line(event, "DTSTART;TZID=Europe/Berlin", &start);
+        content_line(event, "DTEND;TZID=Europe/Berlin", &end);
+    } else {
+        let format = format_description!("[year][month][day]");
+        let start = date.format(format).unwrap();
+        let end = date.next_day().unwrap().format(format).unwrap();
+        content_line(event, "DTSTART;VALUE=DATE", &start);
+        content_line(event, "DTEND;VALUE=DATE", &end);
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.trim()
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Writes `name:value` folded to lines of at most 75 octets.
+fn content_line(output: &mut String, name: &str, value: &str) {
+    let line = format!("{name}:{value}");
+    let mut length = 0;
+    for char in line.chars() {
+        if length + char.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(char);
+        length += char.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime, time};
+    use tucan_types::coursedetails::Room;
+
+    use super::*;
+
+    #[test]
+    fn parses_tucan_dates() {
+        assert_eq!(parse_date("Mo, 14. Okt. 2024"), Some(date!(2024 - 10 - 14)));
+        assert_eq!(parse_date("Fr, 2. Mai 2025"), Some(date!(2025 - 05 - 02)));
+        assert_eq!(
+            parse_date("Mi, 19. Mär. 2025 08:00-10:00"),
+            Some(date!(2025 - 03 - 19))
+        );
+        assert_eq!(parse_date("Kein Datum"), None);
+        assert_eq!(parse_time(" 13:30 "), Some(time!(13:30)));
+    }
+
+    #[test]
+    fn switches_to_summer_time() {
+        assert_eq!(
+            assume_berlin(datetime!(2025-03-30 01:59)),
+            datetime!(2025-03-30 00:59 UTC)
+        );
+        assert_eq!(
+            assume_berlin(datetime!(2025-03-30 03:00)),
+            datetime!(2025-03-30 01:00 UTC)
+        );
+        assert_eq!(
+            assume_berlin(datetime!(2025-10-26 02:59)),
+            datetime!(2025-10-26 00:59 UTC)
+        );
+        assert_eq!(
+            assume_berlin(datetime!(2025-10-26 03:00)),
+            datetime!(2025-10-26 02:00 UTC)
+        );
+    }
+
+    #[test]
+    fn exports_events_with_stable_uids() {
+        let course = CourseDetailsResponse {
+            id: "20-00-0004-iv".to_owned(),
+            name: "Funktionale und objektorientierte Programmierkonzepte".to_owned(),
+            material_and_messages_url: None,
+            r#type: "Integrierte Veranstaltung".to_owned(),
+            type_number: 0,
+            fachbereich: "Informatik".to_owned(),
+            anzeige_im_stundenplan: None,
+            courselevel: 0,
+            sws: None,
+            credits: None,
+            language: "Deutsch".to_owned(),
+            language_id: 0,
+            teilnehmer_min: None,
+            teilnehmer_max: None,
+            description: Vec::new(),
+            uebungsgruppen: Vec::new(),
+            course_anmeldefristen: Vec::new(),
+            enhalten_in_modulen: Vec::new(),
+            termine: vec![Termin {
+                id: "392837".to_owned(),
+                date: "Mo, 14. Okt. 2024".to_owned(),
+                time_start: "13:30".to_owned(),
+                time_end: "15:10".to_owned(),
+                rooms: vec![
+                    Room {
+                        name: "S1|01/A1".to_owned(),
+                        url: None,
+                    },
+                    Room {
+                        name: "S1|01/A2".to_owned(),
+                        url: None,
+                    },
+                ],
+                instructors: Some("Prof. Dr. Karsten Weihe".to_owned()),
+            }],
+            termine_kleingruppe: Vec::new(),
+            short_termine: Vec::new(),
+            instructors: Vec::new(),
+            plenumsveranstaltung_url: None,
+        };
+        let calendar = calendar(
+            &[course.clone(), course],
+            &[],
+            datetime!(2024-10-01 12:00 UTC),
+        );
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("UID:392837@tucan.tu-darmstadt.de\r\n"));
+        assert!(calendar.contains("DTSTAMP:20241001T120000Z\r\n"));
+        assert!(calendar.contains("DTSTART;TZID=Europe/Berlin:20241014T133000\r\n"));
+        assert!(calendar.contains("DTEND;TZID=Europe/Berlin:20241014T151000\r\n"));
+        assert!(calendar.contains("LOCATION:S1|01/A1\\, S1|01/A2\r\n"));
+        assert!(calendar.lines().all(|line| line.len() <= 75));
+    }
+}
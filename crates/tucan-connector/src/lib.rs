@@ -1,4 +1,5 @@
 use std::{
+    path::PathBuf,
     sync::{Arc, LazyLock},
     time::Duration,
 };
@@ -6,38 +7,53 @@ use std::{
 use data_encoding::BASE64URL_NOPAD;
 use externalpages::welcome::welcome;
 use html_handler::InElement;
-use login::logout;
+use login::{login, logout};
 use regex::Regex;
 use reqwest::header;
+use serde::{Serialize, de::DeserializeOwned};
 use sha3::{Digest as _, Sha3_256};
 use time::{Month, OffsetDateTime, format_description::well_known::Rfc2822, macros::offset};
-use tokio::sync::Semaphore;
-use tucan_plus_worker::{CacheRequest, MyDatabase, StoreCacheRequest, models::CacheEntry};
+use tokio::sync::{Mutex, Semaphore, broadcast};
+use tucan_plus_worker::{MyDatabase, cachekey::CacheKey};
 use tucan_types::{
-    CONCURRENCY, LoginResponse, RevalidationStrategy, SemesterId, Tucan, TucanError,
+    CONCURRENCY, LoginRequest, LoginResponse, RevalidationStrategy, SemesterId, Tucan, TucanError,
+    courseprep::{CoursePrepRequest, CoursePrepResponse},
     courseresults::ModuleResultsResponse,
+    coursesearch::{CourseSearchRequest, CourseSearchResponse},
+    examdetails::{ExamDetailsRequest, ExamDetailsResponse},
     examregistration::ExamRegistrationResponse,
     examresults::ExamResultsResponse,
+    gradechanges::{GradeChange, GradeChanges},
     gradeoverview::{GradeOverviewRequest, GradeOverviewResponse},
+    messages::{MessageDetailResponse, MessageRequest, MessagesResponse},
     mlsstart::MlsStart,
+    month::{MonthRequest, MonthResponse},
     mycourses::MyCoursesResponse,
     mydocuments::MyDocumentsResponse,
     myexams::MyExamsResponse,
     mymodules::MyModulesResponse,
+    registration::{RegistrationRequest, RegistrationResponse},
+    rooms::{RoomDetailsResponse, RoomRequest},
     student_result::StudentResultResponse,
     vv::{ActionRequest, Vorlesungsverzeichnis},
 };
 use vv::vv;
 
+pub mod cache;
 pub mod coursedetails;
 pub mod courseprep;
 pub mod courseresults;
+pub mod coursesearch;
+pub mod examdetails;
 pub mod examregistration;
 pub mod examresults;
 pub mod externalpages;
+pub mod form;
 pub mod gradeoverview;
 pub mod head;
+pub mod ical;
 pub mod login;
+pub mod messages;
 pub mod mlsstart;
 pub mod moduledetails;
 pub mod month;
@@ -46,10 +62,15 @@ pub mod mydocuments;
 pub mod myexams;
 pub mod mymodules;
 pub mod registration;
+pub mod revalidate;
+pub mod rooms;
 pub mod root;
+pub mod snapshot;
 pub mod startpage_dispatch;
 pub mod student_result;
+pub mod sync;
 pub mod vv;
+pub mod watcher;
 
 pub type InElement5<'a, T> =
     InElement<'a, InElement<'a, InElement<'a, InElement<'a, InElement<'a, T>>>>>;
@@ -58,58 +79,176 @@ pub fn h(input: &str) -> String {
     BASE64URL_NOPAD.encode(&Sha3_256::digest(input))
 }
 
-pub async fn fetch_with_cache<Request, Response>(
+pub async fn fetch_with_cache<Request, Response: PartialEq + Serialize + DeserializeOwned>(
     tucan: &TucanConnector,
     login_response: &LoginResponse,
     revalidation_strategy: RevalidationStrategy,
     request: &Request,
-    key: String,
-    url: String,
+    cache_key: CacheKey,
+    url: impl Fn(&LoginResponse) -> String,
     parser: fn(&LoginResponse, &str, &Request) -> Result<Response, TucanError>,
 ) -> Result<Response, TucanError> {
-    let old_content_and_date = tucan
-        .database
-        .send_message(CacheRequest { key: key.clone() })
-        .await;
+    fetch_with_cache_and_diff(
+        tucan,
+        revalidation_strategy,
+        cache_key,
+        |content| parser(login_response, content, request),
+        async |key| {
+            renewing_fetch_and_parse(tucan, login_response, key, url, request, parser).await
+        },
+        None,
+    )
+    .await
+}
+
+/// Called with the old and the new response of a page that changed.
+type OnChange<'a, Response> = &'a (dyn Fn(&Response, &Response) + Sync);
+
+/// Like [`fetch_with_cache`], but for any page that `fetch` gets from the
+/// network, e.g. with or without a session. `parser` reads the cached page.
+/// Calls `changed` with the previously cached response if the one fetched
+/// from the network differs from it.
+pub(crate) async fn fetch_with_cache_and_diff<
+    Response: PartialEq + Serialize + DeserializeOwned,
+>(
+    tucan: &TucanConnector,
+    revalidation_strategy: RevalidationStrategy,
+    cache_key: CacheKey,
+    parser: impl Fn(&str) -> Result<Response, TucanError>,
+    fetch: impl AsyncFnOnce(&str) -> Result<(String, OffsetDateTime, Response), TucanError>,
+    changed: Option<OnChange<'_, Response>>,
+) -> Result<Response, TucanError> {
+    let key = cache_key.to_string();
+    if let Some(directory) = &tucan.replay_directory {
+        let content = snapshot::read_page(directory, &key)?;
+        return parser(&content).map_err(|error| error.in_page(&key));
+    }
+
+    let old = read_cache(tucan, cache_key.clone()).await;
     if revalidation_strategy.max_age != 0 {
-        if let Some(CacheEntry {
-            key: _,
-            value: content,
-            updated: date,
-        }) = &old_content_and_date
-        {
+        if let Some(page) = &old {
             // probably here
-            if OffsetDateTime::now_utc() - *date
+            if OffsetDateTime::now_utc() - page.updated
                 < time::Duration::seconds(revalidation_strategy.max_age)
             {
-                return parser(login_response, content, request);
+                record_cache_age(page.updated);
+                match parse_cached(tucan, cache_key.clone(), page, &parser).await {
+                    // the page could not be read, so it is fetched again
+                    Err(TucanError::NotCached) => {}
+                    result => return result,
+                }
             }
         }
     }
 
-    let Some(invalidate_dependents) = revalidation_strategy.invalidate_dependents else {
+    if !revalidation_strategy.network {
         return Err(TucanError::NotCached);
-    };
+    }
 
-    let (content, date) =
-        authenticated_retryable_get(tucan, &url, &login_response.cookie_cnsc).await?;
-    let result = parser(login_response, &content, request)?;
-    if invalidate_dependents && old_content_and_date.as_ref().map(|m| &m.key) != Some(&content) {
-        // TODO invalidate cached ones?
+    let (content, date, result) = fetch(&key).await?;
+    let hash = h(&content);
+    // the pages contain the session, so the parsed responses are compared
+    if old.as_ref().map(|old| &old.hash) != Some(&hash) {
+        let previous = match &old {
+            Some(old) => parse_cached(tucan, cache_key.clone(), old, &parser)
+                .await
+                .ok(),
+            None => None,
+        };
+        if previous.as_ref() != Some(&result) {
+            record_change();
+            // nothing to compare against on the first fetch or if the current
+            // parser fails on the old page
+            if let (Some(previous), Some(changed)) = (&previous, changed) {
+                changed(previous, &result);
+            }
+        }
     }
 
-    tucan
-        .database
-        .send_message(StoreCacheRequest(CacheEntry {
-            key,
-            value: content,
-            updated: date,
-        }))
-        .await;
+    write_cache(tucan, cache_key, old.as_ref(), &hash, &content, date).await;
+    write_parsed(tucan, key, hash, &result).await;
 
     Ok(result)
 }
 
+/// Like [`fetch_with_cache`], but publishes the grades that changed compared
+/// to the cached response.
+async fn fetch_with_grade_changes<
+    Request,
+    Response: GradeChanges + PartialEq + Serialize + DeserializeOwned,
+>(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    revalidation_strategy: RevalidationStrategy,
+    request: &Request,
+    key: CacheKey,
+    url: impl Fn(&LoginResponse) -> String,
+    parser: fn(&LoginResponse, &str, &Request) -> Result<Response, TucanError>,
+) -> Result<Response, TucanError> {
+    fetch_with_cache_and_diff(
+        tucan,
+        revalidation_strategy,
+        key,
+        |content| parser(login_response, content, request),
+        async |key| {
+            renewing_fetch_and_parse(tucan, login_response, key, url, request, parser).await
+        },
+        Some(&|old: &Response, new: &Response| {
+            for change in new.grade_changes(old) {
+                // no one listening is fine
+                let _ = tucan.grade_changes.send(change);
+            }
+        }),
+    )
+    .await
+}
+
+/// Retries once with a fresh session if the session timed out and the
+/// connector has credentials.
+async fn renewing_fetch_and_parse<Request, Response>(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    key: &str,
+    url: impl Fn(&LoginResponse) -> String,
+    request: &Request,
+    parser: fn(&LoginResponse, &str, &Request) -> Result<Response, TucanError>,
+) -> Result<(String, OffsetDateTime, Response), TucanError> {
+    let login_response = tucan.current_session(login_response).await;
+    match fetch_and_parse(tucan, &login_response, key, &url, request, parser).await {
+        Err(TucanError::Timeout) if tucan.credentials.is_some() => {
+            let login_response = tucan.renew_session(&login_response).await?;
+            fetch_and_parse(tucan, &login_response, key, &url, request, parser).await
+        }
+        result => result,
+    }
+}
+
+async fn fetch_and_parse<Request, Response>(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    key: &str,
+    url: impl Fn(&LoginResponse) -> String,
+    request: &Request,
+    parser: fn(&LoginResponse, &str, &Request) -> Result<Response, TucanError>,
+) -> Result<(String, OffsetDateTime, Response), TucanError> {
+    let url = url(login_response);
+    let (content, date) =
+        authenticated_retryable_get(tucan, &url, &login_response.cookie_cnsc).await?;
+    // before parsing so pages that break a template are kept too
+    if let Some(directory) = &tucan.record_directory {
+        snapshot::record_page(
+            directory,
+            key,
+            &url,
+            date,
+            &content,
+            Some(login_response.id),
+        )?;
+    }
+    let result = parser(login_response, &content, request).map_err(|error| error.in_page(key))?;
+    Ok((content, date, result))
+}
+
 #[cfg(target_arch = "wasm32")]
 pub async fn sleep(duration: Duration) {
     use fragile::Fragile;
@@ -137,12 +276,27 @@ pub async fn sleep(duration: Duration) {
 use tokio::time::sleep;
 
 use crate::{
-    coursedetails::course_details_internal, courseresults::course_results_internal,
-    examregistration::exam_registration_internal, examresults::exam_results_internal,
-    gradeoverview::gradeoverview_internal, mlsstart::after_login_internal,
-    moduledetails::module_details_internal, mycourses::my_courses_internal,
-    mydocuments::my_documents_internal, myexams::my_exams_internal, mymodules::my_modules_internal,
-    registration::anmeldung_internal, student_result::student_result_internal,
+    cache::{parse_cached, read_cache, write_cache, write_parsed},
+    coursedetails::course_details_internal,
+    courseprep::course_prep_internal,
+    courseresults::course_results_internal,
+    coursesearch::search_courses,
+    examdetails::exam_details_internal,
+    examregistration::exam_registration_internal,
+    examresults::exam_results_internal,
+    gradeoverview::gradeoverview_internal,
+    messages::{delete_message, message_detail_internal, messages_internal},
+    mlsstart::after_login_internal,
+    moduledetails::module_details_internal,
+    month::month_internal,
+    mycourses::my_courses_internal,
+    mydocuments::my_documents_internal,
+    myexams::my_exams_internal,
+    mymodules::my_modules_internal,
+    registration::{anmeldung_internal, registration_action},
+    revalidate::{record_cache_age, record_change},
+    rooms::room_details_internal,
+    student_result::student_result_internal,
 };
 
 static COURSEDETAILS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
@@ -154,11 +308,46 @@ static COURSEDETAILS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 
 type MyClient = reqwest::Client;
 
+const GRADE_CHANGES_CAPACITY: usize = 64;
+
 #[cfg_attr(not(target_arch = "wasm32"), derive(Clone))]
 pub struct TucanConnector {
     pub client: MyClient,
     pub database: MyDatabase,
     semaphore: Arc<Semaphore>,
+    credentials: Option<LoginRequest>,
+    session: Arc<Mutex<Option<SessionRenewal>>>,
+    replay_directory: Option<PathBuf>,
+    record_directory: Option<PathBuf>,
+    grade_changes: broadcast::Sender<GradeChange>,
+}
+
+/// The session obtained by the last automatic re-login and the ids of the
+/// sessions it replaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SessionRenewal {
+    session: LoginResponse,
+    replaced: Vec<u64>,
+}
+
+impl SessionRenewal {
+    /// The renewed session if `login_response` is one it replaced, so a
+    /// logout or a login as someone else is not overridden.
+    fn substitute(&self, login_response: &LoginResponse) -> Option<LoginResponse> {
+        self.replaced
+            .contains(&login_response.id)
+            .then(|| self.session.clone())
+    }
+
+    fn renewed(previous: Option<Self>, expired: &LoginResponse, session: LoginResponse) -> Self {
+        let mut replaced = previous.map_or_else(Vec::new, |previous| {
+            let mut replaced = previous.replaced;
+            replaced.push(previous.session.id);
+            replaced
+        });
+        replaced.push(expired.id);
+        Self { session, replaced }
+    }
 }
 
 /// `TUCaN` being unreliable is a feature
@@ -188,6 +377,45 @@ pub async fn retryable_get(
         i += 1;
     }
 }
+/// A single request for actions like registering that must not be repeated
+/// when only the response got lost.
+pub async fn authenticated_get(
+    connector: &TucanConnector,
+    url: &str,
+    cookie_cnsc: &str,
+) -> Result<(String, OffsetDateTime), TucanError> {
+    let _permit = connector.semaphore.acquire().await.unwrap();
+    let response = connector
+        .client
+        .get(url)
+        .header("Cookie", format!("cnsc={cookie_cnsc}"))
+        .send()
+        .await?
+        .error_for_status()?;
+    let date = &response.headers()["Date"];
+    let date = OffsetDateTime::parse(date.to_str().unwrap(), &Rfc2822).unwrap();
+    Ok((response.text().await?, date))
+}
+
+/// Submits `form` once, see [`authenticated_get`].
+pub async fn authenticated_post(
+    connector: &TucanConnector,
+    form: &form::Form,
+    cookie_cnsc: &str,
+) -> Result<String, TucanError> {
+    let _permit = connector.semaphore.acquire().await.unwrap();
+    Ok(connector
+        .client
+        .post(form.url())
+        .header("Cookie", format!("cnsc={cookie_cnsc}"))
+        .form(&form.fields)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
 pub async fn authenticated_retryable_get(
     connector: &TucanConnector,
     url: &str,
@@ -195,21 +423,7 @@ pub async fn authenticated_retryable_get(
 ) -> Result<(String, OffsetDateTime), TucanError> {
     let mut i = 0;
     loop {
-        let permit = connector.semaphore.acquire().await.unwrap();
-        let result = async {
-            let response = connector
-                .client
-                .get(url)
-                .header("Cookie", format!("cnsc={cookie_cnsc}"))
-                .send()
-                .await?
-                .error_for_status()?;
-            let date = &response.headers()["Date"];
-            let date = OffsetDateTime::parse(date.to_str().unwrap(), &Rfc2822).unwrap();
-            Ok((response.text().await?, date))
-        }
-        .await;
-        drop(permit);
+        let result = authenticated_get(connector, url, cookie_cnsc).await;
         if i == 4 {
             return result;
         }
@@ -241,6 +455,11 @@ impl TucanConnector {
             client,
             database,
             semaphore: Arc::new(Semaphore::new(CONCURRENCY)),
+            credentials: None,
+            session: Arc::default(),
+            replay_directory: None,
+            record_directory: None,
+            grade_changes: broadcast::channel(GRADE_CHANGES_CAPACITY).0,
         })
     }
 
@@ -253,8 +472,73 @@ impl TucanConnector {
             client,
             database,
             semaphore,
+            credentials: None,
+            session: Arc::default(),
+            replay_directory: None,
+            record_directory: None,
+            grade_changes: broadcast::channel(GRADE_CHANGES_CAPACITY).0,
         })
     }
+
+    /// Log in again with these credentials and retry once when the session
+    /// times out.
+    #[must_use]
+    pub fn with_credentials(mut self, credentials: LoginRequest) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Serve pages from a directory of snapshots instead of the network. See
+    /// [`snapshot`].
+    #[must_use]
+    pub fn with_replay(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.replay_directory = Some(directory.into());
+        self
+    }
+
+    /// Additionally write every fetched page to a directory. See [`snapshot`].
+    #[must_use]
+    pub fn with_recording(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.record_directory = Some(directory.into());
+        self
+    }
+
+    /// Grades that changed when exam or course results were fetched again.
+    #[must_use]
+    pub fn subscribe_grade_changes(&self) -> broadcast::Receiver<GradeChange> {
+        self.grade_changes.subscribe()
+    }
+
+    async fn current_session(&self, login_response: &LoginResponse) -> LoginResponse {
+        self.session
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|renewal| renewal.substitute(login_response))
+            .unwrap_or_else(|| login_response.clone())
+    }
+
+    async fn renew_session(&self, expired: &LoginResponse) -> Result<LoginResponse, TucanError> {
+        let Some(credentials) = &self.credentials else {
+            return Err(TucanError::Timeout);
+        };
+        let mut session = self.session.lock().await;
+        // another request may have renewed the session while we were waiting
+        if let Some(current) = session
+            .as_ref()
+            .and_then(|renewal| renewal.substitute(expired))
+            .filter(|current| current != expired)
+        {
+            return Ok(current);
+        }
+        let login_response = login(self, credentials).await?;
+        *session = Some(SessionRenewal::renewed(
+            session.take(),
+            expired,
+            login_response.clone(),
+        ));
+        Ok(login_response)
+    }
 }
 
 impl Tucan for TucanConnector {
@@ -262,6 +546,10 @@ impl Tucan for TucanConnector {
         welcome(self).await
     }
 
+    async fn login(&self, request: LoginRequest) -> Result<LoginResponse, TucanError> {
+        login(self, &request).await
+    }
+
     async fn after_login(
         &self,
         login_response: &tucan_types::LoginResponse,
@@ -271,11 +559,16 @@ impl Tucan for TucanConnector {
         let datetime = time::OffsetDateTime::now_utc();
         let datetime = datetime.to_offset(offset!(+2));
         let date = datetime.date();
-        let key = format!("unparsed_mlsstart.{}.{}", date, login_response.id);
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MLSSTART&ARGUMENTS=-N{},-N000019,",
-            login_response.id
-        );
+        let key = CacheKey::MlsStart {
+            date,
+            session_id: login_response.id,
+        };
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MLSSTART&ARGUMENTS=-N{},-N000019,",
+                login_response.id
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -298,18 +591,20 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<MyModulesResponse, TucanError> {
-        let key = format!("unparsed_mymodules.{}", semester.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MYMODULES&ARGUMENTS=-N{:015},-N000275,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                "-N1337".to_owned() // DO NOT ASK
-            } else if semester == SemesterId::all() {
-                "-N999".to_owned()
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
+        let key = CacheKey::MyModules(semester.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MYMODULES&ARGUMENTS=-N{:015},-N000275,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    "-N1337".to_owned() // DO NOT ASK
+                } else if semester == SemesterId::all() {
+                    "-N999".to_owned()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -328,18 +623,20 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<MyCoursesResponse, TucanError> {
-        let key = format!("unparsed_mycourses.{}", semester.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=PROFCOURSES&ARGUMENTS=-N{:015},-N000274,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                "-N1337".to_owned() // DO NOT ASK
-            } else if semester == SemesterId::all() {
-                "-N999".to_owned()
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
+        let key = CacheKey::MyCourses(semester.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=PROFCOURSES&ARGUMENTS=-N{:015},-N000274,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    "-N1337".to_owned() // DO NOT ASK
+                } else if semester == SemesterId::all() {
+                    "-N999".to_owned()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -358,18 +655,20 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<MyExamsResponse, TucanError> {
-        let key = format!("unparsed_myexams.{}", semester.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MYEXAMS&ARGUMENTS=-N{:015},-N000318,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                String::new()
-            } else if semester == SemesterId::all() {
-                "-N999".to_owned()
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
+        let key = CacheKey::MyExams(semester.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MYEXAMS&ARGUMENTS=-N{:015},-N000318,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    String::new()
+                } else if semester == SemesterId::all() {
+                    "-N999".to_owned()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -388,18 +687,20 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<ExamRegistrationResponse, TucanError> {
-        let key = format!("unparsed_examregistration.{}", semester.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=EXAMREGISTRATION&ARGUMENTS=-N{:015},-N000318,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                String::new()
-            } else if semester == SemesterId::all() {
-                "-N999".to_owned()
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
+        let key = CacheKey::ExamRegistration(semester.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=EXAMREGISTRATION&ARGUMENTS=-N{:015},-N000318,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    String::new()
+                } else if semester == SemesterId::all() {
+                    "-N999".to_owned()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -418,20 +719,22 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<ExamResultsResponse, TucanError> {
-        let key = format!("unparsed_examresults.{}", semester.inner());
-
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=EXAMRESULTS&ARGUMENTS=-N{:015},-N000325,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                String::new()
-            } else if semester == SemesterId::all() {
-                "-N999".to_owned()
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
-        fetch_with_cache(
+        let key = CacheKey::ExamResults(semester.clone());
+
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=EXAMRESULTS&ARGUMENTS=-N{:015},-N000325,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    String::new()
+                } else if semester == SemesterId::all() {
+                    "-N999".to_owned()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
+        fetch_with_grade_changes(
             self,
             login_response,
             revalidation_strategy,
@@ -449,19 +752,22 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         semester: SemesterId,
     ) -> Result<ModuleResultsResponse, TucanError> {
-        let key = format!("unparsed_courseresults.{}", semester.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=COURSERESULTS&ARGUMENTS=-N{:015},-N000324,{}",
-            login_response.id,
-            if semester == SemesterId::current() {
-                String::new()
-            } else if semester == SemesterId::all() {
-                panic!("not supported")
-            } else {
-                format!("-N{}", semester.inner())
-            }
-        );
-        fetch_with_cache(
+        if semester == SemesterId::all() {
+            return Err(TucanError::Unsupported("course results of all semesters"));
+        }
+        let key = CacheKey::CourseResults(semester.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=COURSERESULTS&ARGUMENTS=-N{:015},-N000324,{}",
+                login_response.id,
+                if semester == SemesterId::current() {
+                    String::new()
+                } else {
+                    format!("-N{}", semester.inner())
+                }
+            )
+        };
+        fetch_with_grade_changes(
             self,
             login_response,
             revalidation_strategy,
@@ -478,11 +784,13 @@ impl Tucan for TucanConnector {
         login_response: &tucan_types::LoginResponse,
         revalidation_strategy: RevalidationStrategy,
     ) -> Result<MyDocumentsResponse, TucanError> {
-        let key = "unparsed_mydocuments".to_string();
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=CREATEDOCUMENT&ARGUMENTS=-N{:015},-N000557,",
-            login_response.id
-        );
+        let key = CacheKey::MyDocuments;
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=CREATEDOCUMENT&ARGUMENTS=-N{:015},-N000557,",
+                login_response.id
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -495,6 +803,54 @@ impl Tucan for TucanConnector {
         .await
     }
 
+    async fn messages(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MessageRequest,
+    ) -> Result<MessagesResponse, TucanError> {
+        let key = CacheKey::Inbox(request.clone());
+        let url = |login_response: &LoginResponse| request.url(login_response.id);
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &(),
+            key,
+            url,
+            messages_internal,
+        )
+        .await
+    }
+
+    async fn message_detail(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MessageRequest,
+    ) -> Result<MessageDetailResponse, TucanError> {
+        let key = CacheKey::Message(request.clone());
+        let url = |login_response: &LoginResponse| request.url(login_response.id);
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &(),
+            key,
+            url,
+            message_detail_internal,
+        )
+        .await
+    }
+
+    async fn delete_message(
+        &self,
+        login_response: &LoginResponse,
+        request: MessageRequest,
+    ) -> Result<(), TucanError> {
+        delete_message(self, login_response, request).await
+    }
+
     async fn anmeldung(
         &self,
         login_response: &tucan_types::LoginResponse,
@@ -506,16 +862,17 @@ impl Tucan for TucanConnector {
         let datetime = datetime.to_offset(offset!(+2));
         let date = datetime.date();
         let registration_sose = Month::March <= date.month() && date.month() <= Month::August;
-        let key = format!(
-            "unparsed_anmeldung.{}.{}",
-            if registration_sose { "sose" } else { "wise" },
-            request.inner()
-        );
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N{:015},-N000311,{}",
-            login_response.id,
-            request.inner()
-        );
+        let key = CacheKey::Anmeldung {
+            summer_semester: registration_sose,
+            request: request.clone(),
+        };
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N{:015},-N000311,{}",
+                login_response.id,
+                request.inner()
+            )
+        };
         /* let keys: Vec<String> = result
             .entries
             .iter()
@@ -544,18 +901,36 @@ impl Tucan for TucanConnector {
         future.await
     }
 
+    async fn register(
+        &self,
+        login_response: &LoginResponse,
+        request: RegistrationRequest,
+    ) -> Result<RegistrationResponse, TucanError> {
+        registration_action(self, login_response, request, true).await
+    }
+
+    async fn unregister(
+        &self,
+        login_response: &LoginResponse,
+        request: RegistrationRequest,
+    ) -> Result<RegistrationResponse, TucanError> {
+        registration_action(self, login_response, request, false).await
+    }
+
     async fn module_details(
         &self,
         login_response: &tucan_types::LoginResponse,
         revalidation_strategy: RevalidationStrategy,
         request: tucan_types::moduledetails::ModuleDetailsRequest,
     ) -> Result<tucan_types::moduledetails::ModuleDetailsResponse, TucanError> {
-        let key = format!("unparsed_module_details.{}", request.inner());
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MODULEDETAILS&ARGUMENTS=-N{:015},-N000311,{}",
-            login_response.id,
-            request.inner()
-        );
+        let key = CacheKey::ModuleDetails(request.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MODULEDETAILS&ARGUMENTS=-N{:015},-N000311,{}",
+                login_response.id,
+                request.inner()
+            )
+        };
 
         fetch_with_cache(
             self,
@@ -575,13 +950,78 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         request: tucan_types::coursedetails::CourseDetailsRequest,
     ) -> Result<tucan_types::coursedetails::CourseDetailsResponse, TucanError> {
-        fetch_with_cache(self, login_response, revalidation_strategy, &request, format!("unparsed_course_details.{}", request.inner()), format!(
+        fetch_with_cache(self, login_response, revalidation_strategy, &request, CacheKey::CourseDetails(request.clone()), |login_response: &LoginResponse| format!(
             "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=COURSEDETAILS&ARGUMENTS=-N{:015},-N000311,{}",
             login_response.id,
             request.inner()
         ), course_details_internal).await
     }
 
+    async fn course_prep(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: CoursePrepRequest,
+    ) -> Result<CoursePrepResponse, TucanError> {
+        let key = CacheKey::CoursePrep(request.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=COURSEPREP&ARGUMENTS=-N{:015},-N000271,{request}",
+                login_response.id
+            )
+        };
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &request,
+            key,
+            url,
+            course_prep_internal,
+        )
+        .await
+    }
+
+    async fn exam_details(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: ExamDetailsRequest,
+    ) -> Result<ExamDetailsResponse, TucanError> {
+        let key = CacheKey::ExamDetails(request.clone());
+        let url = |login_response: &LoginResponse| request.url(login_response.id);
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &(),
+            key,
+            url,
+            exam_details_internal,
+        )
+        .await
+    }
+
+    async fn room_details(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: RoomRequest,
+    ) -> Result<RoomDetailsResponse, TucanError> {
+        let key = CacheKey::Room(request.clone());
+        let url = |login_response: &LoginResponse| request.url(login_response.id);
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &(),
+            key,
+            url,
+            room_details_internal,
+        )
+        .await
+    }
+
     async fn vv(
         &self,
         login_response: Option<&tucan_types::LoginResponse>,
@@ -591,6 +1031,14 @@ impl Tucan for TucanConnector {
         vv(self, login_response, revalidation_strategy, request).await
     }
 
+    async fn search_courses(
+        &self,
+        login_response: Option<&LoginResponse>,
+        request: CourseSearchRequest,
+    ) -> Result<CourseSearchResponse, TucanError> {
+        search_courses(self, login_response, &request).await
+    }
+
     async fn student_result(
         &self,
         login_response: &LoginResponse,
@@ -598,16 +1046,18 @@ impl Tucan for TucanConnector {
         request: u64,
     ) -> Result<StudentResultResponse, TucanError> {
         // 0 is the default
-        let key = format!("unparsed_student_result.{request}");
+        let key = CacheKey::StudentResult(request);
 
         // TODO FIXME this can break as the normal tucan usage will remember which one
         // you selected
         let request =
             format!("-N0,-N000000000000000,-N000000000000000,-N{request},-N0,-N000000000000000");
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=STUDENT_RESULT&ARGUMENTS=-N{:015},-N000316,{}",
-            login_response.id, request
-        );
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=STUDENT_RESULT&ARGUMENTS=-N{:015},-N000316,{}",
+                login_response.id, request
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -626,11 +1076,13 @@ impl Tucan for TucanConnector {
         revalidation_strategy: RevalidationStrategy,
         request: GradeOverviewRequest,
     ) -> Result<GradeOverviewResponse, TucanError> {
-        let key = format!("unparsed_gradeoverview.{request}");
-        let url = format!(
-            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=GRADEOVERVIEW&ARGUMENTS=-N{},-N000325,{request}",
-            login_response.id
-        );
+        let key = CacheKey::GradeOverview(request.clone());
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=GRADEOVERVIEW&ARGUMENTS=-N{},-N000325,{request}",
+                login_response.id
+            )
+        };
         fetch_with_cache(
             self,
             login_response,
@@ -642,6 +1094,31 @@ impl Tucan for TucanConnector {
         )
         .await
     }
+
+    async fn month(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MonthRequest,
+    ) -> Result<MonthResponse, TucanError> {
+        let key = CacheKey::Month(request);
+        let url = |login_response: &LoginResponse| {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MONTH&ARGUMENTS=-N{:015},-N000271,-A01.{request},-A,-N000000000000000",
+                login_response.id
+            )
+        };
+        fetch_with_cache(
+            self,
+            login_response,
+            revalidation_strategy,
+            &(),
+            key,
+            url,
+            month_internal,
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -652,15 +1129,32 @@ mod tests {
     use tokio::sync::{OnceCell, Semaphore};
     use tucan_plus_worker::MyDatabase;
     use tucan_types::{
-        LoginResponse, RevalidationStrategy, coursedetails::CourseDetailsRequest,
-        moduledetails::ModuleDetailsRequest,
+        LoginRequest, LoginResponse, RevalidationStrategy, TucanError,
+        coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest,
     };
 
     use crate::{
-        Tucan, TucanConnector, externalpages::welcome::welcome, root::root,
+        SessionRenewal, Tucan, TucanConnector, externalpages::welcome::welcome, root::root,
         startpage_dispatch::one::startpage_dispatch_1,
     };
 
+    #[test]
+    fn renewed_session_only_replaces_expired_ones() {
+        let session = |id| LoginResponse {
+            id,
+            cookie_cnsc: format!("cookie{id}"),
+        };
+        let renewal = SessionRenewal::renewed(None, &session(1), session(2));
+        assert_eq!(renewal.substitute(&session(1)), Some(session(2)));
+        assert_eq!(renewal.substitute(&session(2)), None);
+        // e.g. after logging in as someone else
+        assert_eq!(renewal.substitute(&session(3)), None);
+        let renewal = SessionRenewal::renewed(Some(renewal), &session(2), session(4));
+        assert_eq!(renewal.substitute(&session(1)), Some(session(4)));
+        assert_eq!(renewal.substitute(&session(2)), Some(session(4)));
+        assert_eq!(renewal.substitute(&session(4)), None);
+    }
+
     static ONCE_CONNECTOR: OnceCell<(Client, Arc<Semaphore>)> = OnceCell::const_new();
 
     pub async fn get_tucan_connector() -> TucanConnector {
@@ -711,6 +1205,19 @@ mod tests {
         startpage_dispatch_1(&tucan).await.unwrap();
     }
 
+    #[tokio::test]
+
+    pub async fn test_login_invalid_credentials() {
+        let tucan = get_tucan_connector().await;
+        let result = tucan
+            .login(LoginRequest {
+                username: "nonexistent".to_owned(),
+                password: "wrong".to_owned(),
+            })
+            .await;
+        assert!(matches!(result, Err(TucanError::InvalidCredentials)));
+    }
+
     /// /scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=STARTPAGE_DISPATCH&
     /// ARGUMENTS=-N000000000000001 redirects to
     /// /scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=EXTERNALPAGES&
@@ -884,15 +1391,23 @@ mod authenticated_tests {
     async fn get_login_session() -> &'static LoginResponse {
         ONCE.get_or_init(|| async {
             let tucan = get_tucan_connector().await;
-            let (Ok(session_id), Ok(session_key)) =
+            if let (Ok(session_id), Ok(session_key)) =
                 (std::env::var("SESSION_ID"), std::env::var("SESSION_KEY"))
-            else {
-                panic!();
-            };
-            LoginResponse {
-                id: session_id.parse().unwrap(),
-                cookie_cnsc: session_key,
+            {
+                return LoginResponse {
+                    id: session_id.parse().unwrap(),
+                    cookie_cnsc: session_key,
+                };
             }
+            tucan
+                .login(LoginRequest {
+                    username: std::env::var("TUCAN_USERNAME")
+                        .expect("env variable TUCAN_USERNAME missing"),
+                    password: std::env::var("TUCAN_PASSWORD")
+                        .expect("env variable TUCAN_PASSWORD missing"),
+                })
+                .await
+                .unwrap()
         })
         .await
     }
@@ -1,7 +1,77 @@
+use std::sync::LazyLock;
 
-use tucan_types::LoginResponse;
+use regex::Regex;
+use reqwest::header;
+use tucan_types::{LoginRequest, LoginResponse};
 
-use crate::{TucanConnector, TucanError, authenticated_retryable_get};
+use crate::{
+    TucanConnector, TucanError, authenticated_retryable_get,
+    startpage_dispatch::after_login::redirect_after_login,
+};
+
+static REFRESH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        "^0; URL=/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=STARTPAGE_DISPATCH&ARGUMENTS=-N(?P<id>\\d+),-N000019,-N000000000000000$",
+    )
+    .unwrap()
+});
+
+/// Not retried, so a wrong password can't lock the account.
+pub async fn login(
+    connector: &TucanConnector,
+    login_request: &LoginRequest,
+) -> Result<LoginResponse, TucanError> {
+    let permit = connector.semaphore.acquire().await.unwrap();
+    let response = connector
+        .client
+        .post("https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll")
+        .form(&[
+            ("usrname", login_request.username.as_str()),
+            ("pass", login_request.password.as_str()),
+            ("APPNAME", "CampusNet"),
+            ("PRGNAME", "LOGINCHECK"),
+            (
+                "ARGUMENTS",
+                "clino,usrname,pass,menuno,menu_type,browser,platform",
+            ),
+            ("clino", "000000000000001"),
+            ("menuno", "000344"),
+            ("menu_type", "classic"),
+            ("browser", ""),
+            ("platform", ""),
+        ])
+        .send()
+        .await?
+        .error_for_status()?;
+    drop(permit);
+    // a failed login returns the login page again without a redirect
+    let Some(refresh) = response.headers().get("refresh") else {
+        return Err(TucanError::InvalidCredentials);
+    };
+    let unexpected = || TucanError::UnexpectedResponse(format!("refresh header {refresh:?}"));
+    let id = REFRESH_REGEX
+        .captures(refresh.to_str().map_err(|_| unexpected())?)
+        .ok_or_else(unexpected)?["id"]
+        .parse()
+        .map_err(|_| unexpected())?;
+    let cookie_cnsc = response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .find_map(|cookie| {
+            cookie
+                .to_str()
+                .ok()?
+                .strip_prefix("cnsc=")?
+                .split(';')
+                .next()
+                .map(str::to_owned)
+        })
+        .ok_or_else(|| TucanError::UnexpectedResponse("missing cnsc cookie".to_owned()))?;
+    let login_response = LoginResponse { id, cookie_cnsc };
+    redirect_after_login(connector, login_response.clone()).await?;
+    Ok(login_response)
+}
 
 pub async fn logout(
     connector: &TucanConnector,
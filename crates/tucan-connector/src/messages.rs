@@ -0,0 +1,425 @@
+use ego_tree::NodeRef;
+use html_handler::{MyNode, Root, parse_document};
+use tucan_plus_worker::{InvalidateCacheRequest, cachekey::CachePage};
+use tucan_types::{
+    LoginResponse, TucanError,
+    messages::{Attachment, MessageDetailResponse, MessageRequest, MessagesResponse},
+    mlsstart::Nachricht,
+};
+
+use crate::{
+    TucanConnector, authenticated_get,
+    head::{footer, html_head, logged_in_head},
+    registration::is_timeout,
+};
+
+/// The cached pages that list or show messages.
+const MESSAGE_PAGES: [CachePage; 3] = [CachePage::Inbox, CachePage::MlsStart, CachePage::Message];
+
+/// Elements of a message body that are kept, others are replaced by their
+/// content.
+const ALLOWED_ELEMENTS: [&str; 30] = [
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "div",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "thead",
+    "tr",
+    "u",
+];
+
+/// Elements of a message body that are removed with their content.
+const REMOVED_ELEMENTS: [&str; 8] = [
+    "script", "style", "iframe", "object", "embed", "template", "noscript", "svg",
+];
+
+/// Deletes a message with a single request, as repeating it could delete
+/// another message.
+pub(crate) async fn delete_message(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    request: MessageRequest,
+) -> Result<(), TucanError> {
+    let login_response = tucan.current_session(login_response).await;
+    let (content, _date) = authenticated_get(
+        tucan,
+        &request.url(login_response.id),
+        &login_response.cookie_cnsc,
+    )
+    .await?;
+    if is_timeout(&content) {
+        return Err(TucanError::Timeout);
+    }
+    for page in MESSAGE_PAGES {
+        tucan
+            .database
+            .send_message(InvalidateCacheRequest { page })
+            .await;
+    }
+    if still_listed(&login_response, &content, &request) {
+        return Err(TucanError::UnexpectedResponse(
+            "Die Nachricht wurde nicht gelöscht".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether TUCaN answered the deletion `request` with an inbox that still
+/// lists the message.
+fn still_listed(login_response: &LoginResponse, content: &str, request: &MessageRequest) -> bool {
+    messages_internal(login_response, content, &()).is_ok_and(|messages| {
+        messages
+            .messages
+            .iter()
+            .any(|message| MessageRequest::parse(&message.delete_url) == *request)
+    })
+}
+
+fn node_text(node: NodeRef<'_, MyNode>) -> String {
+    node.descendants()
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Serializes `node` keeping only the [`ALLOWED_ELEMENTS`] and links to
+/// web or mail addresses, so the body of a message can be shown as HTML.
+fn sanitized_html(node: NodeRef<'_, MyNode>, output: &mut String) {
+    match node.value() {
+        MyNode::Text(text) => output.push_str(&escape(text)),
+        MyNode::Element(element) if REMOVED_ELEMENTS.contains(&element.name()) => {}
+        MyNode::Element(element) if ALLOWED_ELEMENTS.contains(&element.name()) => {
+            output.push('<');
+            output.push_str(element.name());
+            if let Some(href) = element.attr("href").filter(|href| {
+                element.name() == "a"
+                    && ["https://", "http://", "mailto:"]
+                        .iter()
+                        .any(|scheme| href.starts_with(scheme))
+            }) {
+                output.push_str(" href=\"");
+                output.push_str(&escape(href));
+                output.push_str("\" rel=\"noopener noreferrer\" target=\"_blank\"");
+            }
+            output.push('>');
+            if !matches!(element.name(), "br" | "hr") {
+                for child in node.children() {
+                    sanitized_html(child, output);
+                }
+                output.push_str("</");
+                output.push_str(element.name());
+                output.push('>');
+            }
+        }
+        _ => {
+            for child in node.children() {
+                sanitized_html(child, output);
+            }
+        }
+    }
+}
+
+#[expect(clippy::too_many_lines)]
+pub(crate) fn messages_internal(
+    login_response: &LoginResponse,
+    content: &str,
+    _nothing: &(),
+) -> Result<MessagesResponse, TucanError> {
+    let document = parse_document(content);
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        #![tolerant]
+        <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
+            <head>
+                use html_head(html_handler)?;
+                <style type="text/css">
+                    _style
+                </style>
+            </head>
+            <body class=_body_class>
+                use logged_in_head(html_handler, login_response.id)?.0;
+                <script type="text/javascript">
+                </script>
+                <h1>
+                    _title
+                </h1>
+                <div class="tb rw-table">
+                    <div class="tbhead">
+                        _heading
+                    </div>
+                    <div class="tbcontrol">
+                        let pages = while html_handler.peek().is_some() {
+                            <a href=url class="img">
+                                name
+                            </a>
+                        } => (name, MessageRequest::parse(&url));
+                    </div>
+                    let messages = if html_handler.peek().and_then(|n| n.value().as_element()).is_some_and(|e| e.name() == "table") {
+                        <table class="nb rw-table rw-all" summary=_summary>
+                            <tbody>
+                                <tr class="tbsubhead rw-hide">
+                                    <th id="Datum">
+                                        "Datum"
+                                    </th>
+                                    <th id="Uhrzeit">
+                                        "Uhrzeit"
+                                    </th>
+                                    <th id="Absender">
+                                        "Absender"
+                                    </th>
+                                    <th id="Betreff">
+                                        "Betreff"
+                                    </th>
+                                    <th id="Aktion">
+                                        "Aktion"
+                                    </th>
+                                </tr>
+                                let messages = while html_handler.peek().is_some() {
+                                    <tr class="tbdata">
+                                        <td headers="Datum" class="rw rw-maildate">
+                                            <a class="link" href=url>
+                                                date
+                                            </a>
+                                        </td>
+                                        <td headers="Uhrzeit" class="rw rw-mailtime">
+                                            <a class="link" href={|u: &str| u == url}>
+                                                hour
+                                            </a>
+                                        </td>
+                                        <td headers="Absender" class="rw rw-mailpers">
+                                            <a class="link" href={|u: &str| u == url}>
+                                                source
+                                            </a>
+                                        </td>
+                                        <td headers="Betreff" class="rw rw-mailsubject">
+                                            <a class="link" href={|u: &str| u == url}>
+                                                let message = html_handler.next_any_child()?;
+                                            </a>
+                                        </td>
+                                        <td headers="Aktion" class="rw rw-maildel">
+                                            <a class="link" href=delete_url>
+                                                "Löschen"
+                                            </a>
+                                        </td>
+                                    </tr>
+                                } => Nachricht {
+                                    url,
+                                    date,
+                                    hour,
+                                    source,
+                                    message: node_text(message),
+                                    delete_url
+                                };
+                            </tbody>
+                        </table>
+                    } => messages else {
+                        <div class="tbsubhead">
+                            _no_messages
+                        </div>
+                    } => Vec::<Nachricht>::new();
+                </div>
+            </div>
+        </div>
+    </div>
+    };
+    let html_handler = footer(html_handler, login_response.id, 299)?;
+    html_handler.end_document()?;
+    Ok(MessagesResponse {
+        messages: messages.either_into(),
+        pages,
+    })
+}
+
+pub(crate) fn message_detail_internal(
+    login_response: &LoginResponse,
+    content: &str,
+    _nothing: &(),
+) -> Result<MessageDetailResponse, TucanError> {
+    let document = parse_document(content);
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        #![tolerant]
+        <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
+            <head>
+                use html_head(html_handler)?;
+                <style type="text/css">
+                    _style
+                </style>
+            </head>
+            <body class=_body_class>
+                use logged_in_head(html_handler, login_response.id)?.0;
+                <script type="text/javascript">
+                </script>
+                <h1>
+                    _title
+                </h1>
+                <div class="tb">
+                    <div class="tbhead">
+                        subject
+                    </div>
+                    <div class="tbcontrol">
+                        <a href=_back_url class="img">
+                            _back
+                        </a>
+                        let delete_url = if html_handler.peek().is_some() {
+                            <a href=delete_url class="img">
+                                "Löschen"
+                            </a>
+                        } => MessageRequest::parse(&delete_url);
+                    </div>
+                    <table class="tb">
+                        <tbody>
+                            <tr>
+                                <td class="tbsubhead">
+                                    "Von:"
+                                </td>
+                                <td class="tbdata">
+                                    source
+                                </td>
+                            </tr>
+                            <tr>
+                                <td class="tbsubhead">
+                                    "Datum:"
+                                </td>
+                                <td class="tbdata">
+                                    date
+                                </td>
+                            </tr>
+                            <tr>
+                                <td class="tbdata" colspan="2">
+                                    let body = while html_handler.peek().is_some() {
+                                        let node = html_handler.next_any_child()?;
+                                    } => {
+                                        let mut body = String::new();
+                                        sanitized_html(node, &mut body);
+                                        body
+                                    };
+                                </td>
+                            </tr>
+                            let attachments = while html_handler.peek().is_some() {
+                                <tr>
+                                    <td class="tbsubhead">
+                                        "Anhang:"
+                                    </td>
+                                    <td class="tbdata">
+                                        <a href=url>
+                                            name
+                                        </a>
+                                    </td>
+                                </tr>
+                            } => Attachment { name, url };
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    </div>
+    };
+    let html_handler = footer(html_handler, login_response.id, 299)?;
+    html_handler.end_document()?;
+    Ok(MessageDetailResponse {
+        subject,
+        source,
+        date,
+        body: body.concat(),
+        attachments,
+        delete_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use html_handler::parse_document;
+
+    use super::{node_text, sanitized_html};
+
+    fn cell(html: &str) -> String {
+        let document = parse_document(&format!(
+            "<!DOCTYPE html><html><head></head><body><table><tbody><tr><td>{html}</td></tr></tbody></table></body></html>"
+        ));
+        let cell = document
+            .root()
+            .descendants()
+            .find(|node| {
+                node.value()
+                    .as_element()
+                    .is_some_and(|element| element.name() == "td")
+            })
+            .unwrap();
+        let mut output = String::new();
+        for child in cell.children() {
+            sanitized_html(child, &mut output);
+        }
+        output
+    }
+
+    #[test]
+    fn sanitizes_body() {
+        assert_eq!(
+            cell(r#"<p onclick="alert(1)"><b>Hallo</b><br>&lt;3</p>"#),
+            "<p><b>Hallo</b><br>&lt;3</p>"
+        );
+        assert_eq!(
+            cell(
+                r#"<script>alert(1)</script><img src="x" onerror="alert(1)"><font color="red">rot</font>"#
+            ),
+            "rot"
+        );
+        assert_eq!(
+            cell(
+                r#"<a href="javascript:alert(1)">a</a><a href="https://www.tu-darmstadt.de/?a=1&amp;b=2">b</a>"#
+            ),
+            r#"<a>a</a><a href="https://www.tu-darmstadt.de/?a=1&amp;b=2" rel="noopener noreferrer" target="_blank">b</a>"#
+        );
+    }
+
+    #[test]
+    fn subject_is_text() {
+        let document = parse_document(
+            "<!DOCTYPE html><html><head></head><body><a>Fragen &amp; Antworten<b>!</b></a></body></html>",
+        );
+        let link = document
+            .root()
+            .descendants()
+            .find(|node| {
+                node.value()
+                    .as_element()
+                    .is_some_and(|element| element.name() == "a")
+            })
+            .unwrap();
+        assert_eq!(node_text(link), "Fragen & Antworten!");
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     COURSEDETAILS_REGEX, TucanError,
     head::{footer, html_head, logged_in_head},
 };
-use html_handler::{MyElementRef, MyNode, Root, parse_document};
+use html_handler::{MyElementRef, MyNode, ParseError, Root, parse_document};
 
 #[expect(clippy::too_many_lines)]
 pub(crate) fn after_login_internal(
@@ -19,7 +19,7 @@ pub(crate) fn after_login_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -29,7 +29,7 @@ pub(crate) fn after_login_internal(
                     </style>
                 </head>
                 <body class="currentevents">
-                    let head = logged_in_head(html_handler, login_response.id);
+                    let head = logged_in_head(html_handler, login_response.id)?;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -86,7 +86,7 @@ pub(crate) fn after_login_internal(
                                                 </a>
                                             </td>
                                             <td headers="bis">
-                                                <a class="link" href={|v| assert_eq!(v, courseprep_url)}>
+                                                <a class="link" href={|v: &str| v == courseprep_url}>
                                                     to
                                                 </a>
                                             </td>
@@ -144,18 +144,18 @@ pub(crate) fn after_login_internal(
                                                 </a>
                                             </td>
                                             <td headers="Uhrzeit" class="rw rw-mailtime">
-                                                <a class="link" href={|u| assert_eq!(url, u)}>
+                                                <a class="link" href={|u: &str| u == url}>
                                                     hour
                                                 </a>
                                             </td>
                                             <td headers="Absender" class="rw rw-mailpers">
-                                                <a class="link" href={|u| assert_eq!(url, u)}>
+                                                <a class="link" href={|u: &str| u == url}>
                                                     source
                                                 </a>
                                             </td>
                                             <td headers="Betreff" class="rw rw-mailsubject">
-                                                <a class="link" href={|u| assert_eq!(url, u)}>
-                                                    let message = html_handler.next_any_child();
+                                                <a class="link" href={|u: &str| u == url}>
+                                                    let message = html_handler.next_any_child()?;
                                                 </a>
                                             </td>
                                             <td headers="Aktion" class="rw rw-maildel">
@@ -172,7 +172,7 @@ pub(crate) fn after_login_internal(
                                         message: match message.value() {
                                             MyNode::Text(text) => text.to_string(),
                                             MyNode::Element(_element) => MyElementRef::wrap(message).unwrap().html(),
-                                            _ => panic!(),
+                                            _ => return Err(ParseError::new(message, "text or element", format!("{:?}", message.value())).into()),
                                         },
                                         delete_url
                                     };
@@ -188,8 +188,8 @@ pub(crate) fn after_login_internal(
             </div>
         </div>
     };
-    let html_handler = footer(html_handler, login_response.id, 19);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.id, 19)?;
+    html_handler.end_document()?;
     Ok(MlsStart {
         logged_in_head: head,
         stundenplan: stundenplan.either_into(),
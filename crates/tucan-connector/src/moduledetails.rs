@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use itertools::Itertools;
 use scraper::CaseSensitivity::CaseSensitive;
 use tucan_types::InstructorImage;
@@ -11,7 +13,7 @@ use crate::{
     TucanError,
     head::{footer, html_head, logged_in_head, logged_out_head},
 };
-use html_handler::{MyElementRef, MyNode, Root, parse_document};
+use html_handler::{MyElementRef, MyNode, ParseError, Root, parse_document};
 
 #[expect(clippy::too_many_lines, clippy::cognitive_complexity)]
 pub(crate) fn module_details_internal(
@@ -22,7 +24,7 @@ pub(crate) fn module_details_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -36,9 +38,9 @@ pub(crate) fn module_details_internal(
                 </head>
                 <body class="moduledetails">
                     use if login_response.id == 1 {
-                        logged_out_head(html_handler).0
+                        logged_out_head(html_handler)?.0
                     } else {
-                        logged_in_head(html_handler, login_response.id).0
+                        logged_in_head(html_handler, login_response.id)?.0
                     };
                     <script type="text/javascript">
                     </script>
@@ -133,11 +135,11 @@ pub(crate) fn module_details_internal(
                                                 </p>
                                             } => percentage;
                                             let description = while html_handler.peek().is_some() {
-                                                let child = html_handler.next_any_child();
+                                                let child = html_handler.next_any_child()?;
                                             } => match child.value() {
                                                 MyNode::Text(text) => text.to_string(),
                                                 MyNode::Element(_element) => MyElementRef::wrap(child).unwrap().html(),
-                                                _ => panic!(),
+                                                _ => return Err(ParseError::new(child, "text or element", format!("{:?}", child.value())).into()),
                                             };
                                         </td>
                                     </tr>
@@ -272,14 +274,14 @@ pub(crate) fn module_details_internal(
                                                         </a>
                                                     </td>
                                                     <td>
-                                                        <a name="eventLink" class="link" href={|v| assert_eq!(v, url)}>
+                                                        <a name="eventLink" class="link" href={|v: &str| v == url}>
                                                             name
                                                         </a>
                                                     </td>
                                                     <td>
                                                     </td>
                                                     <td>
-                                                        <a name="eventLink" class="link" href={|v| assert_eq!(v, url)}>
+                                                        <a name="eventLink" class="link" href={|v: &str| v == url}>
                                                             semester
                                                         </a>
                                                     </td>
@@ -298,15 +300,9 @@ pub(crate) fn module_details_internal(
                                         } => KursKategorie {
                                             course_no,
                                             name,
-                                            mandatory: if mandatory == "Ja" {
-                                                true
-                                            } else if mandatory == "Nein" {
-                                                false
-                                            } else {
-                                                panic!("unknown mandatory {mandatory}")
-                                            },
+                                            mandatory: ja_nein(&mandatory)?,
                                             semester,
-                                            credits: credits.replace(',', ".").parse().expect(&credits),
+                                            credits: parse_number(&credits.replace(',', "."))?,
                                             kurse
                                         };
                                     </tbody>
@@ -366,13 +362,13 @@ pub(crate) fn module_details_internal(
                                                 </tr>
                                                 <tr class="tbdata">
                                                     <td class="tbborderleft rw rw-detail-reqachieve">
-                                                        {|v: String| assert_eq!(name, v)}
+                                                        {|v: &str| v == name}
                                                     </td>
                                                     <td class="rw rw-detail-compulsory">
                                                         compulsory
                                                     </td>
                                                     <td class="rw rw-detail-weight alignRight">
-                                                        {|v: String| assert_eq!(weight, v)}
+                                                        {|v: &str| v == weight}
                                                     </td>
                                                 </tr>
                                                 let leistungen = while rowspan > 2 {
@@ -395,13 +391,13 @@ pub(crate) fn module_details_internal(
                                                     </tr>
                                                     <tr class="tbdata">
                                                         <td class="tbborderleft rw rw-detail-reqachieve">
-                                                            {|v: String| assert_eq!(name, v)}
+                                                            {|v: &str| v == name}
                                                         </td>
                                                         <td class="rw rw-detail-compulsory">
                                                             compulsory
                                                         </td>
                                                         <td class="rw rw-detail-weight alignRight">
-                                                            {|v: String| assert_eq!(weight, v)}
+                                                            {|v: &str| v == weight}
                                                             let weight_more = if html_handler.peek().is_some() {
                                                                 <br></br>
                                                                 weight_more
@@ -413,13 +409,7 @@ pub(crate) fn module_details_internal(
                                                     Leistung {
                                                         name,
                                                         weight,
-                                                        compulsory: if compulsory == "Ja" {
-                                                            true
-                                                        } else if compulsory == "Nein" {
-                                                            false
-                                                        } else {
-                                                            panic!("unknown compulsory {compulsory}")
-                                                        },
+                                                        compulsory: ja_nein(&compulsory)?,
                                                         weight_more,
                                                     }
                                                 };
@@ -429,13 +419,7 @@ pub(crate) fn module_details_internal(
                                                     Leistung {
                                                         name,
                                                         weight,
-                                                        compulsory: if compulsory == "Ja" {
-                                                            true
-                                                        } else if compulsory == "Nein" {
-                                                            false
-                                                        } else {
-                                                            panic!("unknown compulsory {compulsory}")
-                                                        },
+                                                        compulsory: ja_nein(&compulsory)?,
                                                         weight_more: None,
                                                     },
                                                 );
@@ -468,13 +452,7 @@ pub(crate) fn module_details_internal(
                                                     Leistung {
                                                         name,
                                                         weight,
-                                                        compulsory: if compulsory == "Ja" {
-                                                            true
-                                                        } else if compulsory == "Nein" {
-                                                            false
-                                                        } else {
-                                                            panic!("unknown compulsory {compulsory}")
-                                                        },
+                                                        compulsory: ja_nein(&compulsory)?,
                                                         weight_more: None,
                                                     }
                                                 };
@@ -484,13 +462,7 @@ pub(crate) fn module_details_internal(
                                                     Leistung {
                                                         name,
                                                         weight,
-                                                        compulsory: if compulsory == "Ja" {
-                                                            true
-                                                        } else if compulsory == "Nein" {
-                                                            false
-                                                        } else {
-                                                            panic!("unknown compulsory {compulsory}")
-                                                        },
+                                                        compulsory: ja_nein(&compulsory)?,
                                                         weight_more: None,
                                                     },
                                                 );
@@ -563,7 +535,7 @@ pub(crate) fn module_details_internal(
                                                             examiner
                                                         </td>
                                                         <td class="rw rw-detail-compulsory">
-                                                            {|v: String| assert_eq!(compulsory, v)}
+                                                            {|v: &str| v == compulsory}
                                                         </td>
                                                     </tr>
                                                 } => {
@@ -573,13 +545,7 @@ pub(crate) fn module_details_internal(
                                             } => {
                                                 termine.insert(0, Pruefungstermin { date, examiner, subname });
                                                 Pruefung {
-                                                    compulsory: if compulsory == "Ja" {
-                                                        true
-                                                    } else if compulsory == "Nein" {
-                                                        false
-                                                    } else {
-                                                        panic!("unknown compulsory {compulsory}")
-                                                    },
+                                                    compulsory: ja_nein(&compulsory)?,
                                                     name,
                                                     termine,
                                                 }
@@ -600,13 +566,7 @@ pub(crate) fn module_details_internal(
                                                 </tr>
                                             } => Pruefung {
                                                 name: name.clone(),
-                                                compulsory: if compulsory == "Ja" {
-                                                    true
-                                                } else if compulsory == "Nein" {
-                                                    false
-                                                } else {
-                                                    panic!("unknown compulsory {compulsory}")
-                                                },
+                                                compulsory: ja_nein(&compulsory)?,
                                                 termine: vec![Pruefungstermin { date, examiner, subname: name }]
                                             };
                                         } => pruefung.either_into();
@@ -645,32 +605,34 @@ pub(crate) fn module_details_internal(
             </div>
         </div>
     };
-    let html_handler = footer(html_handler, login_response.id, 311);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.id, 311)?;
+    html_handler.end_document()?;
     let modulverantwortliche = modulverantwortliche.unwrap_or_default();
     if modulverantwortliche.is_empty() {
-        assert_eq!(dozenten, "N.N.");
+        if dozenten != "N.N." {
+            return Err(ParseError::invalid_value("N.N.", dozenten).into());
+        }
     } else if h(&dozenten) != "AQqH52ruCZMoOSRwd1MfCbJa5Lmtj-kLr31YnzK0Cv0"
         && h(&dozenten) != "boPaxUXsZK4B_2AgJ9IdwGyfKjFeKUzG0Q021sDhchI"
         && h(&dozenten) != "5i9j3HZ9xzH1GKNmLwl7E1cI2Il0QW5VdnRfhx09Yps"
     {
         // hack, one person has a (1) at one place and not at the other
-        assert_eq!(
-            dozenten.split("; ").sorted().collect::<Vec<_>>(),
-            modulverantwortliche
-                .iter()
-                .map(|m| &m.0)
-                .sorted()
-                .collect::<Vec<_>>(),
-            "{module_id} {}",
-            h(&dozenten)
-        );
+        let expected = modulverantwortliche
+            .iter()
+            .map(|m| &m.0)
+            .sorted()
+            .join("; ");
+        if dozenten.split("; ").sorted().join("; ") != expected {
+            return Err(ParseError::invalid_value(expected, dozenten).into());
+        }
     }
     Ok(ModuleDetailsResponse {
         module_id,
         registered: registered.is_some(),
         count_elective_courses,
-        credits: credits.map(|credits| credits.trim_end_matches(",0").parse().expect(&credits)),
+        credits: credits
+            .map(|credits| parse_number(credits.trim_end_matches(",0")))
+            .transpose()?,
         description,
         display_in_timetable,
         duration,
@@ -688,3 +650,19 @@ pub(crate) fn module_details_internal(
         warteliste_percentage,
     })
 }
+
+#[track_caller]
+fn ja_nein(value: &str) -> Result<bool, ParseError> {
+    match value {
+        "Ja" => Ok(true),
+        "Nein" => Ok(false),
+        _ => Err(ParseError::invalid_value("Ja or Nein", value)),
+    }
+}
+
+#[track_caller]
+fn parse_number<N: FromStr>(value: &str) -> Result<N, ParseError> {
+    value
+        .parse()
+        .map_err(|_| ParseError::invalid_value("a number", value))
+}
@@ -1,24 +1,21 @@
 use std::sync::LazyLock;
 
 use crate::head::{html_head, logged_in_head, logged_out_head};
-use html_handler::{Root, parse_document};
+use html_handler::{ParseError, Root, parse_document};
 use regex::Regex;
 use scraper::CaseSensitivity;
-use tucan_types::{LoginResponse, TucanError, courseprep::CoursePrepRequest};
-/*
-    let key = format!("unparsed_month.{request}");
+use tucan_types::{
+    LoginResponse, TucanError,
+    courseprep::CoursePrepRequest,
+    month::{Appointment, MonthDay, MonthResponse, MonthWeek},
+};
 
-    let url = format!(
-        "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MONTH&ARGUMENTS=-N{:015},-N000271,-A01.{},-A,-N000000000000000",
-        login_response.id, request
-    );
-*/
 #[expect(clippy::too_many_lines)]
-#[allow(unused)]
 pub(crate) fn month_internal(
     login_response: &LoginResponse,
     content: &str,
-) -> Result<Vec<(String, CoursePrepRequest)>, TucanError> {
+    _nothing: &(),
+) -> Result<MonthResponse, TucanError> {
     static COURSEPREP_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         Regex::new(
             "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=COURSEPREP&ARGUMENTS=-N\\d+,\
@@ -28,10 +25,9 @@ pub(crate) fn month_internal(
     });
 
     let document = parse_document(content);
-    //println!("{}", html(&document));
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
         <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
             <head>
@@ -45,9 +41,9 @@ pub(crate) fn month_internal(
             </head>
             <body class="month">
                 use if login_response.id == 1 {
-                    logged_out_head(html_handler).0
+                    logged_out_head(html_handler)?.0
                 } else {
-                    logged_in_head(html_handler, login_response.id).0
+                    logged_in_head(html_handler, login_response.id)?.0
                 };
                 <script type="text/javascript">
                 </script>
@@ -56,7 +52,7 @@ pub(crate) fn month_internal(
                 </h1>
                 <div class="tb tbMonthContainer" id="tbmonthContainer">
                     <div class="tbhead">
-                        _month_year
+                        month_year
                     </div>
                     <div class="tbcontrol">
                         <div class="arrow_skipBtn">
@@ -109,52 +105,59 @@ pub(crate) fn month_internal(
                                     "Sonntag"
                                 </th>
                             </tr>
-                            let appointments = while html_handler.peek().is_some() {
+                            let weeks = while html_handler.peek().is_some() {
                                 <tr>
                                     <th class="nb KW_month" scope="row">
                                         <a href=_url>
-                                            _number
+                                            number
                                         </a>
                                     </th>
-                                    let appointments = while html_handler.peek().is_some() {
+                                    let days = while html_handler.peek().is_some() {
                                         <td class="tbMonthDayCell">
-                                            let appointments = if html_handler
+                                            let day = if html_handler
                                                 .peek()
-                                                .unwrap()
-                                                .value()
-                                                .as_element()
-                                                .unwrap()
-                                                .has_class("emptyDay", CaseSensitivity::CaseSensitive) {
+                                                .and_then(|node| node.value().as_element())
+                                                .is_some_and(|element| element.has_class("emptyDay", CaseSensitivity::CaseSensitive)) {
                                                 <div class="tbMonthDay nb emptyDay">
                                                     <img src="/gfx/_default/clear.gif" alt="empty"></img>
                                                 </div>
-                                            } => Vec::<(String, CoursePrepRequest)>::new() else {
+                                            } => () else {
                                                 <div class="tbMonthDay" title=_day>
                                                     <div class="tbsubhead">
-                                                        <a title=_date href=_url>
-                                                            _number
+                                                        <a title=date href=_url>
+                                                            day
                                                         </a>
                                                     </div>
                                                     let appointments = while html_handler.peek().is_some() {
                                                         <div class="appMonth">
                                                             <a title=name xss="href" href=url class="apmntLink" style="overflow:hidden;">
-                                                                _title
+                                                                title
                                                             </a>
                                                             let _optional_br = if html_handler.peek().is_some() {
                                                                 <br></br>
                                                             } => ();
                                                         </div>
-                                                    } => (name, CoursePrepRequest::parse(&COURSEPREP_REGEX.replace(&url, "")));
+                                                    } => Appointment {
+                                                        name,
+                                                        title,
+                                                        courseprep_url: CoursePrepRequest::parse(&COURSEPREP_REGEX.replace(&url, "")),
+                                                    };
                                                 </div>
-                                            } => appointments;
+                                            } => MonthDay { date, day, appointments };
                                         </td>
-                                    } => appointments.either_into::<Vec<(String, CoursePrepRequest)>>();
+                                    } => day.right();
                                 </tr>
-                            } => appointments.into_iter().flatten().collect::<Vec<_>>();
+                            } => MonthWeek {
+                                number: number
+                                    .trim()
+                                    .parse()
+                                    .map_err(|_| ParseError::invalid_value("a week number", number.as_str()))?,
+                                days,
+                            };
                         </tbody>
                     </table>
                 </div>
     }
     let _ = html_handler;
-    Ok(appointments.into_iter().flatten().collect::<Vec<_>>())
+    Ok(MonthResponse { month_year, weeks })
 }
@@ -14,7 +14,7 @@ pub(crate) fn my_documents_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -27,7 +27,7 @@ pub(crate) fn my_documents_internal(
                     </style>
                 </head>
                 <body class="createdocument">
-                    use logged_in_head(html_handler, login_response.id).0;
+                    use logged_in_head(html_handler, login_response.id)?.0;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -99,8 +99,8 @@ pub(crate) fn my_documents_internal(
                 </div>
             </div>
         </div>
-        use footer(html_handler, login_response.id, 326);
+        use footer(html_handler, login_response.id, 326)?;
     }
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(MyDocumentsResponse { documents })
 }
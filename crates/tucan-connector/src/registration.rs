@@ -2,21 +2,31 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 use scraper::CaseSensitivity;
+use tucan_plus_worker::{
+    InvalidateCacheRequest,
+    cachekey::{CacheKey, CachePage},
+};
 use tucan_types::{
-    LoginResponse,
+    LoginResponse, RevalidationStrategy, Tucan as _,
     coursedetails::CourseDetailsRequest,
+    examregistration::ExamRegistrationState,
+    link::LinkRequest,
     moduledetails::ModuleDetailsRequest,
     registration::{
         AnmeldungCourse, AnmeldungEntry, AnmeldungExam, AnmeldungModule, AnmeldungRequest,
-        AnmeldungResponse, RegistrationState, Studiumsauswahl,
+        AnmeldungResponse, RegistrationRequest, RegistrationResponse, RegistrationState,
+        Studiumsauswahl,
     },
 };
 
 use crate::{
-    COURSEDETAILS_REGEX, InElement5, TucanError,
+    COURSEDETAILS_REGEX, InElement5, TucanConnector, TucanError, authenticated_get,
+    authenticated_post,
+    form::Form,
     head::{footer, html_head, logged_in_head},
+    snapshot,
 };
-use html_handler::{InElement, InRoot, MyElementRef, MyNode, Root, parse_document};
+use html_handler::{InElement, InRoot, MyElementRef, MyNode, ParseError, Root, parse_document};
 
 pub static MODULEDETAILS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
@@ -25,6 +35,220 @@ pub static MODULEDETAILS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     .unwrap()
 });
 
+static REGISTRATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N\\d+,-N000311,",
+    )
+    .unwrap()
+});
+
+/// The session argument of a link.
+static SESSION_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new("ARGUMENTS=-N\\d+,").unwrap());
+
+/// The cached pages that show registration states.
+const REGISTRATION_DEPENDENT_PAGES: [CachePage; 5] = [
+    CachePage::Anmeldung,
+    CachePage::ExamRegistration,
+    CachePage::MyCourses,
+    CachePage::MyModules,
+    CachePage::MyExams,
+];
+
+/// Follows the link of a [`RegistrationRequest`] and checks whether the
+/// registration state is the expected one afterwards.
+///
+/// The link is followed exactly once as TUCaN may have registered even if the
+/// response got lost. If there is no usable response, the state is read from
+/// the menu or the exam registration page instead.
+pub(crate) async fn registration_action(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    request: RegistrationRequest,
+    register: bool,
+) -> Result<RegistrationResponse, TucanError> {
+    let login_response = tucan.current_session(login_response).await;
+    let response = match &request {
+        RegistrationRequest::Course { link, menu, .. }
+        | RegistrationRequest::Module { link, menu, .. } => {
+            // TUCaN answers with the registration menu the link was on
+            let link = parse_link(link)?;
+            let arguments = link
+                .inner()
+                .strip_prefix("REGISTRATION,-N000311,")
+                .ok_or_else(|| {
+                    TucanError::UnexpectedResponse(format!("not a registration link: {link}"))
+                })?
+                .to_owned();
+            let key = CacheKey::RegistrationAction { arguments };
+            let anmeldung = follow(tucan, &login_response, &key, &link.url(login_response.id))
+                .await
+                .and_then(|content| {
+                    anmeldung_internal(&login_response, &content, &())
+                        .map_err(|error| error.in_page(&key.to_string()))
+                });
+            invalidate_registration_pages(tucan).await;
+            let anmeldung = match anmeldung {
+                Ok(anmeldung) => anmeldung,
+                Err(error) => {
+                    log::warn!("re-reading the registration menu after {error}");
+                    tucan
+                        .anmeldung(
+                            &login_response,
+                            RevalidationStrategy::default(),
+                            menu.clone(),
+                        )
+                        .await?
+                }
+            };
+            RegistrationResponse {
+                success: is_done(register, registration_state(&anmeldung, &request)),
+                messages: anmeldung.additional_information,
+            }
+        }
+        RegistrationRequest::Exam {
+            semester,
+            examdetail_url,
+            link,
+        } => {
+            let link = parse_link(link)?;
+            let key = CacheKey::ExamRegistrationAction {
+                arguments: link.inner().to_owned(),
+            };
+            match follow(tucan, &login_response, &key, &link.url(login_response.id)).await {
+                // unregistering from an exam has to be confirmed
+                Ok(content) => {
+                    if let Some(mut form) = confirmation(&content) {
+                        form.click();
+                        if let Err(error) =
+                            authenticated_post(tucan, &form, &login_response.cookie_cnsc).await
+                        {
+                            log::warn!("re-reading the exam registration after {error}");
+                        }
+                    }
+                }
+                Err(error) => log::warn!("re-reading the exam registration after {error}"),
+            }
+            invalidate_registration_pages(tucan).await;
+            // the exam registration page shows the new state
+            let exam_registration = tucan
+                .exam_registration(
+                    &login_response,
+                    RevalidationStrategy::default(),
+                    semester.clone(),
+                )
+                .await?;
+            let registered = exam_registration
+                .exam_registrations
+                .iter()
+                .flat_map(|course| &course.registrations)
+                .find(|registration| {
+                    SESSION_REGEX.replace(&registration.examdetail_url, "")
+                        == SESSION_REGEX.replace(examdetail_url, "")
+                })
+                .is_some_and(|registration| {
+                    matches!(
+                        registration.registration_state,
+                        ExamRegistrationState::Registered(_)
+                    )
+                });
+            RegistrationResponse {
+                success: registered == register,
+                messages: Vec::new(),
+            }
+        }
+    };
+    Ok(response)
+}
+
+/// Parses a registration link from a page, so following it can only reach
+/// TUCaN.
+fn parse_link(link: &str) -> Result<LinkRequest, TucanError> {
+    LinkRequest::try_parse(link).map_err(|error| TucanError::UnexpectedResponse(error.to_string()))
+}
+
+/// Invalidates the cached pages that show registration states, as TUCaN may
+/// have registered even if the action seemingly failed.
+async fn invalidate_registration_pages(tucan: &TucanConnector) {
+    for page in REGISTRATION_DEPENDENT_PAGES {
+        tucan
+            .database
+            .send_message(InvalidateCacheRequest { page })
+            .await;
+    }
+}
+
+/// Requests `url` once and records the page. A timed out session is an error
+/// like a lost response, so the caller does not repeat the action.
+async fn follow(
+    tucan: &TucanConnector,
+    login_response: &LoginResponse,
+    key: &CacheKey,
+    url: &str,
+) -> Result<String, TucanError> {
+    let (content, date) = authenticated_get(tucan, url, &login_response.cookie_cnsc).await?;
+    if let Some(directory) = &tucan.record_directory {
+        snapshot::record_page(
+            directory,
+            &key.to_string(),
+            url,
+            date,
+            &content,
+            Some(login_response.id),
+        )?;
+    }
+    if is_timeout(&content) {
+        return Err(TucanError::Timeout);
+    }
+    Ok(content)
+}
+
+/// The state of the course or module of `request` on its menu page.
+fn registration_state<'a>(
+    anmeldung: &'a AnmeldungResponse,
+    request: &RegistrationRequest,
+) -> Option<&'a RegistrationState> {
+    anmeldung.entries.iter().find_map(|entry| match request {
+        RegistrationRequest::Course { id, .. } => entry
+            .courses
+            .iter()
+            .find(|(_exam, course)| course.id == *id)
+            .map(|(_exam, course)| &course.registration_button_link),
+        RegistrationRequest::Module { id, .. } => entry
+            .module
+            .as_ref()
+            .filter(|module| module.id == *id)
+            .map(|module| &module.registration_state),
+        RegistrationRequest::Exam { .. } => None,
+    })
+}
+
+fn is_done(register: bool, state: Option<&RegistrationState>) -> bool {
+    matches!(
+        (register, state),
+        (true, Some(RegistrationState::Registered { .. }))
+            | (false, Some(RegistrationState::NotRegistered { .. }))
+    )
+}
+
+/// The form asking to confirm an exam registration action. The form to choose
+/// the semester of the exam registration page is not one.
+pub(crate) fn confirmation(content: &str) -> Option<Form> {
+    let document = parse_document(content);
+    Form::all(document.root()).find(|form| {
+        form.get("PRGNAME")
+            .is_some_and(|program| program.starts_with("EXAM") && program != "EXAMREGISTRATION")
+    })
+}
+
+/// Whether TUCaN answered with the page saying the session timed out.
+pub(crate) fn is_timeout(content: &str) -> bool {
+    parse_document(content).root().descendants().any(|node| {
+        node.value().as_element().is_some_and(|element| {
+            element.name() == "body" && element.has_class("timeout", CaseSensitivity::CaseSensitive)
+        })
+    })
+}
+
 fn is_tbsubhead(
     html_handler: &InElement<
         '_,
@@ -49,20 +273,13 @@ pub(crate) fn anmeldung_internal(
     content: &str,
     _nothing: &(),
 ) -> Result<AnmeldungResponse, TucanError> {
-    static REGISTRATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(
-            "^/scripts/mgrqispi.dll\\?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N\\d+,\
-             -N000311,",
-        )
-        .unwrap()
-    });
     static RE: LazyLock<Regex> = LazyLock::new(|| {
         Regex::new(r"^\p{Alphabetic}{2}, \d{1,2}\. \p{Alphabetic}{3}\. \d{4} \[\d\d:\d\d\] - \p{Alphabetic}{2}, \d{1,2}\. \p{Alphabetic}{3}\. \d{4} \[\d\d:\d\d\]$").unwrap()
     });
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -75,7 +292,7 @@ pub(crate) fn anmeldung_internal(
                     </style>
                 </head>
                 <body class="registration">
-                    use logged_in_head(html_handler, login_response.id).0;
+                    use logged_in_head(html_handler, login_response.id)?.0;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -132,9 +349,9 @@ pub(crate) fn anmeldung_internal(
                                             <input
                                                 name="sessionno"
                                                 type="hidden"
-                                                value={|v: String| {
+                                                value={|v: &str| {
                                                     static REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new("^\\d+$").unwrap());
-                                                    assert!(REGEX.is_match(&v), "{v}");
+                                                    REGEX.is_match(v)
                                                 }}
                                             ></input>
                                             <input name="menuno" type="hidden" value="000311"></input>
@@ -155,16 +372,16 @@ pub(crate) fn anmeldung_internal(
                             ">"
                             <a href=url>
                                 let any_child = if html_handler.peek().is_some() {
-                                    let any_child = html_handler.next_any_child();
+                                    let any_child = html_handler.next_any_child()?;
                                 } => any_child;
                             </a>
-                        } => match any_child.map(|c| c.value()) {
-                            Some(MyNode::Text(text)) => {
+                        } => match any_child.map(|c| (c, c.value())) {
+                            Some((_, MyNode::Text(text))) => {
                                 let url = REGISTRATION_REGEX.replace(&url, "");
                                 Some((text.to_string(), AnmeldungRequest::parse(&url)))
                             }
                             None => None,
-                            _ => panic!(),
+                            Some((child, value)) => return Err(ParseError::new(child, "text or element", format!("{value:?}")).into()),
                         };
                         extern {
                             let registration_url = REGISTRATION_REGEX.replace(&registration_url, "");
@@ -188,11 +405,11 @@ pub(crate) fn anmeldung_internal(
                             .and_then(ego_tree::NodeRef::next_sibling)
                             .and_then(|e| e.value().as_element())
                             .is_none_or(|e| !e.has_class("tbcoursestatus", CaseSensitivity::CaseSensitive)) {
-                        let child = html_handler.next_any_child();
+                        let child = html_handler.next_any_child()?;
                     } => if let MyNode::Element(_element) = child.value() {
                         Some(MyElementRef::wrap(child).unwrap().html())
                     } else {
-                        panic!()
+                        return Err(ParseError::new(child, "text or element", format!("{:?}", child.value())).into())
                     };
                     <br></br>
                     let anmeldung_entries = if html_handler.peek().is_some() {
@@ -376,12 +593,12 @@ pub(crate) fn anmeldung_internal(
                                                                     let location_or_additional_info = if html_handler
                                                                         .peek()
                                                                         .is_some() {
-                                                                            let location_or_additional_info = html_handler.next_any_child();
+                                                                            let location_or_additional_info = html_handler.next_any_child()?;
                                                                         </p>
                                                                     } => match location_or_additional_info.value() {
                                                                         MyNode::Text(text) => text.to_string(),
                                                                         MyNode::Element(_element) => MyElementRef::wrap(location_or_additional_info).unwrap().html(),
-                                                                        _ => panic!(),
+                                                                        _ => return Err(ParseError::new(location_or_additional_info, "text or element", format!("{:?}", location_or_additional_info.value())).into()),
                                                                     } else {
                                                                         </p>
                                                                     } => ();
@@ -464,8 +681,8 @@ pub(crate) fn anmeldung_internal(
             </div>
         </div>
     };
-    let html_handler = footer(html_handler, login_response.id, 311);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.id, 311)?;
+    html_handler.end_document()?;
     let path: Vec<(String, AnmeldungRequest)> = path.into_iter().flatten().collect();
     Ok(AnmeldungResponse {
         studiumsauswahl: studiumsauswahl.unwrap_or_else(|| {
@@ -481,3 +698,125 @@ pub(crate) fn anmeldung_internal(
         additional_information: additional_information.into_iter().flatten().collect(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use tucan_types::{
+        TucanError,
+        coursedetails::CourseDetailsRequest,
+        registration::{
+            AnmeldungCourse, AnmeldungEntry, AnmeldungRequest, AnmeldungResponse,
+            RegistrationRequest, RegistrationState,
+        },
+    };
+
+    use super::{confirmation, is_done, is_timeout, parse_link, registration_state};
+
+    fn course(id: &str, registration_button_link: RegistrationState) -> AnmeldungCourse {
+        AnmeldungCourse {
+            url: CourseDetailsRequest::parse("-N0,-N389955196291846,-N389955196264847,-N0,-N0,-N0"),
+            id: id.to_owned(),
+            name: "Übung".to_owned(),
+            lecturers: None,
+            begin_and_end: None,
+            registration_until: None,
+            limit_and_size: None,
+            registration_button_link,
+            gefaehrdung_schwangere: false,
+            location_or_additional_info: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn course_state_from_menu() {
+        let anmeldung = AnmeldungResponse {
+            path: Vec::new(),
+            submenus: Vec::new(),
+            entries: vec![AnmeldungEntry {
+                module: None,
+                courses: vec![
+                    (
+                        None,
+                        course(
+                            "20-00-0004-iv",
+                            RegistrationState::Registered {
+                                unregister_link: "unregister".to_owned(),
+                            },
+                        ),
+                    ),
+                    (
+                        None,
+                        course(
+                            "20-00-0004-ue",
+                            RegistrationState::NotRegistered {
+                                register_link: "register".to_owned(),
+                            },
+                        ),
+                    ),
+                ],
+            }],
+            additional_information: Vec::new(),
+            studiumsauswahl: Vec::new(),
+        };
+        let request = |id: &str| RegistrationRequest::Course {
+            id: id.to_owned(),
+            link: String::new(),
+            menu: AnmeldungRequest::default(),
+        };
+        let lecture = registration_state(&anmeldung, &request("20-00-0004-iv"));
+        assert!(is_done(true, lecture));
+        assert!(!is_done(false, lecture));
+        let exercise = registration_state(&anmeldung, &request("20-00-0004-ue"));
+        assert!(is_done(false, exercise));
+        // a course that vanished from the menu is not a success either way
+        let missing = registration_state(&anmeldung, &request("20-00-0005-iv"));
+        assert!(!is_done(true, missing) && !is_done(false, missing));
+    }
+
+    #[test]
+    fn only_follows_tucan_links() {
+        let link = parse_link(
+            "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N123456789012345,-N000311,-AbC_1",
+        )
+        .unwrap();
+        assert_eq!(
+            link.url(1),
+            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N000000000000001,-N000311,-AbC_1"
+        );
+        assert!(matches!(
+            parse_link("//example.org/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION"),
+            Err(TucanError::UnexpectedResponse(_))
+        ));
+    }
+
+    #[test]
+    fn exam_confirmation_form() {
+        let confirm = r#"<!DOCTYPE html><html><head></head><body class="examunreg">
+            <form name="examunreg" action="/scripts/mgrqispi.dll" method="post">
+                <input type="submit" name="Confirm" value="Abmelden">
+                <input name="APPNAME" type="hidden" value="CampusNet">
+                <input name="PRGNAME" type="hidden" value="EXAMUNREG_CONFIRM">
+                <input name="sessionno" type="hidden" value="123456789012345">
+            </form>
+            </body></html>"#;
+        let mut form = confirmation(confirm).unwrap();
+        assert!(form.click());
+        assert_eq!(form.get("Confirm"), Some("Abmelden"));
+        assert_eq!(form.get("sessionno"), Some("123456789012345"));
+
+        // answered directly with the exam registration page
+        let done = r#"<!DOCTYPE html><html><head></head><body class="registration">
+            <form id="semesterchange" action="/scripts/mgrqispi.dll" method="post">
+                <select name="semester"><option value="000000015166000">WiSe 2024/25</option></select>
+                <input name="Refresh" type="submit" value="Aktualisieren">
+                <input name="PRGNAME" type="hidden" value="EXAMREGISTRATION">
+            </form>
+            </body></html>"#;
+        assert_eq!(confirmation(done), None);
+        assert!(!is_timeout(done));
+        assert!(is_timeout(
+            r#"<!DOCTYPE html><html><head></head><body class="timeout"></body></html>"#
+        ));
+    }
+}
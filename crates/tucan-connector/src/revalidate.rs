@@ -0,0 +1,163 @@
+//! Tracks which cached pages a request was answered from, so callers can show
+//! cached data right away and only update it once the page changed.
+
+use std::cell::Cell;
+
+use futures_util::{Stream, StreamExt as _, future, stream};
+use time::{Duration, OffsetDateTime};
+use tucan_types::{RevalidationStrategy, TucanError};
+
+#[derive(Clone, Copy, Default)]
+struct CacheState {
+    /// When the oldest cached page was fetched.
+    oldest: Option<OffsetDateTime>,
+    /// Whether the response for a page fetched from the network differed
+    /// from the cached one.
+    changed: bool,
+}
+
+tokio::task_local! {
+    static CACHE_STATE: Cell<CacheState>;
+}
+
+async fn with_cache_state<F: Future>(future: F) -> (F::Output, CacheState) {
+    CACHE_STATE
+        .scope(Cell::new(CacheState::default()), async move {
+            let output = future.await;
+            (output, CACHE_STATE.with(Cell::get))
+        })
+        .await
+}
+
+/// Runs `future` and additionally returns when the oldest cached page it was
+/// answered from was fetched, [`None`] if everything came from the network.
+pub async fn with_cache_age<F: Future>(future: F) -> (F::Output, Option<OffsetDateTime>) {
+    let (output, state) = with_cache_state(future).await;
+    (output, state.oldest)
+}
+
+pub(crate) fn record_cache_age(updated: OffsetDateTime) {
+    // outside of `with_cache_state` no one is interested
+    let _ = CACHE_STATE.try_with(|state| {
+        state.set(CacheState {
+            oldest: Some(state.get().oldest.map_or(updated, |age| age.min(updated))),
+            ..state.get()
+        });
+    });
+}
+
+pub(crate) fn record_change() {
+    let _ = CACHE_STATE.try_with(|state| {
+        state.set(CacheState {
+            changed: true,
+            ..state.get()
+        });
+    });
+}
+
+/// Never uses the network.
+pub const CACHE_ONLY: RevalidationStrategy = RevalidationStrategy {
+    max_age: i64::MAX,
+    network: false,
+};
+
+enum Stage {
+    Cached,
+    Revalidate { cached: bool },
+    Done,
+}
+
+/// Yields the cached response right away together with when it was fetched.
+/// If that is older than `max_age` seconds or nothing is cached, the page is
+/// fetched again and the fresh response is yielded too, but only if it
+/// changed. A failed revalidation is yielded as error, so the caller decides
+/// whether the cached response is good enough.
+pub fn stale_while_revalidate<O>(
+    max_age: i64,
+    fetch: impl AsyncFn(RevalidationStrategy) -> Result<O, TucanError>,
+) -> impl Stream<Item = Result<(O, Option<OffsetDateTime>), TucanError>> {
+    stream::unfold((Stage::Cached, fetch), move |(stage, fetch)| async move {
+        match stage {
+            Stage::Cached => {
+                let (result, state) = with_cache_state(fetch(CACHE_ONLY)).await;
+                let Ok(response) = result else {
+                    return Some((None, (Stage::Revalidate { cached: false }, fetch)));
+                };
+                let fresh = state.oldest.is_some_and(|oldest| {
+                    OffsetDateTime::now_utc() - oldest < Duration::seconds(max_age)
+                });
+                let next = if fresh {
+                    Stage::Done
+                } else {
+                    Stage::Revalidate { cached: true }
+                };
+                Some((Some(Ok((response, state.oldest))), (next, fetch)))
+            }
+            Stage::Revalidate { cached } => {
+                let (result, state) = with_cache_state(fetch(RevalidationStrategy {
+                    max_age: 0,
+                    network: true,
+                }))
+                .await;
+                let item = match result {
+                    Ok(response) if !cached || state.changed => Some(Ok((response, None))),
+                    Ok(_) => None,
+                    Err(error) => Some(Err(error)),
+                };
+                Some((item, (Stage::Done, fetch)))
+            }
+            Stage::Done => None,
+        }
+    })
+    .filter_map(future::ready)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Pretends the cached page is a day old.
+    fn fetch(
+        changed: bool,
+        calls: &RefCell<Vec<i64>>,
+    ) -> impl AsyncFn(RevalidationStrategy) -> Result<&'static str, TucanError> + '_ {
+        async move |strategy: RevalidationStrategy| {
+            calls.borrow_mut().push(strategy.max_age);
+            if !strategy.network {
+                record_cache_age(OffsetDateTime::now_utc() - Duration::days(1));
+                Ok("cached")
+            } else {
+                if changed {
+                    record_change();
+                }
+                Ok("fresh")
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn yields_fresh_response_only_if_changed() {
+        let calls = RefCell::new(Vec::new());
+        let responses: Vec<_> = stale_while_revalidate(60, fetch(true, &calls))
+            .map(|item| item.unwrap().0)
+            .collect()
+            .await;
+        assert_eq!(responses, ["cached", "fresh"]);
+
+        let responses: Vec<_> = stale_while_revalidate(60, fetch(false, &calls))
+            .map(|item| item.unwrap().0)
+            .collect()
+            .await;
+        assert_eq!(responses, ["cached"]);
+
+        calls.borrow_mut().clear();
+        let responses: Vec<_> = stale_while_revalidate(2 * 24 * 60 * 60, fetch(true, &calls))
+            .map(|item| item.unwrap().0)
+            .collect()
+            .await;
+        assert_eq!(responses, ["cached"]);
+        assert_eq!(*calls.borrow(), [i64::MAX]);
+    }
+}
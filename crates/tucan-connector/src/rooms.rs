@@ -0,0 +1,353 @@
+//! The details of rooms and a finder for rooms that are free at a given time.
+
+use std::collections::BTreeMap;
+
+use futures_util::future::join_all;
+use html_handler::{Root, parse_document};
+use time::{Date, Duration, Time};
+use tucan_types::{
+    LoginResponse, RevalidationStrategy, SemesterId, Tucan, TucanError,
+    coursedetails::Room,
+    rooms::{RoomAppointment, RoomDetailsResponse, RoomRequest},
+};
+
+use crate::{
+    head::{footer, html_head, logged_in_head},
+    ical::{parse_date, parse_time},
+};
+
+/// The rooms found by [`free_rooms`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeRooms {
+    /// The first and last day of the weeks shown on the room pages, the
+    /// appointments of other courses are only known for these days.
+    pub week: Option<(Date, Date)>,
+    /// Empty if the date is outside of [`FreeRooms::week`].
+    pub rooms: Vec<Room>,
+    /// The number of course and room pages that could not be loaded. Their
+    /// rooms are left out.
+    pub failed: usize,
+}
+
+/// Fetches the rooms of all courses of the semester and returns the ones
+/// without an appointment overlapping `start..end` on `date`. Only rooms with
+/// a page are considered, as the appointments of other courses are taken from
+/// it. The pages are fetched concurrently.
+pub async fn free_rooms<T: Tucan + ?Sized>(
+    tucan: &T,
+    login_response: &LoginResponse,
+    revalidation_strategy: RevalidationStrategy,
+    semester: SemesterId,
+    date: Date,
+    start: Time,
+    end: Time,
+) -> Result<FreeRooms, TucanError> {
+    let my_courses = tucan
+        .my_courses(login_response, revalidation_strategy, semester)
+        .await?;
+    let courses = join_all(
+        my_courses
+            .sections
+            .into_iter()
+            .flat_map(|(_section, courses)| courses)
+            .map(|course| tucan.course_details(login_response, revalidation_strategy, course.url)),
+    )
+    .await;
+    let mut failed = 0;
+    // keyed by link as the same room shows up in many courses
+    let mut rooms = BTreeMap::<String, Room>::new();
+    for course in courses {
+        let course = match course {
+            Ok(course) => course,
+            Err(TucanError::Timeout) => return Err(TucanError::Timeout),
+            Err(_error) => {
+                failed += 1;
+                continue;
+            }
+        };
+        for termin in course.termine.iter().chain(&course.termine_kleingruppe) {
+            for room in &termin.rooms {
+                if let Some(url) = &room.url {
+                    rooms.entry(url.clone()).or_insert_with(|| room.clone());
+                }
+            }
+        }
+    }
+    let details = join_all(rooms.keys().map(|url| {
+        tucan.room_details(
+            login_response,
+            revalidation_strategy,
+            RoomRequest::parse(url),
+        )
+    }))
+    .await;
+    let mut occupancies = Vec::new();
+    for (room, details) in rooms.into_values().zip(details) {
+        match details {
+            Ok(details) => occupancies.push((room, details.occupancy)),
+            Err(TucanError::Timeout) => return Err(TucanError::Timeout),
+            Err(_error) => failed += 1,
+        }
+    }
+    let week = covered_week(occupancies.iter().flat_map(|(_room, occupancy)| occupancy));
+    let known = week.is_some_and(|(first, last)| first <= date && date <= last);
+    Ok(FreeRooms {
+        week,
+        rooms: occupancies
+            .into_iter()
+            .filter(|(_room, occupancy)| known && is_free(occupancy, date, start, end))
+            .map(|(room, _occupancy)| room)
+            .collect(),
+        failed,
+    })
+}
+
+/// From the Monday before the first to the Sunday after the last appointment.
+fn covered_week<'a>(occupancy: impl Iterator<Item = &'a RoomAppointment>) -> Option<(Date, Date)> {
+    let dates = occupancy
+        .filter_map(|appointment| parse_date(&appointment.date))
+        .collect::<Vec<_>>();
+    let first = dates.iter().min()?;
+    let last = dates.iter().max()?;
+    Some((
+        *first - Duration::days(first.weekday().number_days_from_monday().into()),
+        *last + Duration::days((6 - last.weekday().number_days_from_monday()).into()),
+    ))
+}
+
+/// Appointments with an unparseable time block the whole day.
+#[must_use]
+pub fn is_free(occupancy: &[RoomAppointment], date: Date, start: Time, end: Time) -> bool {
+    !occupancy.iter().any(|appointment| {
+        parse_date(&appointment.date) == Some(date)
+            && match (
+                parse_time(&appointment.time_start),
+                parse_time(&appointment.time_end),
+            ) {
+                (Some(time_start), Some(time_end)) => time_start < end && start < time_end,
+                _ => true,
+            }
+    })
+}
+
+/// Splits the rows of the details table into building, capacity and
+/// equipment, other rows are ignored.
+fn room_properties(
+    properties: Vec<(String, Option<String>)>,
+) -> (Option<String>, Option<u64>, Vec<String>) {
+    let mut building = None;
+    let mut capacity = None;
+    let mut equipment = Vec::new();
+    for (label, value) in properties {
+        let Some(value) = value else {
+            continue;
+        };
+        match label.trim_end_matches(':') {
+            "Gebäude" => building = Some(value),
+            "Kapazität" | "Plätze" | "Sitzplätze" => {
+                capacity = value
+                    .split_whitespace()
+                    .next()
+                    .and_then(|number| number.parse().ok());
+            }
+            "Ausstattung" => {
+                equipment = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    (building, capacity, equipment)
+}
+
+pub(crate) fn room_details_internal(
+    login_response: &LoginResponse,
+    content: &str,
+    _nothing: &(),
+) -> Result<RoomDetailsResponse, TucanError> {
+    let document = parse_document(content);
+    let html_handler = Root::new(document.root());
+    let html_handler = html_handler.document_start();
+    let html_handler = html_handler.doctype()?;
+    html_extractor::html! {
+        #![tolerant]
+        <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
+            <head>
+                use html_head(html_handler)?;
+                <style type="text/css">
+                    _style
+                </style>
+            </head>
+            <body class=_body_class>
+                use logged_in_head(html_handler, login_response.id)?.0;
+                <script type="text/javascript">
+                </script>
+                <h1>
+                    name
+                </h1>
+                <table class="tb rw-table">
+                    <caption>
+                        _details
+                    </caption>
+                    <tbody>
+                        let properties = while html_handler.peek().is_some() {
+                            <tr>
+                                <td class="tbsubhead">
+                                    label
+                                </td>
+                                <td class="tbdata">
+                                    let value = if html_handler.peek().is_some() {
+                                        value
+                                    } => value;
+                                </td>
+                            </tr>
+                        } => (label, value);
+                    </tbody>
+                </table>
+                <table class="tb rw-table">
+                    <caption>
+                        _occupancy
+                    </caption>
+                    <tbody>
+                        <tr class="rw-hide">
+                            <td class="tbsubhead">
+                                "Veranstaltung"
+                            </td>
+                            <td class="tbsubhead">
+                                "Datum"
+                            </td>
+                            <td class="tbsubhead">
+                                "Von"
+                            </td>
+                            <td class="tbsubhead">
+                                "Bis"
+                            </td>
+                        </tr>
+                        let occupancy = if html_handler.peek().and_then(|row| row.first_child()).and_then(|cell| cell.value().as_element()).is_some_and(|cell| cell.attr("colspan").is_some()) {
+                            <tr>
+                                <td class="tbdata" colspan="4">
+                                    _no_appointments
+                                </td>
+                            </tr>
+                        } => Vec::<RoomAppointment>::new() else {
+                            let occupancy = while html_handler.peek().is_some() {
+                                <tr>
+                                    <td class="tbdata">
+                                        name
+                                    </td>
+                                    <td class="tbdata">
+                                        date
+                                    </td>
+                                    <td class="tbdata">
+                                        time_start
+                                    </td>
+                                    <td class="tbdata">
+                                        time_end
+                                    </td>
+                                </tr>
+                            } => RoomAppointment {
+                                name,
+                                date,
+                                time_start,
+                                time_end
+                            };
+                        } => occupancy;
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    </div>
+    };
+    let html_handler = footer(html_handler, login_response.id, 311)?;
+    html_handler.end_document()?;
+    let (building, capacity, equipment) = room_properties(properties);
+    Ok(RoomDetailsResponse {
+        name,
+        building,
+        capacity,
+        equipment,
+        occupancy: occupancy.either_into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, time};
+
+    use super::*;
+
+    #[test]
+    fn free_unless_overlapping() {
+        let occupancy = [
+            RoomAppointment {
+                name: "Funktionale und objektorientierte Programmierkonzepte".to_owned(),
+                date: "Mo, 14. Okt. 2024".to_owned(),
+                time_start: "13:30".to_owned(),
+                time_end: "15:10".to_owned(),
+            },
+            RoomAppointment {
+                name: "Sprechstunde".to_owned(),
+                date: "Di, 15. Okt. 2024".to_owned(),
+                time_start: String::new(),
+                time_end: String::new(),
+            },
+        ];
+        let monday = date!(2024 - 10 - 14);
+        assert!(!is_free(&occupancy, monday, time!(15:00), time!(16:00)));
+        assert!(is_free(&occupancy, monday, time!(15:10), time!(16:50)));
+        assert!(is_free(&occupancy, monday, time!(11:40), time!(13:30)));
+        assert!(!is_free(
+            &occupancy,
+            date!(2024 - 10 - 15),
+            time!(8:00),
+            time!(9:40)
+        ));
+        assert!(is_free(
+            &occupancy,
+            date!(2024 - 10 - 16),
+            time!(13:30),
+            time!(15:10)
+        ));
+    }
+
+    #[test]
+    fn reads_room_properties() {
+        let (building, capacity, equipment) = room_properties(vec![
+            ("Gebäude:".to_owned(), Some("S1|01".to_owned())),
+            ("Kapazität:".to_owned(), Some("384 Plätze".to_owned())),
+            ("Ausstattung:".to_owned(), Some("Beamer, Tafel,".to_owned())),
+            ("Bemerkung:".to_owned(), None),
+        ]);
+        assert_eq!(building.as_deref(), Some("S1|01"));
+        assert_eq!(capacity, Some(384));
+        assert_eq!(equipment, ["Beamer", "Tafel"]);
+    }
+
+    #[test]
+    fn week_of_the_appointments() {
+        let appointment = |date: &str| RoomAppointment {
+            name: "Sprechstunde".to_owned(),
+            date: date.to_owned(),
+            time_start: String::new(),
+            time_end: String::new(),
+        };
+        let occupancy = [
+            appointment("Mi, 16. Okt. 2024"),
+            appointment("Mo, 14. Okt. 2024"),
+            appointment("unbekannt"),
+        ];
+        assert_eq!(
+            covered_week(occupancy.iter()),
+            Some((date!(2024 - 10 - 14), date!(2024 - 10 - 20)))
+        );
+        assert_eq!(
+            covered_week([appointment("So, 20. Okt. 2024")].iter()),
+            Some((date!(2024 - 10 - 14), date!(2024 - 10 - 20)))
+        );
+        assert_eq!(covered_week([appointment("unbekannt")].iter()), None);
+    }
+}
@@ -6,7 +6,7 @@ pub async fn root(connector: &TucanConnector) -> Result<(), TucanError> {
     let document = parse_document(&content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
         <html>
             <head>
@@ -73,6 +73,6 @@ pub async fn root(connector: &TucanConnector) -> Result<(), TucanError> {
             </body>
         </html>
     };
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(())
 }
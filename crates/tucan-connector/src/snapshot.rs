@@ -0,0 +1,315 @@
+//! Saved TUCaN pages stored as `{key}.html` with their metadata in
+//! `{key}.json`, where `key` is the
+//! [`CacheKey`](tucan_plus_worker::cachekey::CacheKey) used by
+//! [`fetch_with_cache`](crate::fetch_with_cache).
+
+use std::{
+    fmt::Debug,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tucan_plus_worker::cachekey::CachePage;
+use tucan_types::{
+    LoginResponse, TucanError, coursedetails::CourseDetailsRequest, courseprep::CoursePrepRequest,
+};
+
+use crate::{
+    coursedetails::course_details_internal,
+    courseprep::course_prep_internal,
+    courseresults::course_results_internal,
+    examdetails::exam_details_internal,
+    examregistration::exam_registration_internal,
+    examresults::exam_results_internal,
+    gradeoverview::gradeoverview_internal,
+    messages::{message_detail_internal, messages_internal},
+    mlsstart::after_login_internal,
+    moduledetails::module_details_internal,
+    month::month_internal,
+    mycourses::my_courses_internal,
+    mydocuments::my_documents_internal,
+    myexams::my_exams_internal,
+    mymodules::my_modules_internal,
+    registration::{anmeldung_internal, confirmation},
+    rooms::room_details_internal,
+    student_result::student_result_internal,
+    vv::vv_internal,
+};
+
+/// The session id recorded pages are rewritten to.
+pub const SCRUBBED_SESSION_ID: u64 = 42;
+
+const SCRUBBED_NAME: &str = "Max Mustermann";
+
+static LOGIN_DATA_NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new("(?s)<span class=\"loginDataName\" id=\"loginDataName\">.*?</b>([^<]*)</span>")
+        .unwrap()
+});
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    pub url: String,
+    pub date: OffsetDateTime,
+}
+
+#[must_use]
+pub fn page_path(directory: &Path, key: &str) -> PathBuf {
+    directory.join(format!("{key}.html"))
+}
+
+/// A missing page is reported as [`TucanError::NotCached`].
+pub fn read_page(directory: &Path, key: &str) -> Result<String, TucanError> {
+    match std::fs::read_to_string(page_path(directory, key)) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Err(TucanError::NotCached),
+        result => Ok(result?),
+    }
+}
+
+#[must_use]
+pub fn metadata_path(directory: &Path, key: &str) -> PathBuf {
+    directory.join(format!("{key}.json"))
+}
+
+/// Replaces the session id and the name of the logged in user.
+#[must_use]
+pub fn scrub(content: &str, session_id: Option<u64>) -> String {
+    let mut content = content.to_owned();
+    if let Some(session_id) = session_id {
+        content = content
+            .replace(
+                &format!("-N{session_id:015}"),
+                &format!("-N{SCRUBBED_SESSION_ID:015}"),
+            )
+            .replace(
+                &format!("-N{session_id}"),
+                &format!("-N{SCRUBBED_SESSION_ID:015}"),
+            );
+    }
+    let name = LOGIN_DATA_NAME_REGEX
+        .captures(&content)
+        .map(|captures| captures[1].trim().to_owned())
+        .filter(|name| !name.is_empty());
+    if let Some(name) = name {
+        // the name also shows up in e.g. the welcome message
+        content = content.replace(&name, SCRUBBED_NAME);
+    }
+    content
+}
+
+/// Replaces the session id in keys like `unparsed_mlsstart.{date}.{id}` so
+/// the page is found again when replaying with [`SCRUBBED_SESSION_ID`].
+#[must_use]
+pub fn scrub_key(key: &str, session_id: Option<u64>) -> String {
+    let Some(session_id) = session_id else {
+        return key.to_owned();
+    };
+    let id = session_id.to_string();
+    let key = key
+        .split('.')
+        .map(|part| {
+            if part == id {
+                SCRUBBED_SESSION_ID.to_string()
+            } else {
+                part.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    scrub(&key, Some(session_id))
+}
+
+/// Stores a fetched page, see [`scrub`] and [`scrub_key`].
+pub fn record_page(
+    directory: &Path,
+    key: &str,
+    url: &str,
+    date: OffsetDateTime,
+    content: &str,
+    session_id: Option<u64>,
+) -> Result<(), TucanError> {
+    let key = scrub_key(key, session_id);
+    std::fs::create_dir_all(directory)?;
+    std::fs::write(page_path(directory, &key), scrub(content, session_id))?;
+    let metadata = SnapshotMetadata {
+        url: scrub(url, session_id),
+        date,
+    };
+    let metadata = serde_json::to_string_pretty(&metadata).map_err(std::io::Error::from)?;
+    std::fs::write(metadata_path(directory, &key), metadata)?;
+    Ok(())
+}
+
+/// Runs the parser belonging to `key` on `content` and returns the debug
+/// representation of the result.
+pub fn parse_page(
+    login_response: &LoginResponse,
+    key: &str,
+    content: &str,
+) -> Result<String, TucanError> {
+    let (_page, argument) = key.split_once('.').unwrap_or((key, ""));
+    let pretty = |value: &dyn Debug| format!("{value:#?}");
+    Ok(match CachePage::from_key(key) {
+        Some(CachePage::MlsStart) => pretty(&after_login_internal(login_response, content, &())?),
+        Some(CachePage::MyModules) => pretty(&my_modules_internal(login_response, content, &())?),
+        Some(CachePage::MyCourses) => pretty(&my_courses_internal(login_response, content, &())?),
+        Some(CachePage::MyExams) => pretty(&my_exams_internal(login_response, content, &())?),
+        Some(CachePage::ExamDetails) => {
+            pretty(&exam_details_internal(login_response, content, &())?)
+        }
+        // what an exam registration link answered with
+        Some(CachePage::ExamRegistration) if argument.starts_with("action.") => {
+            pretty(&confirmation(content))
+        }
+        Some(CachePage::ExamRegistration) => {
+            pretty(&exam_registration_internal(login_response, content, &())?)
+        }
+        Some(CachePage::ExamResults) => {
+            pretty(&exam_results_internal(login_response, content, &())?)
+        }
+        Some(CachePage::CourseResults) => {
+            pretty(&course_results_internal(login_response, content, &())?)
+        }
+        Some(CachePage::MyDocuments) => {
+            pretty(&my_documents_internal(login_response, content, &())?)
+        }
+        Some(CachePage::Inbox) => pretty(&messages_internal(login_response, content, &())?),
+        Some(CachePage::Message) => pretty(&message_detail_internal(login_response, content, &())?),
+        Some(CachePage::Anmeldung) => pretty(&anmeldung_internal(login_response, content, &())?),
+        Some(CachePage::ModuleDetails) => {
+            pretty(&module_details_internal(login_response, content, &())?)
+        }
+        Some(CachePage::CourseDetails) => pretty(&course_details_internal(
+            login_response,
+            content,
+            &CourseDetailsRequest::parse(argument),
+        )?),
+        Some(CachePage::CoursePrep) => pretty(&course_prep_internal(
+            login_response,
+            content,
+            &CoursePrepRequest::parse(argument),
+        )?),
+        Some(CachePage::Room) => pretty(&room_details_internal(login_response, content, &())?),
+        Some(CachePage::Vv) => {
+            let logged_in = argument.starts_with("true.");
+            pretty(&vv_internal(logged_in.then_some(login_response), content)?)
+        }
+        Some(CachePage::StudentResult) => {
+            pretty(&student_result_internal(login_response, content, &())?)
+        }
+        Some(CachePage::GradeOverview) => {
+            pretty(&gradeoverview_internal(login_response, content, &())?)
+        }
+        Some(CachePage::Month) => pretty(&month_internal(login_response, content, &())?),
+        None => {
+            return Err(TucanError::UnexpectedResponse(format!(
+                "no parser for {key}"
+            )));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tucan_types::{LoginResponse, TucanError};
+
+    use super::{parse_page, read_page, scrub, scrub_key};
+
+    #[test]
+    fn scrub_session_and_name() {
+        let content = r#"<a href="/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MLSSTART&ARGUMENTS=-N123456789012345,-N000019,">
+<h1>Herzlich willkommen, Erika Musterfrau!</h1>
+<span class="loginDataName" id="loginDataName"><b>Name<span class="colon">:</span></b>Erika Musterfrau</span>"#;
+        let scrubbed = scrub(content, Some(123_456_789_012_345));
+        assert!(!scrubbed.contains("123456789012345"));
+        assert!(!scrubbed.contains("Erika"));
+        assert!(scrubbed.contains("-N000000000000042,-N000019"));
+    }
+
+    #[test]
+    fn scrub_session_in_key() {
+        assert_eq!(
+            scrub_key(
+                "unparsed_mlsstart.2024-10-14.123456789012345",
+                Some(123_456_789_012_345)
+            ),
+            "unparsed_mlsstart.2024-10-14.42"
+        );
+        assert_eq!(
+            scrub_key("unparsed_mymodules.999", Some(123_456_789_012_345)),
+            "unparsed_mymodules.999"
+        );
+    }
+
+    #[test]
+    fn unexpected_page_is_parse_error() {
+        let login_response = LoginResponse {
+            id: 42,
+            cookie_cnsc: String::new(),
+        };
+        let result = parse_page(
+            &login_response,
+            "unparsed_mlsstart",
+            "<!DOCTYPE html><html><body><p>Wartungsarbeiten</p></body></html>",
+        );
+        let Err(TucanError::Parse { page: _, error }) = result else {
+            panic!("{result:?}");
+        };
+        let error = error
+            .downcast_ref::<html_handler::ParseError>()
+            .expect("parse error from the template");
+        // points at the `<html xmlns=...>` of the template
+        assert!(error.template.file().ends_with("mlsstart.rs"), "{error}");
+        assert_eq!(error.path, "html");
+    }
+
+    #[test]
+    fn unknown_page_is_error() {
+        let login_response = LoginResponse {
+            id: 42,
+            cookie_cnsc: String::new(),
+        };
+        let result = parse_page(&login_response, "unparsed_unknown", "");
+        assert!(
+            matches!(result, Err(TucanError::UnexpectedResponse(_))),
+            "{result:?}"
+        );
+    }
+
+    /// Parses every page in `test-data/fixtures`, set `TUCAN_FIXTURES` to use
+    /// another directory. Fails without pages, record them with
+    /// [`TucanConnector::with_recording`](crate::TucanConnector::with_recording).
+    #[test]
+    fn parse_fixtures() {
+        let directory = std::env::var("TUCAN_FIXTURES").map_or_else(
+            |_| Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/fixtures"),
+            Into::into,
+        );
+        let login_response = LoginResponse {
+            id: 42,
+            cookie_cnsc: String::new(),
+        };
+        let mut parsed = 0;
+        for entry in std::fs::read_dir(&directory).unwrap() {
+            let path = entry.unwrap().path();
+            let Some(key) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".html"))
+            else {
+                continue;
+            };
+            let content = read_page(&directory, key).unwrap();
+            if let Err(err) = parse_page(&login_response, key, &content) {
+                panic!("failed to parse {key}: {err}");
+            }
+            parsed += 1;
+        }
+        assert!(parsed > 0, "no recorded pages in {}", directory.display());
+    }
+}
@@ -10,11 +10,11 @@ pub async fn startpage_dispatch_1(connector: &TucanConnector) -> Result<(), Tuca
     let document = parse_document(&content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
         <html>
             <head>
-                use html_head_2(html_handler);
+                use html_head_2(html_handler)?;
             </head>
             <body class="redirect">
                 <div id="wrapper">
@@ -56,6 +56,6 @@ pub async fn startpage_dispatch_1(connector: &TucanConnector) -> Result<(), Tuca
             </body>
         </html>
     };
-    html_handler.end_document();
+    html_handler.end_document()?;
     Ok(())
 }
@@ -2,7 +2,7 @@ use std::sync::LazyLock;
 
 use crate::head::{footer, html_head, logged_in_head, logged_out_head};
 use ego_tree::NodeRef;
-use html_handler::{InElement, MyNode, Root, parse_document};
+use html_handler::{InElement, MyNode, ParseError, Root, parse_document};
 use regex::Regex;
 use scraper::CaseSensitivity;
 use tucan_types::{
@@ -13,26 +13,37 @@ use tucan_types::{
     },
 };
 
-fn get_level(node: &NodeRef<MyNode>) -> i8 {
-    node.value()
-        .as_element()
-        .unwrap()
-        .attr("class")
-        .map_or(-1, |v| {
-            v.trim_start_matches("subhead ")
+/// `-1` for rows without a level.
+fn get_level(node: Option<&NodeRef<MyNode>>) -> i8 {
+    node.and_then(|node| node.value().as_element())
+        .and_then(|element| element.attr("class"))
+        .and_then(|class| {
+            class
+                .trim_start_matches("subhead ")
                 .trim_start_matches("level0")
                 .parse::<i8>()
-                .unwrap()
+                .ok()
         })
+        .unwrap_or(-1)
 }
 
+#[track_caller]
+fn parse_cp(value: &str) -> Result<u64, ParseError> {
+    value
+        .trim_end_matches(",0")
+        .parse()
+        .map_err(|_| ParseError::invalid_value("credits", value))
+}
+
+#[expect(clippy::type_complexity)]
 fn part0<T>(
     html_handler: InElement<'_, T>,
     level: i8,
-) -> (InElement<'_, T>, Option<(String, Vec<StudentResultEntry>)>) {
+) -> Result<(InElement<'_, T>, Option<(String, Vec<StudentResultEntry>)>), TucanError> {
     html_extractor::html! {
-        let result = if get_level(html_handler.peek().unwrap()) == level {
-            <tr class={|l| assert_eq!(l, format!("subhead level0{level}"))}>
+        let result = if get_level(html_handler.peek()) == level {
+            // the level in the class was checked above
+            <tr class=_level_class>
                 <td colspan="2">
                     level_i
                 </td>
@@ -49,13 +60,9 @@ fn part0<T>(
             </tr>
             let entries = while html_handler
                 .peek()
-                .unwrap()
-                .first_child()
-                .unwrap()
-                .value()
-                .as_element()
-                .unwrap()
-                .has_class("tbdata", CaseSensitivity::CaseSensitive) {
+                .and_then(NodeRef::first_child)
+                .and_then(|node| node.value().as_element())
+                .is_some_and(|element| element.has_class("tbdata", CaseSensitivity::CaseSensitive)) {
                 <tr>
                     <td class="tbdata">
                         id
@@ -63,9 +70,7 @@ fn part0<T>(
                     <td class="tbdata">
                         let name_and_resultdetails_url = if html_handler
                             .peek()
-                            .unwrap()
-                            .value()
-                            .is_text() {
+                            .is_some_and(|node| node.value().is_text()) {
                             name
                         } => (name, None::<String>) else {
                             <a name=_name id=_result_id href=resultdetails_url onclick=_onclick>
@@ -106,19 +111,19 @@ fn part0<T>(
                 resultdetails_url: name_and_resultdetails_url
                     .either_into::<(String, Option<String>)>()
                     .1,
-                cp: cp.map(|v| v.trim_end_matches(",0").parse().unwrap()),
-                used_cp: used_cp.map(|v| v.trim_end_matches(",0").parse().unwrap()),
-                grade: LeistungsspiegelGrade::from((
+                cp: cp.as_deref().map(parse_cp).transpose()?,
+                used_cp: used_cp.as_deref().map(parse_cp).transpose()?,
+                grade: LeistungsspiegelGrade::try_from((
                     grade.as_deref(),
                     StudentResultState::from((src.as_str(), alt.as_str(), state.as_str()))
-                )),
+                ))?,
             };
         } => (level_i, entries);
     }
-    (html_handler, result)
+    Ok((html_handler, result))
 }
 
-fn parse_rules(rules: &[String]) -> StudentResultRules {
+fn parse_rules(rules: &[String]) -> Result<StudentResultRules, ParseError> {
     static RULES_1: LazyLock<Regex> = LazyLock::new(|| {
         Regex::new(r"^Es sind mindestens +(?P<min>\d+),0 Credits einzubringen. Die Ergebnisse von maximal +(?P<max>\d+),0 Credits gehen in die Notenberechnung ein.$").unwrap()
     });
@@ -164,10 +169,10 @@ fn parse_rules(rules: &[String]) -> StudentResultRules {
                 result.max_modules = Some(max);
             }
         } else {
-            panic!("{}", rule);
+            return Err(ParseError::invalid_value("a known rule", rule.as_str()));
         }
     }
-    result
+    Ok(result)
 }
 
 fn part1<T>(
@@ -175,21 +180,17 @@ fn part1<T>(
     level: i8,
     name: Option<(String, Vec<StudentResultEntry>)>,
     children: Vec<StudentResultLevel>,
-) -> (InElement<'_, T>, StudentResultLevel) {
+) -> Result<(InElement<'_, T>, StudentResultLevel), TucanError> {
     html_extractor::html! {
         let optional = if html_handler
             .peek()
-            .unwrap()
-            .value()
-            .as_element()
-            .unwrap()
-            .attrs
-            .is_empty()
+            .and_then(|node| node.value().as_element())
+            .is_some_and(|element| element.attrs.is_empty())
             && get_level(
-                &html_handler.peek().unwrap().first_child().unwrap()
+                html_handler.peek().and_then(NodeRef::first_child).as_ref()
             ) == level {
             <tr>
-                <td colspan="2" class={|v| assert_eq!(v, format!("level0{level}"))}>
+                <td colspan="2" class={|v: &str| v == format!("level0{level}")}>
                     _summe
                 </td>
                 let sum_cp_and_used_cp = if html_handler
@@ -202,16 +203,16 @@ fn part1<T>(
                     .is_some() {
                     <td
                         colspan="4"
-                        class={|v| assert_eq!(v, format!("level0{level}"))}
+                        class={|v: &str| v == format!("level0{level}")}
                         style="text-align:left;white-space:nowrap;"
                     >
                         _summe_wird_erst_berechnet_wenn_der_bereich_abgeschlossen_ist
                     </td>
                 } => (None, None) else {
-                    <td class={|v| assert_eq!(v, format!("level0{level}"))}>
+                    <td class={|v: &str| v == format!("level0{level}")}>
                     </td>
                     <td
-                        class={|v| assert_eq!(v, format!("level0{level}"))}
+                        class={|v: &str| v == format!("level0{level}")}
                         style="text-align:right;white-space:nowrap;"
                     >
                         let sum_cp = if html_handler.peek().is_some() {
@@ -219,17 +220,17 @@ fn part1<T>(
                         } => sum_cp;
                     </td>
                     <td
-                        class={|v| assert_eq!(v, format!("level0{level}"))}
+                        class={|v: &str| v == format!("level0{level}")}
                         style="text-align:right;white-space:nowrap;"
                     >
                         let sum_used_cp = if html_handler.peek().is_some() {
                             sum_used_cp
                         } => sum_used_cp;
                     </td>
-                    <td class={|v| assert_eq!(v, format!("level0{level}"))} style="text-align:right;">
+                    <td class={|v: &str| v == format!("level0{level}")} style="text-align:right;">
                     </td>
                 } => (sum_cp, sum_used_cp);
-                <td class={|v| assert_eq!(v, format!("level0{level}"))} style="text-align:center;">
+                <td class={|v: &str| v == format!("level0{level}")} style="text-align:center;">
                     <img src=src alt=alt title=state></img>
                 </td>
             </tr>
@@ -244,7 +245,7 @@ fn part1<T>(
                     .unwrap()
                     .has_class(&format!("level0{level}"), CaseSensitivity::CaseSensitive) {
                 <tr>
-                    <td colspan="   7" class={|v| assert_eq!(v, format!("level0{level}"))}>
+                    <td colspan="   7" class={|v: &str| v == format!("level0{level}")}>
                         rule
                     </td>
                 </tr>
@@ -259,7 +260,7 @@ fn part1<T>(
             )
         };
     }
-    (
+    Ok((
         html_handler,
         StudentResultLevel {
             name: name.as_ref().map(|n| n.0.clone()),
@@ -273,10 +274,10 @@ fn part1<T>(
                 .and_then(|o| o.1)
                 .map(|v| v.trim_end_matches(",0").parse().unwrap()),
             state: optional.clone().map(|o| o.2),
-            rules: parse_rules(&optional.map(|o| o.3).unwrap_or_default()),
+            rules: parse_rules(&optional.map(|o| o.3).unwrap_or_default())?,
             children,
         },
-    )
+    ))
 }
 
 #[expect(clippy::too_many_lines)]
@@ -288,7 +289,7 @@ pub(crate) fn student_result_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -302,9 +303,9 @@ pub(crate) fn student_result_internal(
                 </head>
                 <body class="students_results">
                     use if login_response.id == 1 {
-                        logged_out_head(html_handler).0
+                        logged_out_head(html_handler)?.0
                     } else {
-                        logged_in_head(html_handler, login_response.id).0
+                        logged_in_head(html_handler, login_response.id)?.0
                     };
                     <script type="text/javascript">
                     </script>
@@ -390,32 +391,32 @@ pub(crate) fn student_result_internal(
                                 </tr>
                             </thead>
                             <tbody>
-                                let level0_title = part0(html_handler, 0);
-                                let level1 = while get_level(html_handler.peek().unwrap()) >= 1 {
-                                    let level1_title = part0(html_handler, 1);
-                                    let level2 = while get_level(html_handler.peek().unwrap()) >= 2 {
-                                        let level2_title = part0(html_handler, 2);
-                                        let level3 = while get_level(html_handler.peek().unwrap()) >= 3 {
-                                            let level3_title = part0(html_handler, 3);
-                                            let level4 = while get_level(html_handler.peek().unwrap()) >= 4 {
-                                                let level4_title = part0(html_handler, 4);
-                                                let level5 = while get_level(html_handler.peek().unwrap()) >= 5 {
-                                                    let level5_title = part0(html_handler, 5);
-                                                    let level6 = while get_level(html_handler.peek().unwrap()) >= 6 {
-                                                        let level6_title = part0(html_handler, 6);
-                                                        let level6_contents = part1(html_handler, 6, level6_title, Vec::new());
+                                let level0_title = part0(html_handler, 0)?;
+                                let level1 = while get_level(html_handler.peek()) >= 1 {
+                                    let level1_title = part0(html_handler, 1)?;
+                                    let level2 = while get_level(html_handler.peek()) >= 2 {
+                                        let level2_title = part0(html_handler, 2)?;
+                                        let level3 = while get_level(html_handler.peek()) >= 3 {
+                                            let level3_title = part0(html_handler, 3)?;
+                                            let level4 = while get_level(html_handler.peek()) >= 4 {
+                                                let level4_title = part0(html_handler, 4)?;
+                                                let level5 = while get_level(html_handler.peek()) >= 5 {
+                                                    let level5_title = part0(html_handler, 5)?;
+                                                    let level6 = while get_level(html_handler.peek()) >= 6 {
+                                                        let level6_title = part0(html_handler, 6)?;
+                                                        let level6_contents = part1(html_handler, 6, level6_title, Vec::new())?;
                                                     } => level6_contents;
-                                                    let level5_contents = part1(html_handler, 5, level5_title, level6);
+                                                    let level5_contents = part1(html_handler, 5, level5_title, level6)?;
                                                 } => level5_contents;
-                                                let level4_contents = part1(html_handler, 4, level4_title, level5);
+                                                let level4_contents = part1(html_handler, 4, level4_title, level5)?;
                                             } => level4_contents;
-                                            let level3_contents = part1(html_handler, 3, level3_title, level4);
+                                            let level3_contents = part1(html_handler, 3, level3_title, level4)?;
                                         } => level3_contents;
-                                        let level2_contents = part1(html_handler, 2, level2_title, level3);
+                                        let level2_contents = part1(html_handler, 2, level2_title, level3)?;
                                     } => level2_contents;
-                                    let level1_contents = part1(html_handler, 1, level1_title, level2);
+                                    let level1_contents = part1(html_handler, 1, level1_title, level2)?;
                                 } => level1_contents;
-                                let level0_contents = part1(html_handler, 0, level0_title, level1);
+                                let level0_contents = part1(html_handler, 0, level0_title, level1)?;
                             </tbody>
                         </table>
                         <table class="nb list students_results">
@@ -443,8 +444,8 @@ pub(crate) fn student_result_internal(
             </div>
         </div>
     }
-    let html_handler = footer(html_handler, login_response.id, 311);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.id, 311)?;
+    html_handler.end_document()?;
 
     Ok(StudentResultResponse {
         course_of_study: course_of_study.unwrap_or_else(|| {
@@ -0,0 +1,165 @@
+//! Refreshes all personal pages of the current semester in the background so
+//! opening them is instant from the cache and works offline.
+
+use std::{fmt::Display, time::Duration};
+
+use futures_util::future::join_all;
+use time::OffsetDateTime;
+use tucan_plus_worker::{MyDatabase, StoreSyncStatusRequest};
+use tucan_types::{LoginResponse, RevalidationStrategy, SemesterId, Tucan, TucanError};
+
+use crate::sleep;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPage {
+    AfterLogin,
+    MyModules,
+    MyCourses,
+    MyExams,
+    ExamResults,
+    CourseResults,
+    MyDocuments,
+    StudentResult,
+}
+
+impl SyncPage {
+    pub const ALL: [Self; 8] = [
+        Self::AfterLogin,
+        Self::MyModules,
+        Self::MyCourses,
+        Self::MyExams,
+        Self::ExamResults,
+        Self::CourseResults,
+        Self::MyDocuments,
+        Self::StudentResult,
+    ];
+
+    /// Stored in the sync status table.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::AfterLogin => "after_login",
+            Self::MyModules => "my_modules",
+            Self::MyCourses => "my_courses",
+            Self::MyExams => "my_exams",
+            Self::ExamResults => "exam_results",
+            Self::CourseResults => "course_results",
+            Self::MyDocuments => "my_documents",
+            Self::StudentResult => "student_result",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|page| page.key() == key)
+    }
+
+    async fn fetch<T: Tucan + ?Sized>(
+        self,
+        tucan: &T,
+        login_response: &LoginResponse,
+    ) -> Result<(), TucanError> {
+        let revalidation_strategy = RevalidationStrategy::default();
+        let semester = SemesterId::current();
+        match self {
+            Self::AfterLogin => {
+                tucan
+                    .after_login(login_response, revalidation_strategy)
+                    .await?;
+            }
+            Self::MyModules => {
+                tucan
+                    .my_modules(login_response, revalidation_strategy, semester)
+                    .await?;
+            }
+            Self::MyCourses => {
+                tucan
+                    .my_courses(login_response, revalidation_strategy, semester)
+                    .await?;
+            }
+            Self::MyExams => {
+                tucan
+                    .my_exams(login_response, revalidation_strategy, semester)
+                    .await?;
+            }
+            Self::ExamResults => {
+                tucan
+                    .exam_results(login_response, revalidation_strategy, semester)
+                    .await?;
+            }
+            Self::CourseResults => {
+                tucan
+                    .course_results(login_response, revalidation_strategy, semester)
+                    .await?;
+            }
+            Self::MyDocuments => {
+                tucan
+                    .my_documents(login_response, revalidation_strategy)
+                    .await?;
+            }
+            Self::StudentResult => {
+                // 0 is the default course of study
+                tucan
+                    .student_result(login_response, revalidation_strategy, 0)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for SyncPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AfterLogin => write!(f, "Aktuelles"),
+            Self::MyModules => write!(f, "Meine Module"),
+            Self::MyCourses => write!(f, "Meine Veranstaltungen"),
+            Self::MyExams => write!(f, "Meine Prüfungen"),
+            Self::ExamResults => write!(f, "Prüfungsergebnisse"),
+            Self::CourseResults => write!(f, "Modulergebnisse"),
+            Self::MyDocuments => write!(f, "Meine Dokumente"),
+            Self::StudentResult => write!(f, "Leistungsspiegel"),
+        }
+    }
+}
+
+/// Refreshes all pages and records the outcome of each in the database.
+pub async fn sync<T: Tucan + ?Sized>(
+    tucan: &T,
+    database: &MyDatabase,
+    login_response: &LoginResponse,
+) -> Vec<(SyncPage, Result<(), TucanError>)> {
+    join_all(SyncPage::ALL.into_iter().map(|page| async move {
+        let attempted = OffsetDateTime::now_utc();
+        let result = page.fetch(tucan, login_response).await;
+        database
+            .send_message(StoreSyncStatusRequest {
+                page: page.key().to_owned(),
+                attempted,
+                error: result.as_ref().err().map(ToString::to_string),
+            })
+            .await;
+        (page, result)
+    }))
+    .await
+}
+
+/// Syncs every `interval` until the session times out. Other failures are
+/// only recorded, see [`sync`].
+pub async fn sync_periodically<T: Tucan + ?Sized>(
+    tucan: &T,
+    database: &MyDatabase,
+    login_response: &LoginResponse,
+    interval: Duration,
+) -> TucanError {
+    loop {
+        let results = sync(tucan, database, login_response).await;
+        if results
+            .iter()
+            .any(|(_page, result)| matches!(result, Err(TucanError::Timeout)))
+        {
+            return TucanError::Timeout;
+        }
+        sleep(interval).await;
+    }
+}
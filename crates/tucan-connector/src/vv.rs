@@ -1,5 +1,4 @@
-use time::OffsetDateTime;
-use tucan_plus_worker::{CacheRequest, StoreCacheRequest, models::CacheEntry};
+use tucan_plus_worker::cachekey::CacheKey;
 use tucan_types::{
     LoginResponse, RevalidationStrategy, TucanError,
     coursedetails::CourseDetailsRequest,
@@ -7,11 +6,12 @@ use tucan_types::{
 };
 
 use crate::{
-    COURSEDETAILS_REGEX, TucanConnector, authenticated_retryable_get,
+    COURSEDETAILS_REGEX, TucanConnector, fetch_with_cache_and_diff,
     head::{ACTION_REGEX, footer, html_head, logged_in_or_out_head},
-    retryable_get,
+    renewing_fetch_and_parse, retryable_get,
+    snapshot::record_page,
 };
-use html_handler::{MyElementRef, MyNode, Root, parse_document};
+use html_handler::{MyElementRef, MyNode, ParseError, Root, parse_document};
 
 pub async fn vv(
     tucan: &TucanConnector,
@@ -19,65 +19,46 @@ pub async fn vv(
     revalidation_strategy: RevalidationStrategy,
     request: ActionRequest,
 ) -> Result<Vorlesungsverzeichnis, TucanError> {
-    let key = format!(
-        "unparsed_vv.{}.{}",
-        // TODO FIXME I think the complete cache should be separated for logged in and logged out?
-        // Otherwise we pass a session to the parser but the cached stuff is without a session
-        // Probably only relevant for stuff where you can have both and where parsing differs
-        // depending on session?
-        login_response.is_some(),
-        request.inner()
-    );
-
-    let old_content_and_date = tucan
-        .database
-        .send_message(CacheRequest { key: key.clone() })
-        .await;
-    if revalidation_strategy.max_age != 0 {
-        if let Some(CacheEntry {
-            key: _,
-            value: content,
-            updated: date,
-        }) = &old_content_and_date
-        {
-            // here
-            if OffsetDateTime::now_utc() - *date
-                < time::Duration::seconds(revalidation_strategy.max_age)
-            {
-                return vv_internal(login_response, content);
-            }
-        }
-    }
-
-    let Some(invalidate_dependents) = revalidation_strategy.invalidate_dependents else {
-        return Err(TucanError::NotCached);
+    // TODO FIXME I think the complete cache should be separated for logged in and logged out?
+    // Otherwise we pass a session to the parser but the cached stuff is without a session
+    // Probably only relevant for stuff where you can have both and where parsing differs
+    // depending on session?
+    let cache_key = CacheKey::Vv {
+        logged_in: login_response.is_some(),
+        request: request.clone(),
     };
-
     let url = format!(
         "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS={}",
         request.inner()
     );
-    let (content, date) = if let Some(login_response) = login_response {
-        authenticated_retryable_get(tucan, &url, &login_response.cookie_cnsc).await?
-    } else {
-        retryable_get(tucan, &url).await?
-    };
-    let result = vv_internal(login_response, &content)?;
-
-    if invalidate_dependents && old_content_and_date.as_ref().map(|m| &m.key) != Some(&content) {
-        // TODO invalidate cached ones?
-    }
-
-    tucan
-        .database
-        .send_message(StoreCacheRequest(CacheEntry {
-            key,
-            value: content,
-            updated: date,
-        }))
-        .await;
-
-    Ok(result)
+    fetch_with_cache_and_diff(
+        tucan,
+        revalidation_strategy,
+        cache_key,
+        |content| vv_internal(login_response, content),
+        async |key| {
+            if let Some(login_response) = login_response {
+                renewing_fetch_and_parse(
+                    tucan,
+                    login_response,
+                    key,
+                    |_| url.clone(),
+                    &(),
+                    |login_response, content, _| vv_internal(Some(login_response), content),
+                )
+                .await
+            } else {
+                let (content, date) = retryable_get(tucan, &url).await?;
+                if let Some(directory) = &tucan.record_directory {
+                    record_page(directory, key, &url, date, &content, None)?;
+                }
+                let result = vv_internal(None, &content).map_err(|error| error.in_page(key))?;
+                Ok((content, date, result))
+            }
+        },
+        None,
+    )
+    .await
 }
 
 #[expect(clippy::too_many_lines)]
@@ -88,7 +69,7 @@ pub(crate) fn vv_internal(
     let document = parse_document(content);
     let html_handler = Root::new(document.root());
     let html_handler = html_handler.document_start();
-    let html_handler = html_handler.doctype();
+    let html_handler = html_handler.doctype()?;
     html_extractor::html! {
             <html xmlns="http://www.w3.org/1999/xhtml" xml:lang="de" lang="de">
                 <head>
@@ -103,7 +84,7 @@ pub(crate) fn vv_internal(
                     } => ();
                 </head>
                 <body class="registration_auditor">
-                    let _unused = logged_in_or_out_head(html_handler, login_response);
+                    let _unused = logged_in_or_out_head(html_handler, login_response)?;
                     <script type="text/javascript">
                     </script>
                     <h1>
@@ -122,11 +103,11 @@ pub(crate) fn vv_internal(
                         && html_handler.peek().unwrap().value().as_element().unwrap().has_class("nb", scraper::CaseSensitivity::CaseSensitive) {
                         <div class="tb nb">
                             let description = while html_handler.peek().is_some() {
-                                let any_child = html_handler.next_any_child();
+                                let any_child = html_handler.next_any_child()?;
                             } => match any_child.value() {
                                 MyNode::Text(text) => text.to_string(),
                                 MyNode::Element(_element) => MyElementRef::wrap(any_child).unwrap().html(),
-                                _ => panic!(),
+                                _ => return Err(ParseError::new(any_child, "text or element", format!("{:?}", any_child.value())).into()),
                             };
                         </div>
                     } => description;
@@ -214,8 +195,8 @@ pub(crate) fn vv_internal(
             </div>
         </div>
     }
-    let html_handler = footer(html_handler, login_response.map_or(1, |l| l.id), 326);
-    html_handler.end_document();
+    let html_handler = footer(html_handler, login_response.map_or(1, |l| l.id), 326)?;
+    html_handler.end_document()?;
     Ok(Vorlesungsverzeichnis {
         title,
         entries: entries.unwrap_or_default(),
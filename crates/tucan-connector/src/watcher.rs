@@ -0,0 +1,277 @@
+//! Registers for courses the moment their registration opens.
+//!
+//! All requests go through the connector, so many watched courses still only
+//! use [`CONCURRENCY`](tucan_types::CONCURRENCY) connections.
+//!
+//! The `watcher` binary runs it, and so does the extension while one of its
+//! tabs is open. The extension does not watch with all tabs closed.
+
+use std::{fmt::Display, sync::LazyLock, time::Duration};
+
+use futures_util::stream::FuturesUnordered;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, PrimitiveDateTime};
+use tucan_types::{
+    LoginResponse, RevalidationStrategy, Tucan, TucanError,
+    coursedetails::{CourseDetailsRequest, CourseDetailsResponse},
+    registration::{
+        AnmeldungCourse, AnmeldungRequest, AnmeldungResponse, RegistrationRequest,
+        RegistrationState,
+    },
+};
+
+use crate::{
+    ical::{assume_berlin, parse_date, parse_time},
+    sleep,
+};
+
+/// Start polling this long before the registration opens.
+const LEAD_TIME: Duration = Duration::from_secs(10);
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WatchRequest {
+    /// The registration menu that lists the course.
+    pub registration: AnmeldungRequest,
+    pub course: CourseDetailsRequest,
+    /// Names of Übungsgruppen in order of preference, see
+    /// [`CourseUebungsGruppe`](tucan_types::coursedetails::CourseUebungsGruppe).
+    #[serde(default)]
+    pub uebungsgruppen: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WatchOutcome {
+    /// Registered for the course and the first preferred Übungsgruppe that
+    /// had a free slot, if any.
+    Registered {
+        uebungsgruppe: Option<String>,
+    },
+    AlreadyRegistered,
+    /// TUCaN refused the registration.
+    Failed {
+        messages: Vec<String>,
+    },
+    /// The course is not listed in the registration menu.
+    NotFound,
+    /// The registration period ended before a slot opened.
+    Closed,
+}
+
+impl Display for WatchOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Registered {
+                uebungsgruppe: Some(uebungsgruppe),
+            } => write!(f, "Angemeldet mit Übungsgruppe {uebungsgruppe}"),
+            Self::Registered {
+                uebungsgruppe: None,
+            } => write!(f, "Angemeldet"),
+            Self::AlreadyRegistered => write!(f, "Bereits angemeldet"),
+            Self::Failed { .. } => write!(f, "Anmeldung fehlgeschlagen"),
+            Self::NotFound => write!(f, "Nicht im Anmeldemenü gefunden"),
+            Self::Closed => write!(f, "Anmeldezeitraum beendet"),
+        }
+    }
+}
+
+/// Watches all courses concurrently and yields each outcome when it is known.
+pub fn watch_all<'a, T: Tucan + ?Sized>(
+    tucan: &'a T,
+    login_response: &'a LoginResponse,
+    requests: Vec<WatchRequest>,
+) -> impl futures_util::Stream<Item = (WatchRequest, Result<WatchOutcome, TucanError>)> + 'a {
+    requests
+        .into_iter()
+        .map(|request| async move {
+            let outcome = watch(tucan, login_response, &request).await;
+            (request, outcome)
+        })
+        .collect::<FuturesUnordered<_>>()
+}
+
+/// Waits until the registration opens and registers as soon as the course
+/// can be registered for.
+pub async fn watch<T: Tucan + ?Sized>(
+    tucan: &T,
+    login_response: &LoginResponse,
+    request: &WatchRequest,
+) -> Result<WatchOutcome, TucanError> {
+    let course_details = tucan
+        .course_details(
+            login_response,
+            RevalidationStrategy::cache(),
+            request.course.clone(),
+        )
+        .await?;
+    let (start, end) = registration_period(&course_details);
+    if let Some(start) = start {
+        let until_start = start - OffsetDateTime::now_utc() - LEAD_TIME;
+        if until_start.is_positive() {
+            sleep(until_start.unsigned_abs()).await;
+        }
+    }
+
+    let mut interval = MIN_INTERVAL;
+    loop {
+        let anmeldung = tucan
+            .anmeldung(
+                login_response,
+                RevalidationStrategy::default(),
+                request.registration.clone(),
+            )
+            .await?;
+        let Some(course) = find_course(&anmeldung, &request.course) else {
+            return Ok(WatchOutcome::NotFound);
+        };
+        match &course.registration_button_link {
+            RegistrationState::Registered { .. } => return Ok(WatchOutcome::AlreadyRegistered),
+            RegistrationState::NotRegistered { register_link } => {
+                let response = tucan
+                    .register(
+                        login_response,
+                        RegistrationRequest::Course {
+                            id: course.id.clone(),
+                            link: register_link.clone(),
+                            menu: request.registration.clone(),
+                        },
+                    )
+                    .await?;
+                if !response.success {
+                    return Ok(WatchOutcome::Failed {
+                        messages: response.messages,
+                    });
+                }
+                let uebungsgruppe = register_uebungsgruppe(tucan, login_response, request).await?;
+                return Ok(WatchOutcome::Registered { uebungsgruppe });
+            }
+            RegistrationState::Unknown | RegistrationState::NotPossible => {}
+        }
+        if end.is_some_and(|end| end < OffsetDateTime::now_utc()) {
+            return Ok(WatchOutcome::Closed);
+        }
+        sleep(interval).await;
+        // poll fast right after the registration opens, then back off
+        if start.is_none_or(|start| start + MAX_INTERVAL < OffsetDateTime::now_utc()) {
+            interval = (interval * 2).min(MAX_INTERVAL);
+        }
+    }
+}
+
+/// Tries the preferred Übungsgruppen in order and returns the first one that
+/// worked.
+async fn register_uebungsgruppe<T: Tucan + ?Sized>(
+    tucan: &T,
+    login_response: &LoginResponse,
+    request: &WatchRequest,
+) -> Result<Option<String>, TucanError> {
+    if request.uebungsgruppen.is_empty() {
+        return Ok(None);
+    }
+    let course_details = tucan
+        .course_details(
+            login_response,
+            RevalidationStrategy::default(),
+            request.course.clone(),
+        )
+        .await?;
+    let anmeldung = tucan
+        .anmeldung(
+            login_response,
+            RevalidationStrategy::default(),
+            request.registration.clone(),
+        )
+        .await?;
+    for name in &request.uebungsgruppen {
+        let Some(uebungsgruppe) = course_details
+            .uebungsgruppen
+            .iter()
+            .find(|uebungsgruppe| uebungsgruppe.name.trim() == name.trim())
+        else {
+            continue;
+        };
+        let Some(course) = find_course(&anmeldung, &uebungsgruppe.url) else {
+            continue;
+        };
+        if let RegistrationState::NotRegistered { register_link } = &course.registration_button_link
+        {
+            let response = tucan
+                .register(
+                    login_response,
+                    RegistrationRequest::Course {
+                        id: course.id.clone(),
+                        link: register_link.clone(),
+                        menu: request.registration.clone(),
+                    },
+                )
+                .await?;
+            if response.success {
+                return Ok(Some(uebungsgruppe.name.clone()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn find_course<'a>(
+    anmeldung: &'a AnmeldungResponse,
+    course: &CourseDetailsRequest,
+) -> Option<&'a AnmeldungCourse> {
+    anmeldung
+        .entries
+        .iter()
+        .flat_map(|entry| &entry.courses)
+        .map(|(_exam, course)| course)
+        .find(|anmeldung_course| anmeldung_course.url == *course)
+}
+
+/// The earliest start and latest end of the registration periods.
+fn registration_period(
+    course_details: &CourseDetailsResponse,
+) -> (Option<OffsetDateTime>, Option<OffsetDateTime>) {
+    let fristen = &course_details.course_anmeldefristen;
+    let start = fristen
+        .iter()
+        .filter_map(|frist| parse_date_time(frist.start.as_deref()?))
+        .min();
+    let end = fristen
+        .iter()
+        .filter_map(|frist| parse_date_time(frist.ende_anmeldung.as_deref()?))
+        .max();
+    (start, end)
+}
+
+/// Dates like `Mo, 7. Apr. 2025 12:00` in German local time.
+fn parse_date_time(input: &str) -> Option<OffsetDateTime> {
+    static TIME_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d{1,2}:\d{2}").unwrap());
+    let date = parse_date(input)?;
+    let time = parse_time(TIME_REGEX.find(input)?.as_str())?;
+    Some(assume_berlin(PrimitiveDateTime::new(date, time)))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn parses_registration_period() {
+        assert_eq!(
+            parse_date_time("Mo, 7. Apr. 2025 12:00"),
+            Some(datetime!(2025-04-07 10:00 UTC))
+        );
+        assert_eq!(
+            parse_date_time("Di, 30. Sep. 2025 [23:59]"),
+            Some(datetime!(2025-09-30 21:59 UTC))
+        );
+        // winter registrations are an hour later in UTC
+        assert_eq!(
+            parse_date_time("Mo, 2. Feb. 2026 [08:00]"),
+            Some(datetime!(2026-02-02 07:00 UTC))
+        );
+        assert_eq!(parse_date_time("Mo, 7. Apr. 2025"), None);
+    }
+}
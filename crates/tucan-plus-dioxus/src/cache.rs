@@ -0,0 +1,176 @@
+use dioxus::prelude::*;
+use futures::future::join_all;
+use time::{Duration, OffsetDateTime};
+use tucan_plus_worker::{
+    CacheEntriesRequest, CacheSize, CacheSizeRequest, InvalidateCacheRequest, MyDatabase,
+    PurgeCacheRequest, cachekey::CachePage,
+};
+
+use crate::common::local_date_time;
+
+fn format_size(bytes: i64) -> String {
+    let bytes = bytes as f64;
+    if bytes < 1024.0 {
+        format!("{bytes} B")
+    } else if bytes < 1024.0 * 1024.0 {
+        format!("{:.1} KiB", bytes / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes / 1024.0 / 1024.0)
+    }
+}
+
+/// Shows what is stored in the cache and allows removing parts of it.
+#[component]
+pub fn CacheManagement() -> Element {
+    let worker: MyDatabase = use_context();
+    let mut expanded = use_signal(|| None::<CachePage>);
+    let mut purge_days = use_signal(|| 30);
+    let mut sizes = use_resource({
+        let worker = worker.clone();
+        move || {
+            let worker = worker.clone();
+            async move {
+                join_all(CachePage::ALL.into_iter().map(|page| {
+                    let worker = worker.clone();
+                    async move {
+                        let size = worker.send_message(CacheSizeRequest { page }).await;
+                        (page, size)
+                    }
+                }))
+                .await
+            }
+        }
+    });
+    let mut entries = use_resource({
+        let worker = worker.clone();
+        move || {
+            let worker = worker.clone();
+            async move {
+                let Some(page) = expanded() else {
+                    return Vec::new();
+                };
+                worker.send_message(CacheEntriesRequest { page }).await
+            }
+        }
+    });
+    let delete = {
+        let worker = worker.clone();
+        move |page: CachePage| {
+            let worker = worker.clone();
+            move |_event: MouseEvent| {
+                let worker = worker.clone();
+                async move {
+                    worker.send_message(InvalidateCacheRequest { page }).await;
+                    sizes.restart();
+                    entries.restart();
+                }
+            }
+        }
+    };
+    let purge = move |event: FormEvent| {
+        event.prevent_default();
+        let worker = worker.clone();
+        async move {
+            worker
+                .send_message(PurgeCacheRequest {
+                    older_than: OffsetDateTime::now_utc() - Duration::days(purge_days().max(0)),
+                })
+                .await;
+            sizes.restart();
+            entries.restart();
+        }
+    };
+    let sizes = sizes().unwrap_or_default();
+    let total = sizes
+        .iter()
+        .fold(CacheSize::default(), |total, (_page, size)| CacheSize {
+            entries: total.entries + size.entries,
+            size: total.size + size.size,
+        });
+    rsx! {
+        div { class: "container",
+            h1 { {"Cache"} }
+            p {
+                {format!("{} Seiten mit insgesamt {} gespeichert.", total.entries, format_size(total.size))}
+            }
+            form { onsubmit: purge, class: "row g-2 align-items-center mb-3",
+                div { class: "col-auto",
+                    label { class: "col-form-label", r#for: "purge-days", {"Einträge älter als"} }
+                }
+                div { class: "col-auto",
+                    input {
+                        id: "purge-days",
+                        class: "form-control",
+                        r#type: "number",
+                        min: "0",
+                        value: purge_days(),
+                        oninput: move |event| {
+                            if let Ok(days) = event.value().parse() {
+                                purge_days.set(days);
+                            }
+                        },
+                    }
+                }
+                div { class: "col-auto",
+                    span { class: "form-text", {"Tage"} }
+                }
+                div { class: "col-auto",
+                    button { r#type: "submit", class: "btn btn-danger", {"Löschen"} }
+                }
+            }
+            table { class: "table",
+                thead {
+                    tr {
+                        th { scope: "col", {"Seite"} }
+                        th { scope: "col", {"Einträge"} }
+                        th { scope: "col", {"Größe"} }
+                        th { scope: "col" }
+                    }
+                }
+                tbody {
+                    for (page, size) in sizes.into_iter().filter(|(_page, size)| size.entries > 0) {
+                        tr { key: "{page.prefix()}",
+                            td {
+                                button {
+                                    r#type: "button",
+                                    class: "btn btn-link p-0",
+                                    onclick: move |_event| {
+                                        expanded.set((expanded() != Some(page)).then_some(page));
+                                    },
+                                    {page.to_string()}
+                                }
+                            }
+                            td { {size.entries.to_string()} }
+                            td { {format_size(size.size)} }
+                            td {
+                                button {
+                                    r#type: "button",
+                                    class: "btn btn-sm btn-outline-danger",
+                                    onclick: delete(page),
+                                    {"Löschen"}
+                                }
+                            }
+                        }
+                        if expanded() == Some(page) {
+                            tr {
+                                td { colspan: "4",
+                                    table { class: "table table-sm mb-0",
+                                        tbody {
+                                            for entry in entries().unwrap_or_default() {
+                                                tr { key: "{entry.key}",
+                                                    td { class: "text-break", {entry.key.clone()} }
+                                                    td { {format_size(entry.size)} }
+                                                    td { {local_date_time(entry.updated)} }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
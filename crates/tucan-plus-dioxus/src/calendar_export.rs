@@ -0,0 +1,73 @@
+use dioxus::prelude::*;
+use tucan_connector::ical::export_semester;
+use tucan_types::{LoginResponse, RevalidationStrategy, SemesterId};
+
+use crate::RcTucanType;
+
+/// Downloads the appointments of all courses and exams of the semester as an
+/// `.ics` file.
+#[component]
+pub fn ExportCalendar(semester: ReadSignal<SemesterId>) -> Element {
+    let tucan: RcTucanType = use_context();
+    let current_session_handle = use_context::<Signal<Option<LoginResponse>>>();
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let mut calendar = use_signal(|| None::<String>);
+
+    let onclick = move |_event| {
+        let tucan = tucan.clone();
+        async move {
+            loading.set(true);
+            error.set(None);
+            let session = current_session_handle().unwrap();
+            match export_semester(
+                &**tucan,
+                &session,
+                RevalidationStrategy::cache(),
+                semester(),
+            )
+            .await
+            {
+                Ok(result) => calendar.set(Some(result)),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+            loading.set(false);
+        }
+    };
+
+    rsx! {
+        div { class: "mb-2",
+            button {
+                onclick,
+                r#type: "button",
+                class: "btn btn-primary me-2",
+                disabled: loading(),
+                if loading() {
+                    span { class: "spinner-border spinner-border-sm me-1", "aria-hidden": "true" }
+                }
+                "Stundenplan als Kalender exportieren"
+            }
+            if let Some(calendar) = calendar() {
+                a {
+                    href: {
+                        let blob_properties = web_sys::BlobPropertyBag::new();
+                        blob_properties.set_type("text/calendar");
+                        let parts = js_sys::Array::new();
+                        parts.push(&calendar.into());
+                        let blob = web_sys::Blob::new_with_str_sequence_and_options(
+                                &parts,
+                                &blob_properties,
+                            )
+                            .unwrap();
+                        web_sys::Url::create_object_url_with_blob(&blob).unwrap()
+                    },
+                    download: format!("stundenplan-{}.ics", semester()),
+                    "Download"
+                }
+            }
+            if let Some(error) = error() {
+                div { class: "alert alert-danger mt-2", {error} }
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use dioxus::prelude::*;
+use plotters::prelude::*;
+use time::OffsetDateTime;
+use tucan_plus_worker::{
+    CapacityHistoryRequest, MyDatabase, RecordCapacityRequest, models::CapacityHistoryEntry,
+};
+use tucan_types::{
+    capacity::Occupancy,
+    registration::{AnmeldungResponse, RegistrationState},
+};
+
+use crate::notification::{EnableNotifications, notify};
+
+/// The occupancy of all courses and modules of the registration menu and the
+/// urls of those we are registered for. `recorded` is left at the epoch and
+/// set by [`RecordCapacity`], so the same menu gives the same entries.
+pub fn capacity_entries(anmeldung: &AnmeldungResponse) -> (Vec<CapacityHistoryEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut registered = Vec::new();
+    let modules = anmeldung
+        .entries
+        .iter()
+        .filter_map(|entry| entry.module.as_ref())
+        .map(|module| {
+            (
+                module.url.inner(),
+                &module.name,
+                module.occupancy(),
+                &module.registration_state,
+            )
+        });
+    let courses = anmeldung
+        .entries
+        .iter()
+        .flat_map(|entry| &entry.courses)
+        .map(|(_exam, course)| {
+            (
+                course.url.inner(),
+                &course.name,
+                course.occupancy(),
+                &course.registration_button_link,
+            )
+        });
+    for (url, name, occupancy, state) in modules.chain(courses) {
+        let Some(occupancy) = occupancy else {
+            continue;
+        };
+        if matches!(state, RegistrationState::Registered { .. }) {
+            registered.push(url.to_owned());
+        }
+        entries.push(CapacityHistoryEntry {
+            url: url.to_owned(),
+            recorded: OffsetDateTime::UNIX_EPOCH,
+            name: name.clone(),
+            registration_limit: occupancy
+                .limit
+                .map(|limit| i32::try_from(limit).unwrap_or(i32::MAX)),
+            registered: i32::try_from(occupancy.registered).unwrap_or(i32::MAX),
+        });
+    }
+    (entries, registered)
+}
+
+/// Stores the occupancy in the history whenever it changed and notifies if a
+/// place became free in a full course we are registered for, as we are then
+/// on its waitlist.
+#[component]
+pub fn RecordCapacity(entries: Vec<CapacityHistoryEntry>, registered: Vec<String>) -> Element {
+    let worker: MyDatabase = use_context();
+    use_effect(use_reactive!(|entries, registered| {
+        let worker = worker.clone();
+        let recorded = OffsetDateTime::now_utc();
+        let entries = entries
+            .into_iter()
+            .map(|entry| CapacityHistoryEntry { recorded, ..entry })
+            .collect();
+        spawn(async move {
+            let freed = worker.send_message(RecordCapacityRequest { entries }).await;
+            for entry in freed.iter().filter(|entry| registered.contains(&entry.url)) {
+                notify(
+                    &format!("Platz frei in {}", entry.name),
+                    &format!(
+                        "{} von {} Plätzen belegt",
+                        entry.registered,
+                        entry.registration_limit.unwrap_or_default()
+                    ),
+                );
+            }
+        });
+    }));
+    rsx! {
+        if !registered.is_empty() {
+            EnableNotifications {}
+        }
+    }
+}
+
+#[component]
+pub fn OccupancyBadge(occupancy: Occupancy) -> Element {
+    rsx! {
+        small { class: "text-body-secondary",
+            {
+                match occupancy.limit {
+                    Some(limit) => format!("{} / {limit} Plätze belegt", occupancy.registered),
+                    None => format!("{} angemeldet", occupancy.registered),
+                }
+            }
+        }
+        if occupancy.is_full() {
+            {" "}
+            span { class: "badge text-bg-warning", {"Voll"} }
+        }
+    }
+}
+
+/// Chart of how the course or module filled up, as far as it was recorded
+/// when opening the registration.
+#[component]
+pub fn CapacityHistory(url: String) -> Element {
+    let worker: MyDatabase = use_context();
+    let history = use_resource(use_reactive!(|url| {
+        let worker = worker.clone();
+        async move { worker.send_message(CapacityHistoryRequest { url }).await }
+    }));
+    let Some(history) = history() else {
+        return rsx! {};
+    };
+    if history.len() < 2 {
+        return rsx! {};
+    }
+    let mut output = String::new();
+    {
+        let start = history.first().unwrap().recorded;
+        let end = history.last().unwrap().recorded;
+        let max = history
+            .iter()
+            .map(|entry| {
+                entry
+                    .registered
+                    .max(entry.registration_limit.unwrap_or_default())
+            })
+            .max()
+            .unwrap_or_default();
+        let hours = |recorded: OffsetDateTime| (recorded - start).as_seconds_f64() / 3600.0;
+        let root = SVGBackend::with_string(&mut output, (640, 320)).into_drawing_area();
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .x_label_area_size(35)
+            .y_label_area_size(40)
+            .margin(5)
+            .build_cartesian_2d(0.0..hours(end).max(1.0), 0..max + 1)?;
+        chart
+            .configure_mesh()
+            .bold_line_style(WHITE.mix(0.3))
+            .y_desc("Angemeldet")
+            .x_desc("Stunden")
+            .axis_desc_style(("sans-serif", 15))
+            .draw()?;
+        chart.draw_series(LineSeries::new(
+            history
+                .iter()
+                .filter_map(|entry| Some((hours(entry.recorded), entry.registration_limit?))),
+            RED.mix(0.5),
+        ))?;
+        chart.draw_series(LineSeries::new(
+            history
+                .iter()
+                .map(|entry| (hours(entry.recorded), entry.registered)),
+            &BLUE,
+        ))?;
+        root.present()?;
+    }
+    rsx! {
+        h2 { {"Belegung"} }
+        div { dangerous_inner_html: output.replace(r#"<svg width="640" height="320""#, "<svg") }
+    }
+}
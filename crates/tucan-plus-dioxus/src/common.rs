@@ -1,29 +1,14 @@
+use std::pin::pin;
+
 use crate::RcTucanType;
 use dioxus::prelude::*;
+use futures::StreamExt as _;
 use log::info;
 use reqwest::StatusCode;
-use tokio::io::AsyncWriteExt as _;
+use time::{Duration, OffsetDateTime};
+use tucan_connector::revalidate::{CACHE_ONLY, stale_while_revalidate, with_cache_age};
 use tucan_types::{LoginResponse, RevalidationStrategy, TucanError};
-
-pub async fn compress(in_data: &[u8]) -> std::io::Result<Vec<u8>> {
-    let mut encoder = async_compression::tokio::write::BrotliEncoder::with_quality(
-        Vec::new(),
-        async_compression::Level::Best,
-    );
-    // https://github.com/DioxusLabs/dioxus/blob/09c1de7574abb36b11a2c8c825ac30d7398de948/packages/core/src/tasks.rs#L288
-    for chunk in in_data.chunks(10 * 1024).enumerate() {
-        encoder.write_all(chunk.1).await?; // hangs, move to worker?
-    }
-    encoder.shutdown().await?;
-    Ok(encoder.into_inner())
-}
-
-pub async fn decompress(in_data: &[u8]) -> std::io::Result<Vec<u8>> {
-    let mut decoder = async_compression::tokio::write::BrotliDecoder::new(Vec::new());
-    decoder.write_all(in_data).await?;
-    decoder.shutdown().await?;
-    Ok(decoder.into_inner())
-}
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
 
 pub fn use_authenticated_data_loader<
     I: Clone + PartialEq + std::fmt::Debug + 'static,
@@ -159,6 +144,69 @@ pub async fn handle_error<O: Clone + 'static>(
     }
 }
 
+/// Whether the browser has network access, updated on the `online` and
+/// `offline` events.
+#[derive(Clone, Copy)]
+pub struct Online(pub Signal<bool>);
+
+pub fn use_online_provider() -> Online {
+    use_context_provider(|| {
+        let window = web_sys::window().unwrap();
+        let mut online = Signal::new(window.navigator().on_line());
+        for (event, value) in [("online", true), ("offline", false)] {
+            let closure = Closure::<dyn FnMut()>::new(move || online.set(value));
+            window
+                .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+                .unwrap();
+            closure.forget();
+        }
+        Online(online)
+    })
+}
+
+/// No response at all, so probably no connectivity.
+fn is_unreachable(error: &TucanError) -> bool {
+    matches!(error, TucanError::Http(error) if error.status().is_none())
+}
+
+/// Like `handler`, but falls back to the cache if TUCaN is not reachable.
+/// Also returns when the data was fetched if it came from the cache.
+async fn load<I, O>(
+    handler: impl AsyncFn(
+        RcTucanType,
+        Option<LoginResponse>,
+        RevalidationStrategy,
+        I,
+    ) -> Result<O, TucanError>,
+    tucan: RcTucanType,
+    current_session: Option<LoginResponse>,
+    online: bool,
+    revalidation_strategy: RevalidationStrategy,
+    request: I,
+) -> Result<(O, Option<OffsetDateTime>), TucanError>
+where
+    I: Clone,
+{
+    if online {
+        let (result, updated) = with_cache_age(handler(
+            tucan.clone(),
+            current_session.clone(),
+            revalidation_strategy,
+            request.clone(),
+        ))
+        .await;
+        match result {
+            Err(error) if is_unreachable(&error) => {
+                info!("falling back to the cache: {error}");
+            }
+            result => return result.map(|response| (response, updated)),
+        }
+    }
+    let (result, updated) =
+        with_cache_age(handler(tucan, current_session, CACHE_ONLY, request)).await;
+    result.map(|response| (response, updated))
+}
+
 fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone + 'static>(
     authentication_required: bool,
     handler: impl AsyncFn(
@@ -175,6 +223,7 @@ fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone +
     render: impl Fn(O, Callback<MouseEvent>) -> Element,
 ) -> Element {
     let tucan: RcTucanType = use_context();
+    let Online(online) = use_context();
 
     let mut data = use_signal(|| Ok(None));
     let mut loading = use_signal(|| false);
@@ -192,44 +241,89 @@ fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone +
                     data.set(Err("Not logged in".to_owned()));
                     return;
                 }
+                // reloads when the connectivity changes
+                if !online() {
+                    loading.set(true);
+                    match load(
+                        handler,
+                        tucan.clone(),
+                        current_session_handle(),
+                        false,
+                        CACHE_ONLY,
+                        request(),
+                    )
+                    .await
+                    {
+                        Ok(response) => data.set(Ok(Some(response))),
+                        Err(TucanError::NotCached) => {
+                            data.set(Err("Offline and not cached".to_owned()));
+                        }
+                        Err(error) => {
+                            data.set(handle_error(current_session_handle, error, true).await);
+                        }
+                    }
+                    loading.set(false);
+                    return;
+                }
                 loading.set(true);
-                match handler(
-                    tucan.clone(),
-                    current_session_handle(),
-                    RevalidationStrategy {
-                        max_age: cache_age_seconds,
-                        invalidate_dependents: Some(true),
-                    },
-                    request(),
-                )
-                .await
-                {
-                    Ok(response) => {
-                        data.set(Ok(Some(response)));
-                        loading.set(false);
-
-                        match handler(
+                let current_session = current_session_handle();
+                let request = request();
+                let mut responses = pin!(stale_while_revalidate(
+                    max_stale_age_seconds,
+                    async |revalidation_strategy| {
+                        handler(
                             tucan.clone(),
-                            current_session_handle(),
-                            RevalidationStrategy {
-                                max_age: max_stale_age_seconds,
-                                invalidate_dependents: Some(true),
-                            },
-                            request(),
+                            current_session.clone(),
+                            revalidation_strategy,
+                            request.clone(),
                         )
                         .await
+                    }
+                ));
+                // too old to be shown unless TUCaN is not reachable
+                let mut expired = None;
+                let mut shown = false;
+                let mut failed = false;
+                while let Some(response) = responses.next().await {
+                    match response {
+                        Ok((response, Some(updated)))
+                            if OffsetDateTime::now_utc() - updated
+                                > Duration::seconds(cache_age_seconds) =>
                         {
-                            Ok(response) => data.set(Ok(Some(response))),
-                            Err(error) => {
-                                info!("ignoring error when refetching: {error}")
+                            expired = Some((response, Some(updated)));
+                        }
+                        Ok(response) => {
+                            expired = None;
+                            data.set(Ok(Some(response)));
+                            loading.set(false);
+                            shown = true;
+                        }
+                        Err(error) => {
+                            failed = true;
+                            match expired.take() {
+                                Some(response) if is_unreachable(&error) => {
+                                    info!("falling back to the cache: {error}");
+                                    data.set(Ok(Some(response)));
+                                }
+                                _ if shown => info!("ignoring error when refetching: {error}"),
+                                _ => {
+                                    data.set(
+                                        handle_error(current_session_handle, error, true).await,
+                                    );
+                                }
                             }
                         }
                     }
-                    Err(error) => {
-                        data.set(handle_error(current_session_handle, error, true).await);
-                        loading.set(false);
+                }
+                if !failed {
+                    if let Some((response, _updated)) = expired {
+                        data.set(Ok(Some((response, None))));
+                    } else if let Ok(Some((_response, updated))) = &mut *data.write() {
+                        // revalidated without changes
+                        *updated = None;
                     }
                 }
+                loading.set(false);
             }
         });
     }
@@ -244,12 +338,14 @@ fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone +
             loading.set(true);
             let tucan = tucan.clone();
             spawn(async move {
-                match handler(
+                match load(
+                    handler,
                     tucan.clone(),
                     current_session_handle(),
+                    online(),
                     RevalidationStrategy {
                         max_age: 0,
-                        invalidate_dependents: Some(true),
+                        network: true,
                     },
                     request(),
                 )
@@ -259,6 +355,10 @@ fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone +
                         data.set(Ok(Some(response)));
                         loading.set(false);
                     }
+                    Err(TucanError::NotCached) => {
+                        data.set(Err("Offline and not cached".to_owned()));
+                        loading.set(false);
+                    }
                     Err(error) => {
                         data.set(handle_error(current_session_handle, error, true).await);
                         loading.set(false);
@@ -317,9 +417,31 @@ fn use_data_loader<I: Clone + PartialEq + std::fmt::Debug + 'static, O: Clone +
                     }
                 }
             }
-            if let Some(course) = data {
+            if let Some((course, updated)) = data {
+                if let Some(updated) = updated.filter(|updated| {
+                    !online() || OffsetDateTime::now_utc() - *updated > Duration::seconds(max_stale_age_seconds)
+                }) {
+                    div { class: "alert alert-warning mt-2 py-1",
+                        span { class: "badge text-bg-warning me-2",
+                            if online() {
+                                {"Veraltet"}
+                            } else {
+                                {"Offline"}
+                            }
+                        }
+                        {format!("Stand: {}", local_date_time(updated))}
+                    }
+                }
                 { render(course.to_owned(), reload) }
             }
         }
     }
 }
+
+/// Formats the time in the timezone of the browser.
+pub fn local_date_time(date_time: OffsetDateTime) -> String {
+    let milliseconds = (date_time.unix_timestamp_nanos() / 1_000_000) as f64;
+    js_sys::Date::new(&JsValue::from_f64(milliseconds))
+        .to_locale_string("de-DE", &JsValue::UNDEFINED)
+        .into()
+}
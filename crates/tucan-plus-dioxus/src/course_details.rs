@@ -1,6 +1,9 @@
-use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+use crate::{
+    RcTucanType, Route, capacity::CapacityHistory, common::use_authenticated_data_loader,
+    rooms::RoomLink,
+};
 use dioxus::prelude::*;
-use tucan_types::{Tucan, coursedetails::CourseDetailsRequest};
+use tucan_types::{Tucan, capacity::Capacity, coursedetails::CourseDetailsRequest};
 
 #[component]
 pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
@@ -10,6 +13,8 @@ pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
             .await
     };
 
+    let course_request = course;
+
     use_authenticated_data_loader(
         handler,
         course.to_owned(),
@@ -66,15 +71,15 @@ pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
                     div { {format!("Typ: {}", course.r#type)} }
                     div { {format!("Fachbereich: {}", course.fachbereich)} }
                     {
-                        match (course.teilnehmer_min, course.teilnehmer_max) {
-                            (None, None) => rsx! {},
-                            (None, Some(max)) => rsx! {
+                        match course.capacity() {
+                            Capacity { min: None, max: None } => rsx! {},
+                            Capacity { min: None, max: Some(max) } => rsx! {
                                 div { {format!("Maximal {max} Teilnehmende")} }
                             },
-                            (Some(min), None) => rsx! {
+                            Capacity { min: Some(min), max: None } => rsx! {
                                 div { {format!("Mindestens {min} Teilnehmende")} }
                             },
-                            (Some(min), Some(max)) => rsx! {
+                            Capacity { min: Some(min), max: Some(max) } => rsx! {
                                 div { {format!("{min} - {max} Teilnehmende")} }
                             },
                         }
@@ -197,7 +202,7 @@ pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
                                                                         .iter()
                                                                         .map(|room| {
                                                                             rsx! {
-                                                                                li { {room.name.clone()} }
+                                                                                li { RoomLink { room: room.clone() } }
                                                                             }
                                                                         })
                                                                 }
@@ -243,7 +248,7 @@ pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
                                                                     .iter()
                                                                     .map(|room| {
                                                                         rsx! {
-                                                                            li { {room.name.clone()} }
+                                                                            li { RoomLink { room: room.clone() } }
                                                                         }
                                                                     })
                                                             }
@@ -280,6 +285,7 @@ pub fn CourseDetails(course: ReadSignal<CourseDetailsRequest>) -> Element {
                                 })
                         }
                     }
+                    CapacityHistory { url: course_request.read().inner().to_owned() }
                 }
             }
         },
@@ -0,0 +1,82 @@
+use dioxus::prelude::*;
+use tucan_types::{Tucan, courseprep::CoursePrepRequest};
+
+use crate::{RcTucanType, common::use_authenticated_data_loader, rooms::RoomLink};
+
+/// An appointment of the timetable.
+#[component]
+pub fn CoursePrep(course_prep: ReadSignal<CoursePrepRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .course_prep(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        course_prep.to_owned(),
+        14 * 24 * 60 * 60,
+        60 * 60,
+        |appointment, _reload| {
+            rsx! {
+                div {
+                    h1 { {appointment.title.clone()} }
+                    if let Some(kleingruppe) = appointment.kleingruppe.clone() {
+                        h2 { {kleingruppe} }
+                    }
+                    dl { class: "row",
+                        dt { class: "col-sm-3", {"Termin"} }
+                        dd { class: "col-sm-9",
+                            {format!("{} {} - {}", appointment.date, appointment.time_start, appointment.time_end)}
+                        }
+                        dt { class: "col-sm-3", {"Räume"} }
+                        dd { class: "col-sm-9",
+                            ul { class: "list-unstyled mb-0",
+                                for room in appointment.rooms.iter().cloned() {
+                                    li { RoomLink { room } }
+                                }
+                            }
+                        }
+                        dt { class: "col-sm-3", {"Lehrende"} }
+                        dd { class: "col-sm-9", {appointment.instructors.clone().unwrap_or_default()} }
+                        if let Some(notes) = appointment.notes.clone() {
+                            dt { class: "col-sm-3", {"Bemerkung"} }
+                            dd { class: "col-sm-9", {notes} }
+                        }
+                    }
+                    if !appointment.series.is_empty() {
+                        h2 { {"Terminserie"} }
+                        div { class: "table-responsive",
+                            table { class: "table",
+                                thead {
+                                    tr {
+                                        th { scope: "col", {"Datum"} }
+                                        th { scope: "col", {"Start"} }
+                                        th { scope: "col", {"Ende"} }
+                                        th { scope: "col", {"Räume"} }
+                                    }
+                                }
+                                tbody {
+                                    for termin in appointment.series.iter().cloned() {
+                                        tr {
+                                            td { {termin.date} }
+                                            td { {termin.time_start} }
+                                            td { {termin.time_end} }
+                                            td {
+                                                ul { class: "list-unstyled mb-0",
+                                                    for room in termin.rooms {
+                                                        li { RoomLink { room } }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
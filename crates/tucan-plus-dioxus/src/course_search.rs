@@ -0,0 +1,157 @@
+use dioxus::prelude::*;
+use tucan_types::{
+    LoginResponse, SemesterId, Tucan,
+    coursesearch::{CourseSearchRequest, SearchOption},
+};
+
+use crate::{RcTucanType, Route};
+
+#[component]
+fn SearchSelect(
+    label: &'static str,
+    options: Vec<SearchOption>,
+    onchange: EventHandler<String>,
+) -> Element {
+    rsx! {
+        div { class: "col-md-4",
+            label { class: "form-label", {label} }
+            select {
+                class: "form-select",
+                onchange: move |event| onchange.call(event.value()),
+                for option in options {
+                    option {
+                        key: "{option.value}",
+                        value: option.value.clone(),
+                        selected: option.selected,
+                        {option.name.clone()}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The Lehrveranstaltungssuche. The first search is empty to get the
+/// selectable values.
+#[component]
+pub fn CourseSearch() -> Element {
+    let tucan: RcTucanType = use_context();
+    let current_session = use_context::<Signal<Option<LoginResponse>>>();
+    let mut draft = use_signal(CourseSearchRequest::default);
+    let mut request = use_signal(CourseSearchRequest::default);
+    let response = use_resource(move || {
+        let tucan = tucan.clone();
+        async move {
+            tucan
+                .search_courses(current_session().as_ref(), request())
+                .await
+                .map_err(|error| error.to_string())
+        }
+    });
+    let search = move |event: FormEvent| {
+        event.prevent_default();
+        request.set(draft());
+    };
+    let (options, results, error) = match response() {
+        Some(Ok(response)) => (Some(response.clone()), response.results, None),
+        Some(Err(error)) => (None, Vec::new(), Some(error)),
+        None => (None, Vec::new(), None),
+    };
+    rsx! {
+        div { class: "container",
+            h1 { {"Lehrveranstaltungssuche"} }
+            form { onsubmit: search, class: "row g-3 mb-3",
+                div { class: "col-md-6",
+                    label { class: "form-label", r#for: "course-search-title", {"Titel"} }
+                    input {
+                        id: "course-search-title",
+                        class: "form-control",
+                        value: draft().title,
+                        oninput: move |event| draft.write().title = event.value(),
+                    }
+                }
+                div { class: "col-md-6",
+                    label { class: "form-label", r#for: "course-search-lecturer", {"Dozent"} }
+                    input {
+                        id: "course-search-lecturer",
+                        class: "form-control",
+                        value: draft().lecturer,
+                        oninput: move |event| draft.write().lecturer = event.value(),
+                    }
+                }
+                if let Some(options) = options {
+                    div { class: "col-md-4",
+                        label { class: "form-label", {"Semester"} }
+                        select {
+                            class: "form-select",
+                            onchange: move |event| {
+                                draft.write().semester = Some(event.value().parse::<SemesterId>().unwrap());
+                            },
+                            for semester in options.semester {
+                                option {
+                                    key: "{semester.value}",
+                                    value: semester.value.to_string(),
+                                    selected: semester.selected,
+                                    {semester.name.clone()}
+                                }
+                            }
+                        }
+                    }
+                    SearchSelect {
+                        label: "Veranstaltungsart",
+                        options: options.course_types,
+                        onchange: move |value| draft.write().course_type = value,
+                    }
+                    SearchSelect {
+                        label: "Sprache",
+                        options: options.languages,
+                        onchange: move |value| draft.write().language = value,
+                    }
+                    SearchSelect {
+                        label: "Fachbereich",
+                        options: options.fachbereiche,
+                        onchange: move |value| draft.write().fachbereich = value,
+                    }
+                }
+                div { class: "col-12",
+                    button { r#type: "submit", class: "btn btn-primary", {"Suchen"} }
+                }
+            }
+            if let Some(error) = error {
+                div { class: "alert alert-danger", role: "alert", {error} }
+            }
+            if response.read().is_none() {
+                span { class: "spinner-border spinner-border-sm", "aria-hidden": "true" }
+            }
+            div { class: "table-responsive",
+                table { class: "table",
+                    thead {
+                        tr {
+                            th { scope: "col", {"Veranstaltung"} }
+                            th { scope: "col", {"Dozenten"} }
+                            th { scope: "col", {"Zeitraum"} }
+                            th { scope: "col", {"Veranstaltungsart"} }
+                        }
+                    }
+                    tbody {
+                        for result in results {
+                            tr { key: "{result.coursedetails_url}",
+                                th { scope: "row",
+                                    Link {
+                                        to: Route::CourseDetails {
+                                            course: result.coursedetails_url.clone(),
+                                        },
+                                        {result.title.clone()}
+                                    }
+                                }
+                                td { {result.lecturer_name.clone().unwrap_or_default()} }
+                                td { {result.date_range.clone().unwrap_or_default()} }
+                                td { {result.course_type.clone()} }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use dioxus::prelude::*;
+use tucan_types::{Tucan, examdetails::ExamDetailsRequest};
+
+use crate::{RcTucanType, common::use_authenticated_data_loader, rooms::RoomLink};
+
+#[component]
+pub fn ExamDetails(exam: ReadSignal<ExamDetailsRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .exam_details(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        exam.to_owned(),
+        14 * 24 * 60 * 60,
+        60 * 60,
+        |exam, _reload| {
+            rsx! {
+                div {
+                    h1 { {exam.name.clone()} }
+                    dl { class: "row",
+                        dt { class: "col-sm-3", {"Prüfer"} }
+                        dd { class: "col-sm-9", {exam.examiner.clone().unwrap_or_default()} }
+                        dt { class: "col-sm-3", {"Datum"} }
+                        dd { class: "col-sm-9", {exam.date.clone().unwrap_or_default()} }
+                        dt { class: "col-sm-3", {"Räume"} }
+                        dd { class: "col-sm-9",
+                            ul { class: "list-unstyled mb-0",
+                                for room in exam.rooms.iter().cloned() {
+                                    li { RoomLink { room } }
+                                }
+                            }
+                        }
+                        dt { class: "col-sm-3", {"Anmeldezeitraum"} }
+                        dd { class: "col-sm-9", {exam.registration_range.clone().unwrap_or_default()} }
+                        dt { class: "col-sm-3", {"Abmeldezeitraum"} }
+                        dd { class: "col-sm-9", {exam.unregistration_range.clone().unwrap_or_default()} }
+                        dt { class: "col-sm-3", {"Hilfsmittel"} }
+                        dd { class: "col-sm-9", {exam.aids.clone().unwrap_or_default()} }
+                    }
+                }
+            }
+        },
+    )
+}
@@ -4,9 +4,12 @@ use dioxus::prelude::*;
 use tucan_types::{
     SemesterId, Tucan,
     examregistration::{ExamRegistrationResponse, ExamRegistrationState},
+    registration::RegistrationRequest,
 };
 
-use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+use crate::{
+    RcTucanType, Route, common::use_authenticated_data_loader, registration::RegistrationButton,
+};
 
 #[component]
 pub fn ExamRegistration(semester: ReadSignal<SemesterId>) -> Element {
@@ -116,8 +119,15 @@ pub fn ExamRegistration(semester: ReadSignal<SemesterId>) -> Element {
                                                                         ExamRegistrationState::NotPossible => rsx! { "Nicht anmeldbar" },
                                                                         ExamRegistrationState::ForceSelected => rsx! { "Du musst" },
                                                                         ExamRegistrationState::Registered(href) => rsx! {
-                                                                            a {
-                                                                                href: format!("https://www.tucan.tu-darmstadt.de{}", href),
+                                                                            RegistrationButton {
+                                                                                request: RegistrationRequest::Exam {
+                                                                                    semester: semester(),
+                                                                                    examdetail_url: reg.examdetail_url.clone(),
+                                                                                    link: href.clone(),
+                                                                                },
+                                                                                register: false,
+                                                                                class: "btn btn-sm btn-danger",
+                                                                                reload,
                                                                                 "Abmelden"
                                                                             }
                                                                         },
@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
-use crate::{RcTucanType, common::decompress};
+use crate::RcTucanType;
 use dioxus::{
     html::{FileData, geometry::euclid::num::Zero},
     prelude::*,
@@ -12,6 +12,7 @@ use num::{BigInt, BigRational, FromPrimitive, One};
 use serde::{Deserialize, Serialize};
 use time::{Month, macros::offset};
 use tokio::io::AsyncWriteExt;
+use tucan_connector::cache::decompress;
 use tucan_types::{
     DynTucan, LoginResponse, RevalidationStrategy, Tucan, TucanError,
     moduledetails::{ModuleDetailsRequest, ModuleDetailsResponse},
@@ -2,6 +2,7 @@ use std::panic;
 
 use dioxus::prelude::*;
 use js_sys::Uint8Array;
+use tokio::sync::broadcast::error::RecvError;
 use tracing::Level;
 use tucan_plus_worker::MyDatabase;
 use tucan_types::LoginResponse;
@@ -9,15 +10,22 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::FileReader;
 
+pub mod cache;
+pub mod calendar_export;
+pub mod capacity;
 pub mod common;
 pub mod course_details;
+pub mod course_prep;
 pub mod course_results;
+pub mod course_search;
 pub mod database_management;
+pub mod exam_details;
 pub mod exam_results;
 pub mod examregistration;
 pub mod export_semester;
 pub mod gradeoverview;
 pub mod logout_component;
+pub mod messages;
 pub mod module_details;
 pub mod my_courses;
 pub mod my_documents;
@@ -27,31 +35,47 @@ pub mod my_semester_modules;
 pub mod navbar;
 pub mod navbar_logged_in;
 pub mod navbar_logged_out;
+pub mod notification;
 pub mod overview;
 pub mod planning;
 pub mod registration;
+pub mod rooms;
 pub mod student_result;
+pub mod sync;
+pub mod timetable;
 pub mod vv;
+pub mod watcher;
 
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 use tucan_types::DynTucan;
+use tucan_types::courseprep::CoursePrepRequest;
+use tucan_types::examdetails::ExamDetailsRequest;
 use tucan_types::gradeoverview::GradeOverviewRequest;
+use tucan_types::messages::MessageRequest;
+use tucan_types::month::MonthRequest;
+use tucan_types::rooms::RoomRequest;
 use tucan_types::{
     SemesterId, coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest,
     registration::AnmeldungRequest, vv::ActionRequest,
 };
 
+use crate::cache::CacheManagement;
+use crate::common::use_online_provider;
 use crate::course_details::CourseDetails;
+use crate::course_prep::CoursePrep;
 use crate::course_results::CourseResults;
+use crate::course_search::CourseSearch;
 use crate::database_management::ExportDatabase;
 use crate::database_management::ImportDatabase;
+use crate::exam_details::ExamDetails;
 use crate::exam_results::ExamResults;
 use crate::examregistration::ExamRegistration;
 use crate::export_semester::FetchAnmeldung;
 use crate::export_semester::MigrateV0ToV1;
 use crate::gradeoverview::GradeOverview;
+use crate::messages::{MessageDetail, Messages};
 use crate::module_details::ModuleDetails;
 use crate::my_courses::MyCourses;
 use crate::my_documents::MyDocuments;
@@ -62,8 +86,12 @@ use crate::navbar::Navbar;
 use crate::overview::Overview;
 use crate::planning::Planning;
 use crate::registration::Registration;
+use crate::rooms::{FreeRooms, RoomDetails};
 use crate::student_result::StudentResult;
+use crate::sync::{InitialSyncInterval, SyncStatus, sync_interval, use_sync_interval_provider};
+use crate::timetable::{Month, Week};
 use crate::vv::Vorlesungsverzeichnis;
+use crate::watcher::{InitialWatchList, load_watch_list, use_watch_list_provider};
 
 #[used]
 pub static BOOTSTRAP_CSS: Asset = asset!(
@@ -146,6 +174,12 @@ pub enum Route {
     Overview {},
     #[route("/vv/:vv")]
     Vorlesungsverzeichnis { vv: ActionRequest },
+    #[route("/course-search")]
+    CourseSearch {},
+    #[route("/room/:room")]
+    RoomDetails { room: RoomRequest },
+    #[route("/free-rooms")]
+    FreeRooms {},
     #[route("/my-modules/:semester")]
     MyModules { semester: SemesterId },
     #[route("/my-semester-modules/:semester")]
@@ -154,6 +188,8 @@ pub enum Route {
     MyCourses { semester: SemesterId },
     #[route("/my-exams/:semester")]
     MyExams { semester: SemesterId },
+    #[route("/exam-details/:exam")]
+    ExamDetails { exam: ExamDetailsRequest },
     #[route("/exam-registration/:semester")]
     ExamRegistration { semester: SemesterId },
     #[route("/exam-results/:semester")]
@@ -162,12 +198,22 @@ pub enum Route {
     CourseResults { semester: SemesterId },
     #[route("/my-documents")]
     MyDocuments {},
+    #[route("/messages/:messages")]
+    Messages { messages: MessageRequest },
+    #[route("/message/:message")]
+    MessageDetail { message: MessageRequest },
     #[route("/student-result/:course_of_study")]
     StudentResult { course_of_study: String },
     #[route("/gradeoverview/:gradeoverview")]
     GradeOverview { gradeoverview: GradeOverviewRequest },
     #[route("/fetch-anmeldung")]
     FetchAnmeldung {},
+    #[route("/month/:month")]
+    Month { month: MonthRequest },
+    #[route("/week/:month/:week")]
+    Week { month: MonthRequest, week: i8 },
+    #[route("/course-prep/:course_prep")]
+    CoursePrep { course_prep: CoursePrepRequest },
     #[route("/planning/:course_of_study")]
     Planning { course_of_study: String },
     #[route("/export-database")]
@@ -176,6 +222,10 @@ pub enum Route {
     ImportDatabase {},
     #[route("/migrate-v0-to-v1")]
     MigrateV0ToV1 {},
+    #[route("/sync")]
+    SyncStatus {},
+    #[route("/cache")]
+    CacheManagement {},
 }
 
 #[component]
@@ -417,16 +467,22 @@ async fn worker_main() {
 async fn frontend_main() {
     let worker = MyDatabase::wait_for_worker(); // maybe move this before the wasm-split point?
 
-    let anonymize = {
-        // TODO we need to update this when you update the value in the extension
+    let (anonymize, sync_interval) = {
+        // the sync interval is updated by `use_sync_interval_provider`
         let obj = js_sys::Object::new();
         js_sys::Reflect::set(&obj, &"anonymize".into(), &false.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"syncIntervalMinutes".into(), &60.into()).unwrap();
         let storage = web_extensions_sys::chrome().storage().sync();
         let result = storage.get(&obj).await.unwrap();
-        js_sys::Reflect::get(&result, &"anonymize".into())
+        let anonymize = js_sys::Reflect::get(&result, &"anonymize".into())
             .unwrap()
             .as_bool()
+            .unwrap();
+        let sync_interval_minutes = js_sys::Reflect::get(&result, &"syncIntervalMinutes".into())
             .unwrap()
+            .as_f64()
+            .unwrap();
+        (anonymize, sync_interval(sync_interval_minutes))
     };
 
     // Does not work in Firefox extensions
@@ -444,11 +500,27 @@ async fn frontend_main() {
     let login_response = login_response().await;
     let launcher = launcher.with_context(login_response);
 
-    let launcher = launcher.with_context(RcTucanType::new(tucan_types::DynTucan::new_arc(
-        tucan_connector::TucanConnector::new(worker).await.unwrap(),
-    )));
+    wasm_bindgen_futures::spawn_local({
+        let worker = worker.clone();
+        async move { tucan_connector::cache::recompress_cache(&worker).await }
+    });
+    let connector = tucan_connector::TucanConnector::new(worker).await.unwrap();
+    let mut grade_changes = connector.subscribe_grade_changes();
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            match grade_changes.recv().await {
+                Ok(change) => notification::notify("Neue Note", &change.to_string()),
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    let launcher =
+        launcher.with_context(RcTucanType::new(tucan_types::DynTucan::new_arc(connector)));
 
     let launcher = launcher.with_context(Anonymize(anonymize));
+    let launcher = launcher.with_context(InitialSyncInterval(sync_interval));
+    let launcher = launcher.with_context(InitialWatchList(load_watch_list().await));
     launcher.launch(App);
 }
 
@@ -457,6 +529,9 @@ fn App() -> Element {
     let login_response: Option<LoginResponse> = use_context();
     let login_response = use_signal(|| login_response);
     provide_context(login_response);
+    use_online_provider();
+    use_sync_interval_provider();
+    use_watch_list_provider();
     rsx! {
         document::Link {
             rel: "icon",
@@ -0,0 +1,192 @@
+use dioxus::prelude::*;
+use tucan_types::{LoginResponse, Tucan, messages::MessageRequest};
+
+use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+
+/// Deletes a message in the background and calls `deleted` afterwards.
+#[component]
+pub fn DeleteMessageButton(request: MessageRequest, deleted: Callback<MouseEvent>) -> Element {
+    let tucan: RcTucanType = use_context();
+    let current_session_handle = use_context::<Signal<Option<LoginResponse>>>();
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let onclick = move |event| {
+        let tucan = tucan.clone();
+        let request = request.clone();
+        async move {
+            loading.set(true);
+            error.set(None);
+            let session = current_session_handle().unwrap();
+            let result = tucan.delete_message(&session, request).await;
+            loading.set(false);
+            match result {
+                Ok(()) => deleted.call(event),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        }
+    };
+
+    rsx! {
+        button {
+            onclick,
+            r#type: "button",
+            class: "btn btn-sm btn-outline-danger",
+            disabled: loading(),
+            if loading() {
+                span { class: "spinner-border spinner-border-sm me-1", "aria-hidden": "true" }
+            }
+            {"Löschen"}
+        }
+        if let Some(error) = error() {
+            div { class: "alert alert-warning", {error} }
+        }
+    }
+}
+
+#[component]
+pub fn Messages(messages: ReadSignal<MessageRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .messages(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        messages.to_owned(),
+        14 * 24 * 60 * 60,
+        60 * 60,
+        |messages, reload| {
+            rsx! {
+                div {
+                    h1 {
+                        {"Nachrichten"}
+                        {" "}
+                        button {
+                            onclick: reload,
+                            r#type: "button",
+                            class: "btn btn-secondary",
+                            // https://github.com/twbs/icons
+                            // The MIT License (MIT)
+                            // Copyright (c) 2019-2024 The Bootstrap Authors
+
+                            svg {
+                                xmlns: "http://www.w3.org/2000/svg",
+                                width: "16",
+                                height: "16",
+                                fill: "currentColor",
+                                class: "bi bi-arrow-clockwise",
+                                view_box: "0 0 16 16",
+                                path {
+                                    "fill-rule": "evenodd",
+                                    d: "M8 3a5 5 0 1 0 4.546 2.914.5.5 0 0 1 .908-.417A6 6 0 1 1 8 2z",
+                                }
+                                path { d: "M8 4.466V.534a.25.25 0 0 1 .41-.192l2.36 1.966c.12.1.12.284 0 .384L8.41 4.658A.25.25 0 0 1 8 4.466" }
+                            }
+                        }
+                    }
+                    div { class: "mb-3",
+                        for (name, page) in messages.pages.iter().cloned() {
+                            Link {
+                                to: Route::Messages { messages: page },
+                                class: "btn btn-outline-secondary btn-sm me-2",
+                                {name}
+                            }
+                        }
+                    }
+                    div { class: "table-responsive",
+                        table { class: "table",
+                            thead {
+                                tr {
+                                    th { scope: "col", {"Datum"} }
+                                    th { scope: "col", {"Absender"} }
+                                    th { scope: "col", {"Betreff"} }
+                                    th { scope: "col", {"Löschen"} }
+                                }
+                            }
+                            tbody {
+                                for nachricht in messages.messages.iter().cloned() {
+                                    tr { key: "{nachricht.url}",
+                                        th { scope: "row",
+                                            {nachricht.date.clone()}
+                                            {" "}
+                                            {nachricht.hour.clone()}
+                                        }
+                                        td { {nachricht.source.clone()} }
+                                        td {
+                                            Link {
+                                                to: Route::MessageDetail {
+                                                    message: MessageRequest::parse(&nachricht.url),
+                                                },
+                                                {nachricht.message.clone()}
+                                            }
+                                        }
+                                        td {
+                                            DeleteMessageButton {
+                                                request: MessageRequest::parse(&nachricht.delete_url),
+                                                deleted: reload,
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[component]
+pub fn MessageDetail(message: ReadSignal<MessageRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .message_detail(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    let navigator = use_navigator();
+
+    use_authenticated_data_loader(
+        handler,
+        message.to_owned(),
+        28 * 24 * 60 * 60,
+        24 * 60 * 60,
+        move |message, _reload| {
+            rsx! {
+                div {
+                    h1 { {message.subject.clone()} }
+                    dl { class: "row",
+                        dt { class: "col-sm-2", {"Von"} }
+                        dd { class: "col-sm-10", {message.source.clone()} }
+                        dt { class: "col-sm-2", {"Datum"} }
+                        dd { class: "col-sm-10", {message.date.clone()} }
+                    }
+                    div { class: "card mb-3",
+                        div { class: "card-body", dangerous_inner_html: message.body.clone() }
+                    }
+                    if !message.attachments.is_empty() {
+                        h2 { {"Anhänge"} }
+                        ul {
+                            for attachment in message.attachments.iter().cloned() {
+                                li {
+                                    a { href: format!("https://www.tucan.tu-darmstadt.de{}", attachment.url),
+                                        {attachment.name}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if let Some(delete_url) = message.delete_url.clone() {
+                        DeleteMessageButton {
+                            request: delete_url,
+                            deleted: move |_event| navigator.go_back(),
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 use tucan_types::{Tucan, moduledetails::ModuleDetailsRequest};
 
-use crate::{RcTucanType, common::use_authenticated_data_loader};
+use crate::{RcTucanType, capacity::CapacityHistory, common::use_authenticated_data_loader};
 
 #[component]
 pub fn ModuleDetails(module: ReadSignal<ModuleDetailsRequest>) -> Element {
@@ -11,6 +11,8 @@ pub fn ModuleDetails(module: ReadSignal<ModuleDetailsRequest>) -> Element {
             .await
     };
 
+    let module_request = module;
+
     use_authenticated_data_loader(
         handler,
         module,
@@ -211,6 +213,10 @@ pub fn ModuleDetails(module: ReadSignal<ModuleDetailsRequest>) -> Element {
                     }
                     div { {format!("Dauer: {}", module.duration)} }
                     div { {format!("Anzahl Wahlkurse: {}", module.count_elective_courses)} }
+                    if let Some(waitlist) = module.waitlist() {
+                        div { {format!("Wartelistenquote: {}%", waitlist.quota_percent)} }
+                    }
+                    CapacityHistory { url: module_request.read().inner().to_owned() }
                     br {}
                 }
             }
@@ -3,7 +3,9 @@ use std::str::FromStr;
 use dioxus::prelude::*;
 use tucan_types::{SemesterId, Tucan, mycourses::MyCoursesResponse};
 
-use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+use crate::{
+    RcTucanType, Route, calendar_export::ExportCalendar, common::use_authenticated_data_loader,
+};
 
 #[component]
 pub fn MyCourses(semester: ReadSignal<SemesterId>) -> Element {
@@ -72,6 +74,7 @@ pub fn MyCourses(semester: ReadSignal<SemesterId>) -> Element {
                                 })
                         }
                     }
+                    ExportCalendar { semester }
                     {
                         my_modules
                             .sections
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use dioxus::prelude::*;
-use tucan_types::{SemesterId, Tucan, myexams::MyExamsResponse};
+use tucan_types::{SemesterId, Tucan, examdetails::ExamDetailsRequest, myexams::MyExamsResponse};
 
 use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
 
@@ -111,7 +111,10 @@ pub fn MyExams(semester: ReadSignal<SemesterId>) -> Element {
                                                         }
                                                     }
                                                     td {
-                                                        a { href: format!("https://www.tucan.tu-darmstadt.de{}", exam.examdetail_url),
+                                                        Link {
+                                                            to: Route::ExamDetails {
+                                                                exam: ExamDetailsRequest::parse(&exam.examdetail_url),
+                                                            },
                                                             {exam.pruefungsart.clone()}
                                                         }
                                                     }
@@ -3,7 +3,8 @@ use tucan_types::{LoginResponse, RevalidationStrategy, Tucan};
 
 use crate::{
     LOGO_SVG, RcTucanType, Route, common::handle_error, logout_component::LogoutComponent,
-    navbar_logged_in::NavbarLoggedIn, navbar_logged_out::NavbarLoggedOut,
+    navbar_logged_in::NavbarLoggedIn, navbar_logged_out::NavbarLoggedOut, sync::BackgroundSync,
+    watcher::BackgroundWatcher,
 };
 
 #[component]
@@ -78,6 +79,10 @@ pub fn Navbar() -> Element {
                         }
                     }
                     if let Some(_current_session) = current_session() {
+                        BackgroundSync {
+                        }
+                        BackgroundWatcher {
+                        }
                         LogoutComponent {
                         }
                     } else {
@@ -1,7 +1,10 @@
 use dioxus::prelude::*;
-use tucan_types::{LoginResponse, SemesterId, mlsstart::MlsStart, registration::AnmeldungRequest};
+use tucan_types::{
+    LoginResponse, SemesterId, messages::MessageRequest, mlsstart::MlsStart,
+    registration::AnmeldungRequest,
+};
 
-use crate::Route;
+use crate::{Route, timetable::current_month_and_week};
 
 #[component]
 pub fn Vorlesungsverzeichnisse(data: ReadSignal<Option<MlsStart>>) -> Element {
@@ -35,6 +38,7 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
     } else {
         Default::default()
     };
+    let (current_month, current_week) = current_month_and_week();
     rsx! {
         li { class: "nav-item dropdown",
             a {
@@ -58,12 +62,17 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
                     hr { class: "dropdown-divider" }
                 }
                 li {
-                    a {
-                        class: "dropdown-item {disabled}",
-                        href: data.as_ref()
-                            .map(|v| {
-                                format!("https://www.tucan.tu-darmstadt.de{}", v.logged_in_head.messages_url)
+                    Link {
+                        to: data.as_ref()
+                            .map(|d| Route::Messages {
+                                messages: MessageRequest::parse(&d.logged_in_head.messages_url),
+                            })
+                            .unwrap_or(Route::NotFound {
+                                route: vec!["not-found".to_string()],
                             }),
+                        class: "dropdown-item bg-success {disabled}",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
                         "Nachrichten"
                         if data.is_none() {
                             " "
@@ -113,24 +122,12 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
                     hr { class: "dropdown-divider" }
                 }
                 li {
-                    a {
-                        class: "dropdown-item {disabled}",
-                        href: data.as_ref()
-                            .map(|v| {
-                                format!(
-                                    "https://www.tucan.tu-darmstadt.de{}",
-                                    v.logged_in_head.vv.lehrveranstaltungssuche_url,
-                                )
-                            }),
+                    Link {
+                        to: Route::CourseSearch {},
+                        class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
                         "Lehrveranstaltungssuche"
-                        if data.is_none() {
-                            " "
-                            span {
-                                class: "spinner-grow spinner-grow-sm",
-                                "aria-hidden": "true",
-                            }
-                            span { class: "visually-hidden", role: "status", "Loading..." }
-                        }
                     }
                 }
                 li {
@@ -140,6 +137,15 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
                         "Raumsuche"
                     }
                 }
+                li {
+                    Link {
+                        to: Route::FreeRooms {},
+                        class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
+                        "Freie Räume"
+                    }
+                }
                 Vorlesungsverzeichnisse { data: data.clone() }
                 li {
                     a {
@@ -183,22 +189,25 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
                     }
                 }
                 li {
-                    a {
-                        class: "dropdown-item",
-                        href: format!(
-                            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=SCHEDULER&ARGUMENTS=-N{:015},-N000270,-A,-A,-N1",
-                            current_session.id,
-                        ),
+                    Link {
+                        to: Route::Week {
+                            month: current_month,
+                            week: current_week,
+                        },
+                        class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
                         "Wochenansicht"
                     }
                 }
                 li {
-                    a {
-                        class: "dropdown-item",
-                        href: format!(
-                            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=MONTH&ARGUMENTS=-N{:015},-N000271,-A",
-                            current_session.id,
-                        ),
+                    Link {
+                        to: Route::Month {
+                            month: current_month,
+                        },
+                        class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
                         "Monatsansicht"
                     }
                 }
@@ -580,6 +589,16 @@ pub fn NavbarLoggedIn(current_session: LoginResponse, data: Option<MlsStart>) ->
                         "data-bs-target": "#navbarSupportedContent",
                         "data-bs-hide": "collapse", "Datenbankimport" }
                 }
+                li {
+                    Link { to: Route::SyncStatus {}, class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse", "Synchronisation" }
+                }
+                li {
+                    Link { to: Route::CacheManagement {}, class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse", "Cache" }
+                }
             }
         }
     }
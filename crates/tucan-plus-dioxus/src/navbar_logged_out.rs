@@ -39,9 +39,11 @@ pub fn NavbarLoggedOut() -> Element {
                     hr { class: "dropdown-divider" }
                 }
                 li {
-                    a {
-                        class: "dropdown-item",
-                        href: "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS=-ApmupJef8fipOyK7bl09ygBDteYMod2NhGXau-cCDtwF~ggV1owM8UGwi5QASFA9535nG6r-P3aBHfQ37AdKwI9XU3o5lAqbiph8OKOZdmIc2aQDE2rraXrtfetu-R36DT08Ilu6r2Xk5AWdXIpsk2mXXrhm2",
+                    Link {
+                        to: Route::CourseSearch {},
+                        class: "dropdown-item bg-success",
+                        "data-bs-target": "#navbarSupportedContent",
+                        "data-bs-hide": "collapse",
                         "Lehrveranstaltungssuche"
                     }
                 }
@@ -0,0 +1,36 @@
+use dioxus::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+/// Shows a browser notification if [`EnableNotifications`] was used to allow
+/// them.
+pub fn notify(title: &str, body: &str) {
+    if Notification::permission() == NotificationPermission::Granted {
+        let options = NotificationOptions::new();
+        options.set_body(body);
+        let _ = Notification::new_with_options(title, &options);
+    }
+}
+
+/// Asks for the permission to show notifications. Browsers only allow that in
+/// response to a click, so it is a button.
+#[component]
+pub fn EnableNotifications() -> Element {
+    let mut permission = use_signal(Notification::permission);
+    let request = move |_event: MouseEvent| async move {
+        if let Ok(promise) = Notification::request_permission() {
+            let _ = JsFuture::from(promise).await;
+        }
+        permission.set(Notification::permission());
+    };
+    rsx! {
+        if permission() == NotificationPermission::Default {
+            button {
+                onclick: request,
+                r#type: "button",
+                class: "btn btn-sm btn-outline-secondary",
+                {"Benachrichtigungen aktivieren"}
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use dioxus::prelude::*;
-use tucan_types::{Tucan, mlsstart::MlsStart};
+use tucan_types::{Tucan, messages::MessageRequest, mlsstart::MlsStart};
 
-use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+use crate::{
+    RcTucanType, Route, common::use_authenticated_data_loader, messages::DeleteMessageButton,
+};
 
 #[component]
 pub fn Overview() -> Element {
@@ -111,13 +113,17 @@ pub fn Overview() -> Element {
                                                     th { scope: "row", {nachricht.date.clone()} }
                                                     td { {nachricht.source.clone()} }
                                                     td {
-                                                        a { href: format!("https://www.tucan.tu-darmstadt.de{}", nachricht.url),
+                                                        Link {
+                                                            to: Route::MessageDetail {
+                                                                message: MessageRequest::parse(&nachricht.url),
+                                                            },
                                                             {nachricht.message.clone()}
                                                         }
                                                     }
                                                     td {
-                                                        a { href: format!("https://www.tucan.tu-darmstadt.de{}", nachricht.delete_url),
-                                                            {"Löschen"}
+                                                        DeleteMessageButton {
+                                                            request: MessageRequest::parse(&nachricht.delete_url),
+                                                            deleted: reload,
                                                         }
                                                     }
                                                 }
@@ -1,10 +1,86 @@
 use dioxus::prelude::*;
+use tucan_connector::watcher::WatchRequest;
 use tucan_types::{
-    Tucan,
-    registration::{AnmeldungRequest, RegistrationState},
+    LoginResponse, Tucan,
+    registration::{AnmeldungRequest, RegistrationRequest, RegistrationState},
 };
 
-use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+use crate::{
+    RcTucanType, Route,
+    capacity::{OccupancyBadge, RecordCapacity, capacity_entries},
+    common::use_authenticated_data_loader,
+    watcher::WatchButton,
+};
+
+/// Registers or unregisters in the background and reloads the page
+/// afterwards. Falls back to a link to TUCaN if that did not work.
+#[component]
+pub fn RegistrationButton(
+    request: RegistrationRequest,
+    register: bool,
+    class: &'static str,
+    reload: Callback<MouseEvent>,
+    children: Element,
+) -> Element {
+    let tucan: RcTucanType = use_context();
+    let current_session_handle = use_context::<Signal<Option<LoginResponse>>>();
+    let mut loading = use_signal(|| false);
+    let mut messages = use_signal(|| None::<Vec<String>>);
+    let mut error = use_signal(|| None::<String>);
+    let link = match &request {
+        RegistrationRequest::Course { link, .. }
+        | RegistrationRequest::Module { link, .. }
+        | RegistrationRequest::Exam { link, .. } => link.clone(),
+    };
+
+    let onclick = move |event| {
+        let tucan = tucan.clone();
+        let request = request.clone();
+        async move {
+            loading.set(true);
+            error.set(None);
+            let session = current_session_handle().unwrap();
+            let result = if register {
+                tucan.register(&session, request).await
+            } else {
+                tucan.unregister(&session, request).await
+            };
+            loading.set(false);
+            match result {
+                Ok(response) if response.success => {
+                    messages.set(None);
+                    reload.call(event);
+                }
+                Ok(response) => messages.set(Some(response.messages)),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        }
+    };
+
+    rsx! {
+        button {
+            onclick,
+            r#type: "button",
+            class,
+            disabled: loading(),
+            if loading() {
+                span { class: "spinner-border spinner-border-sm me-1", "aria-hidden": "true" }
+            }
+            {children}
+        }
+        if messages().is_some() || error().is_some() {
+            div { class: "alert alert-warning",
+                for message in messages().unwrap_or_default() {
+                    div { dangerous_inner_html: message }
+                }
+                if let Some(error) = error() {
+                    div { {error} }
+                }
+                a { href: format!("https://www.tucan.tu-darmstadt.de{link}"), "In TUCaN öffnen" }
+            }
+        }
+    }
+}
 
 #[component]
 pub fn Registration(registration: ReadSignal<AnmeldungRequest>) -> Element {
@@ -39,7 +115,9 @@ pub fn Registration(registration: ReadSignal<AnmeldungRequest>) -> Element {
                     });
                 })
             };
+            let (capacity_entries, registered) = capacity_entries(&data);
             rsx! {
+                RecordCapacity { entries: capacity_entries, registered }
                 div { class: "container",
                     h2 { class: "text-center",
                         {"Registration "}
@@ -168,7 +246,9 @@ pub fn Registration(registration: ReadSignal<AnmeldungRequest>) -> Element {
                                                     }
                                                 }
                                                 if let Some(module) = module {
-                                                    if let Some(limit_and_size) = &module.limit_and_size {
+                                                    if let Some(occupancy) = module.occupancy() {
+                                                        OccupancyBadge { occupancy }
+                                                    } else if let Some(limit_and_size) = &module.limit_and_size {
                                                         small { class: "text-body-secondary", {"Teilnehmerlimit ".to_owned() + limit_and_size} }
                                                     }
                                                 }
@@ -180,20 +260,30 @@ pub fn Registration(registration: ReadSignal<AnmeldungRequest>) -> Element {
                                                         RegistrationState::NotPossible => rsx! { "Nicht in diesem Studiengang möglich" },
                                                         RegistrationState::Registered { unregister_link } => {
                                                             rsx! {
-                                                                a {
+                                                                RegistrationButton {
+                                                                    request: RegistrationRequest::Module {
+                                                                        id: module.id.clone(),
+                                                                        link: unregister_link.clone(),
+                                                                        menu: registration(),
+                                                                    },
+                                                                    register: false,
                                                                     class: "btn btn-danger mb-1",
-                                                                    role: "button",
-                                                                    href: format!("https://www.tucan.tu-darmstadt.de{}", unregister_link.clone()),
+                                                                    reload,
                                                                     {"Vom Modul abmelden"}
                                                                 }
                                                             }
                                                         }
                                                         RegistrationState::NotRegistered { register_link } => {
                                                             rsx! {
-                                                                a {
+                                                                RegistrationButton {
+                                                                    request: RegistrationRequest::Module {
+                                                                        id: module.id.clone(),
+                                                                        link: register_link.clone(),
+                                                                        menu: registration(),
+                                                                    },
+                                                                    register: true,
                                                                     class: "btn btn-outline-success mb-1",
-                                                                    role: "button",
-                                                                    href: format!("https://www.tucan.tu-darmstadt.de{}", register_link.clone()),
+                                                                    reload,
                                                                     {"Zum Modul anmelden"}
                                                                 }
                                                             }
@@ -223,31 +313,51 @@ pub fn Registration(registration: ReadSignal<AnmeldungRequest>) -> Element {
                                                                     }
                                                                     div { class: "d-flex w-100 justify-content-between",
                                                                         h6 { class: "mb-1", {course.1.lecturers.clone().unwrap_or_default().to_string()} }
-                                                                        if let Some(limit_and_size) = &course.1.limit_and_size {
+                                                                        if let Some(occupancy) = course.1.occupancy() {
+                                                                            OccupancyBadge { occupancy }
+                                                                        } else if let Some(limit_and_size) = &course.1.limit_and_size {
                                                                             small { class: "text-body-secondary", {"Teilnehmerlimit ".to_owned() + limit_and_size} }
                                                                         }
                                                                     }
                                                                     h6 { class: "mb-1", {course.1.begin_and_end.clone().unwrap_or_default().to_string()} }
                                                                     {
                                                                         match &course.1.registration_button_link {
-                                                                            RegistrationState::Unknown => rsx! {},
+                                                                            RegistrationState::Unknown => rsx! {
+                                                                                WatchButton {
+                                                                                    request: WatchRequest {
+                                                                                        registration: registration(),
+                                                                                        course: course.1.url.clone(),
+                                                                                        uebungsgruppen: Vec::new(),
+                                                                                    },
+                                                                                }
+                                                                            },
                                                                             RegistrationState::NotPossible => rsx! { "Nicht in diesem Studiengang möglich" },
                                                                             RegistrationState::Registered { unregister_link } => {
                                                                                 rsx! {
-                                                                                    a {
+                                                                                    RegistrationButton {
+                                                                                        request: RegistrationRequest::Course {
+                                                                                            id: course.1.id.clone(),
+                                                                                            link: unregister_link.clone(),
+                                                                                            menu: registration(),
+                                                                                        },
+                                                                                        register: false,
                                                                                         class: "btn btn-danger mb-1",
-                                                                                        role: "button",
-                                                                                        href: format!("https://www.tucan.tu-darmstadt.de{}", unregister_link.clone()),
+                                                                                        reload,
                                                                                         {"Vom Kurs abmelden"}
                                                                                     }
                                                                                 }
                                                                             }
                                                                             RegistrationState::NotRegistered { register_link } => {
                                                                                 rsx! {
-                                                                                    a {
+                                                                                    RegistrationButton {
+                                                                                        request: RegistrationRequest::Course {
+                                                                                            id: course.1.id.clone(),
+                                                                                            link: register_link.clone(),
+                                                                                            menu: registration(),
+                                                                                        },
+                                                                                        register: true,
                                                                                         class: "btn btn-outline-success mb-1",
-                                                                                        role: "button",
-                                                                                        href: format!("https://www.tucan.tu-darmstadt.de{}", register_link.clone()),
+                                                                                        reload,
                                                                                         {"Zum Kurs anmelden"}
                                                                                     }
                                                                                 }
@@ -0,0 +1,226 @@
+use dioxus::prelude::*;
+use time::{
+    Date, OffsetDateTime, Time,
+    macros::{format_description, offset},
+};
+use tucan_connector::rooms::free_rooms;
+use tucan_types::{
+    LoginResponse, RevalidationStrategy, SemesterId, Tucan, coursedetails::Room, rooms::RoomRequest,
+};
+
+use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+
+/// The name of the room, linking to its details if TUCaN has a page for it.
+#[component]
+pub fn RoomLink(room: Room) -> Element {
+    rsx! {
+        if let Some(url) = &room.url {
+            Link {
+                to: Route::RoomDetails {
+                    room: RoomRequest::parse(url),
+                },
+                {room.name.clone()}
+            }
+        } else {
+            {room.name.clone()}
+        }
+    }
+}
+
+#[component]
+pub fn RoomDetails(room: ReadSignal<RoomRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .room_details(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        room.to_owned(),
+        7 * 24 * 60 * 60,
+        24 * 60 * 60,
+        |room, _reload| {
+            rsx! {
+                div {
+                    h1 { {room.name.clone()} }
+                    dl { class: "row",
+                        dt { class: "col-sm-3", {"Gebäude"} }
+                        dd { class: "col-sm-9", {room.building.clone().unwrap_or_default()} }
+                        dt { class: "col-sm-3", {"Kapazität"} }
+                        dd { class: "col-sm-9",
+                            {room.capacity.map(|capacity| format!("{capacity} Plätze")).unwrap_or_default()}
+                        }
+                        dt { class: "col-sm-3", {"Ausstattung"} }
+                        dd { class: "col-sm-9", {room.equipment.join(", ")} }
+                    }
+                    h2 { {"Belegung"} }
+                    div { class: "table-responsive",
+                        table { class: "table",
+                            thead {
+                                tr {
+                                    th { scope: "col", {"Datum"} }
+                                    th { scope: "col", {"Start"} }
+                                    th { scope: "col", {"Ende"} }
+                                    th { scope: "col", {"Veranstaltung"} }
+                                }
+                            }
+                            tbody {
+                                for appointment in room.occupancy.iter().cloned() {
+                                    tr {
+                                        td { {appointment.date} }
+                                        td { {appointment.time_start} }
+                                        td { {appointment.time_end} }
+                                        td { {appointment.name} }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Lists the rooms of the courses of the current semester that are free in
+/// the chosen time slot, as far as the week shown on the room pages goes.
+#[component]
+pub fn FreeRooms() -> Element {
+    let tucan: RcTucanType = use_context();
+    let current_session_handle = use_context::<Signal<Option<LoginResponse>>>();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+2));
+    let mut date = use_signal(|| now.date());
+    let mut start = use_signal(|| Time::from_hms(now.hour(), 0, 0).unwrap());
+    let mut end = use_signal(|| Time::from_hms((now.hour() + 2).min(23), 0, 0).unwrap());
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let mut rooms = use_signal(|| None::<tucan_connector::rooms::FreeRooms>);
+
+    let search = move |event: FormEvent| {
+        event.prevent_default();
+        let tucan = tucan.clone();
+        async move {
+            loading.set(true);
+            error.set(None);
+            let session = current_session_handle().unwrap();
+            match free_rooms(
+                &**tucan,
+                &session,
+                RevalidationStrategy::cache(),
+                SemesterId::current(),
+                date(),
+                start(),
+                end(),
+            )
+            .await
+            {
+                Ok(result) => rooms.set(Some(result)),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+            loading.set(false);
+        }
+    };
+
+    let date_format = format_description!("[year]-[month]-[day]");
+    let time_format = format_description!("[hour]:[minute]");
+    let week_format = format_description!("[day].[month].[year]");
+    rsx! {
+        div { class: "container",
+            h1 { {"Freie Räume"} }
+            p {
+                {"Berücksichtigt werden die Räume deiner Veranstaltungen in diesem Semester."}
+            }
+            form { onsubmit: search, class: "row g-3 mb-3",
+                div { class: "col-md-4",
+                    label { class: "form-label", r#for: "free-rooms-date", {"Datum"} }
+                    input {
+                        id: "free-rooms-date",
+                        class: "form-control",
+                        r#type: "date",
+                        value: date().format(date_format).unwrap(),
+                        oninput: move |event| {
+                            if let Ok(value) = Date::parse(&event.value(), date_format) {
+                                date.set(value);
+                            }
+                        },
+                    }
+                }
+                div { class: "col-md-4",
+                    label { class: "form-label", r#for: "free-rooms-start", {"Von"} }
+                    input {
+                        id: "free-rooms-start",
+                        class: "form-control",
+                        r#type: "time",
+                        value: start().format(time_format).unwrap(),
+                        oninput: move |event| {
+                            if let Ok(value) = Time::parse(&event.value(), time_format) {
+                                start.set(value);
+                            }
+                        },
+                    }
+                }
+                div { class: "col-md-4",
+                    label { class: "form-label", r#for: "free-rooms-end", {"Bis"} }
+                    input {
+                        id: "free-rooms-end",
+                        class: "form-control",
+                        r#type: "time",
+                        value: end().format(time_format).unwrap(),
+                        oninput: move |event| {
+                            if let Ok(value) = Time::parse(&event.value(), time_format) {
+                                end.set(value);
+                            }
+                        },
+                    }
+                }
+                div { class: "col-12",
+                    button {
+                        r#type: "submit",
+                        class: "btn btn-primary",
+                        disabled: loading(),
+                        if loading() {
+                            span { class: "spinner-border spinner-border-sm me-1", "aria-hidden": "true" }
+                        }
+                        {"Suchen"}
+                    }
+                }
+            }
+            if let Some(error) = error() {
+                div { class: "alert alert-danger", role: "alert", {error} }
+            }
+            if let Some(result) = rooms() {
+                if let Some((first, last)) = result.week {
+                    p {
+                        {
+                            format!(
+                                "TUCaN zeigt die Belegung der Räume vom {} bis {}.",
+                                first.format(week_format).unwrap(),
+                                last.format(week_format).unwrap(),
+                            )
+                        }
+                    }
+                }
+                if result.week.is_none_or(|(first, last)| date() < first || last < date()) {
+                    div { class: "alert alert-warning",
+                        {"Für dieses Datum ist die Belegung der Räume nicht bekannt."}
+                    }
+                } else if result.rooms.is_empty() {
+                    div { class: "alert alert-info", {"Keine freien Räume gefunden."} }
+                }
+                if result.failed > 0 {
+                    div { class: "alert alert-warning",
+                        {format!("{} Seiten konnten nicht geladen werden, ihre Räume fehlen.", result.failed)}
+                    }
+                }
+                ul { class: "list-group",
+                    for room in result.rooms {
+                        li { key: "{room.name}", class: "list-group-item",
+                            RoomLink { room: room.clone() }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
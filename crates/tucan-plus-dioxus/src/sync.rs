@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use js_sys::{Function, Reflect};
+use tucan_connector::sync::{SyncPage, sync, sync_periodically};
+use tucan_plus_worker::{MyDatabase, SyncStatusRequest};
+use tucan_types::{LoginResponse, TucanError};
+use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+
+use crate::{
+    RcTucanType,
+    common::{handle_error, local_date_time},
+    notification::EnableNotifications,
+};
+
+/// The interval read from the extension options at startup.
+#[derive(Copy, Clone)]
+pub struct InitialSyncInterval(pub Option<Duration>);
+
+/// [`None`] if the background sync is disabled in the extension options.
+/// Follows changes of the options, see [`use_sync_interval_provider`].
+#[derive(Copy, Clone)]
+pub struct SyncInterval(pub Signal<Option<Duration>>);
+
+/// The option `syncIntervalMinutes`, 0 disables the background sync.
+#[must_use]
+pub fn sync_interval(minutes: f64) -> Option<Duration> {
+    (minutes > 0.0).then(|| Duration::from_secs_f64(minutes * 60.0))
+}
+
+/// Provides [`SyncInterval`] and updates it when the option is changed.
+pub fn use_sync_interval_provider() -> SyncInterval {
+    let initial = use_context::<InitialSyncInterval>().0;
+    use_context_provider(|| {
+        let mut interval = Signal::new(initial);
+        let closure =
+            Closure::<dyn FnMut(JsValue, JsValue)>::new(move |changes: JsValue, area: JsValue| {
+                if area.as_string().as_deref() != Some("sync") {
+                    return;
+                }
+                let Ok(change) = Reflect::get(&changes, &"syncIntervalMinutes".into()) else {
+                    return;
+                };
+                if change.is_undefined() {
+                    return;
+                }
+                // a removed option is back to the default
+                let minutes = Reflect::get(&change, &"newValue".into())
+                    .ok()
+                    .and_then(|minutes| minutes.as_f64())
+                    .unwrap_or(60.0);
+                interval.set(sync_interval(minutes));
+            });
+        let on_changed =
+            Reflect::get(&web_extensions_sys::chrome().storage(), &"onChanged".into()).unwrap();
+        Reflect::get(&on_changed, &"addListener".into())
+            .unwrap()
+            .unchecked_into::<Function>()
+            .call1(&on_changed, closure.as_ref())
+            .unwrap();
+        closure.forget();
+        SyncInterval(interval)
+    })
+}
+
+/// Keeps the personal pages in the cache up to date while logged in.
+#[component]
+pub fn BackgroundSync() -> Element {
+    let tucan: RcTucanType = use_context();
+    let worker: MyDatabase = use_context();
+    let interval = use_context::<SyncInterval>().0;
+    let current_session = use_context::<Signal<Option<LoginResponse>>>();
+    use_resource(move || {
+        let tucan = tucan.clone();
+        let worker = worker.clone();
+        async move {
+            let (Some(interval), Some(login_response)) = (interval(), current_session()) else {
+                return;
+            };
+            let error = sync_periodically(&**tucan, &worker, &login_response, interval).await;
+            let _: Result<Option<()>, String> = handle_error(current_session, error, true).await;
+        }
+    });
+    rsx! {}
+}
+
+#[component]
+pub fn SyncStatus() -> Element {
+    let tucan: RcTucanType = use_context();
+    let worker: MyDatabase = use_context();
+    let current_session = use_context::<Signal<Option<LoginResponse>>>();
+    let interval = use_context::<SyncInterval>().0;
+    let mut syncing = use_signal(|| false);
+    let mut status = use_resource({
+        let worker = worker.clone();
+        move || {
+            let worker = worker.clone();
+            async move { worker.send_message(SyncStatusRequest).await }
+        }
+    });
+    let sync_now = move |_event: MouseEvent| {
+        let tucan = tucan.clone();
+        let worker = worker.clone();
+        async move {
+            let Some(login_response) = current_session() else {
+                return;
+            };
+            syncing.set(true);
+            let results = sync(&**tucan, &worker, &login_response).await;
+            syncing.set(false);
+            status.restart();
+            // other errors are shown in the table
+            if let Some((_page, Err(error))) = results
+                .into_iter()
+                .find(|(_page, result)| matches!(result, Err(TucanError::Timeout)))
+            {
+                let _: Result<Option<()>, String> =
+                    handle_error(current_session, error, true).await;
+            }
+        }
+    };
+    rsx! {
+        div { class: "container",
+            h1 {
+                {"Synchronisation "}
+                button {
+                    onclick: sync_now,
+                    r#type: "button",
+                    class: "btn btn-primary",
+                    disabled: syncing(),
+                    if syncing() {
+                        span { class: "spinner-border spinner-border-sm me-1", "aria-hidden": "true" }
+                    }
+                    {"Jetzt synchronisieren"}
+                }
+            }
+            if interval().is_none() {
+                div { class: "alert alert-info", role: "alert",
+                    {"Die Hintergrundsynchronisation ist in den Einstellungen der Erweiterung deaktiviert."}
+                }
+            }
+            p {
+                {"Bei neuen Noten wird eine Benachrichtigung angezeigt. "}
+                EnableNotifications {}
+            }
+            table { class: "table",
+                thead {
+                    tr {
+                        th { scope: "col", {"Seite"} }
+                        th { scope: "col", {"Zuletzt erfolgreich"} }
+                        th { scope: "col", {"Fehler"} }
+                    }
+                }
+                tbody {
+                    for entry in status().unwrap_or_default() {
+                        tr { class: if entry.error.is_some() { "table-danger" } else { "" },
+                            td {
+                                {
+                                    SyncPage::from_key(&entry.page)
+                                        .map_or_else(|| entry.page.clone(), |page| page.to_string())
+                                }
+                            }
+                            td { {entry.last_success.map(local_date_time).unwrap_or_else(|| "nie".to_owned())} }
+                            td { {entry.error.clone().unwrap_or_default()} }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
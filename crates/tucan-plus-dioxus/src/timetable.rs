@@ -0,0 +1,249 @@
+use dioxus::prelude::*;
+use time::macros::offset;
+use tucan_types::{
+    Tucan,
+    month::{MonthDay, MonthRequest, MonthResponse},
+};
+
+use crate::{RcTucanType, Route, common::use_authenticated_data_loader};
+
+const WEEKDAYS: [&str; 7] = [
+    "Montag",
+    "Dienstag",
+    "Mittwoch",
+    "Donnerstag",
+    "Freitag",
+    "Samstag",
+    "Sonntag",
+];
+
+/// The current month and the row of today in its month view.
+#[must_use]
+pub fn current_month_and_week() -> (MonthRequest, i8) {
+    let date = time::OffsetDateTime::now_utc()
+        .to_offset(offset!(+2))
+        .date();
+    let first_weekday = date
+        .replace_day(1)
+        .unwrap()
+        .weekday()
+        .number_days_from_monday();
+    (
+        MonthRequest {
+            month: date.month().into(),
+            year: date.year(),
+        },
+        ((date.day() - 1 + first_weekday) / 7).try_into().unwrap(),
+    )
+}
+
+/// Negative weeks count from the end of the month.
+fn week_index(month: &MonthResponse, week: i8) -> Option<usize> {
+    if week < 0 {
+        month
+            .weeks
+            .len()
+            .checked_sub(usize::from(week.unsigned_abs()))
+    } else {
+        Some(usize::from(week.unsigned_abs())).filter(|index| *index < month.weeks.len())
+    }
+}
+
+#[component]
+fn Day(day: Option<MonthDay>) -> Element {
+    let Some(day) = day else {
+        return rsx! {};
+    };
+    rsx! {
+        div { class: "fw-bold", title: day.date, {day.day} }
+        {
+            day.appointments
+                .iter()
+                .map(|appointment| {
+                    rsx! {
+                        Link {
+                            class: "d-block small text-truncate",
+                            title: appointment.name.clone(),
+                            to: Route::CoursePrep {
+                                course_prep: appointment.courseprep_url.clone(),
+                            },
+                            {appointment.title.clone()}
+                        }
+                    }
+                })
+        }
+    }
+}
+
+#[component]
+fn ReloadButton(reload: Callback<MouseEvent>) -> Element {
+    rsx! {
+        button {
+            onclick: reload,
+            r#type: "button",
+            class: "btn btn-secondary",
+            // https://github.com/twbs/icons
+            // The MIT License (MIT)
+            // Copyright (c) 2019-2024 The Bootstrap Authors
+
+            svg {
+                xmlns: "http://www.w3.org/2000/svg",
+                width: "16",
+                height: "16",
+                fill: "currentColor",
+                class: "bi bi-arrow-clockwise",
+                view_box: "0 0 16 16",
+                path {
+                    "fill-rule": "evenodd",
+                    d: "M8 3a5 5 0 1 0 4.546 2.914.5.5 0 0 1 .908-.417A6 6 0 1 1 8 2z",
+                }
+                path { d: "M8 4.466V.534a.25.25 0 0 1 .41-.192l2.36 1.966c.12.1.12.284 0 .384L8.41 4.658A.25.25 0 0 1 8 4.466" }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Month(month: ReadSignal<MonthRequest>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .month(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        month,
+        14 * 24 * 60 * 60,
+        60 * 60,
+        move |response: MonthResponse, reload| {
+            rsx! {
+                div {
+                    h1 {
+                        {response.month_year.clone()}
+                        {" "}
+                        ReloadButton { reload }
+                    }
+                    div { class: "btn-group mb-2",
+                        Link {
+                            to: Route::Month { month: month().previous() },
+                            class: "btn btn-outline-secondary",
+                            "Vorheriger Monat"
+                        }
+                        Link {
+                            to: Route::Month { month: month().next() },
+                            class: "btn btn-outline-secondary",
+                            "Nächster Monat"
+                        }
+                    }
+                    div { class: "table-responsive",
+                        table { class: "table table-bordered",
+                            thead {
+                                tr {
+                                    th { scope: "col", "KW" }
+                                    for weekday in WEEKDAYS {
+                                        th { scope: "col", {weekday} }
+                                    }
+                                }
+                            }
+                            tbody {
+                                for (index , week) in response.weeks.iter().enumerate() {
+                                    tr {
+                                        th { scope: "row",
+                                            Link {
+                                                to: Route::Week {
+                                                    month: month(),
+                                                    week: index.try_into().unwrap(),
+                                                },
+                                                {week.number.to_string()}
+                                            }
+                                        }
+                                        for day in week.days.iter().cloned() {
+                                            td { Day { day } }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// A row of the month view, see [`current_month_and_week`].
+#[component]
+pub fn Week(month: ReadSignal<MonthRequest>, week: ReadSignal<i8>) -> Element {
+    let handler = async |tucan: RcTucanType, current_session, revalidation_strategy, additional| {
+        tucan
+            .month(&current_session, revalidation_strategy, additional)
+            .await
+    };
+
+    use_authenticated_data_loader(
+        handler,
+        month,
+        14 * 24 * 60 * 60,
+        60 * 60,
+        move |response: MonthResponse, reload| {
+            let Some(index) = week_index(&response, week()) else {
+                return rsx! {
+                    div { class: "alert alert-warning", "Diese Woche gibt es nicht" }
+                };
+            };
+            let current = &response.weeks[index];
+            // a week split between two months is shown in both
+            let previous = if index > 0 {
+                Route::Week {
+                    month: month(),
+                    week: (index - 1).try_into().unwrap(),
+                }
+            } else {
+                Route::Week {
+                    month: month().previous(),
+                    week: if current.days[0].is_none() { -2 } else { -1 },
+                }
+            };
+            let next = if index + 1 < response.weeks.len() {
+                Route::Week {
+                    month: month(),
+                    week: (index + 1).try_into().unwrap(),
+                }
+            } else {
+                Route::Week {
+                    month: month().next(),
+                    week: if current.days[6].is_none() { 1 } else { 0 },
+                }
+            };
+            rsx! {
+                div {
+                    h1 {
+                        {format!("KW {} ({})", current.number, response.month_year)}
+                        {" "}
+                        ReloadButton { reload }
+                    }
+                    div { class: "btn-group mb-2",
+                        Link { to: previous, class: "btn btn-outline-secondary", "Vorherige Woche" }
+                        Link { to: next, class: "btn btn-outline-secondary", "Nächste Woche" }
+                        Link {
+                            to: Route::Month { month: month() },
+                            class: "btn btn-outline-secondary",
+                            "Monatsansicht"
+                        }
+                    }
+                    div { class: "row row-cols-1 row-cols-md-7 g-2",
+                        for (weekday , day) in WEEKDAYS.into_iter().zip(current.days.iter().cloned()) {
+                            div { class: "col",
+                                div { class: "card h-100",
+                                    div { class: "card-header", {weekday} }
+                                    div { class: "card-body", Day { day } }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
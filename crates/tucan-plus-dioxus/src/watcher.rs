@@ -0,0 +1,131 @@
+use dioxus::prelude::*;
+use futures::StreamExt as _;
+use tucan_connector::watcher::{WatchRequest, watch_all};
+use tucan_types::LoginResponse;
+
+use crate::{RcTucanType, common::handle_error, notification::notify};
+
+/// The key of the watched courses in the extension storage.
+const WATCH_LIST_KEY: &str = "watchRequests";
+
+/// The watched courses read from the extension storage at startup.
+#[derive(Clone)]
+pub struct InitialWatchList(pub Vec<WatchRequest>);
+
+/// The courses to register for as soon as their registration opens, see
+/// [`BackgroundWatcher`].
+#[derive(Copy, Clone)]
+pub struct WatchList(pub Signal<Vec<WatchRequest>>);
+
+pub async fn load_watch_list() -> Vec<WatchRequest> {
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(&obj, &WATCH_LIST_KEY.into(), &"[]".into()).unwrap();
+    let storage = web_extensions_sys::chrome().storage().local();
+    let Ok(result) = storage.get(&obj).await else {
+        return Vec::new();
+    };
+    js_sys::Reflect::get(&result, &WATCH_LIST_KEY.into())
+        .ok()
+        .and_then(|value| value.as_string())
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+async fn store_watch_list(requests: &[WatchRequest]) {
+    let obj = js_sys::Object::new();
+    js_sys::Reflect::set(
+        &obj,
+        &WATCH_LIST_KEY.into(),
+        &serde_json::to_string(requests).unwrap().into(),
+    )
+    .unwrap();
+    let storage = web_extensions_sys::chrome().storage().local();
+    if let Err(error) = storage.set(&obj).await {
+        log::error!("failed to store the watched courses {error:?}");
+    }
+}
+
+/// Provides [`WatchList`] starting with [`InitialWatchList`].
+pub fn use_watch_list_provider() -> WatchList {
+    let initial = use_context::<InitialWatchList>().0;
+    use_context_provider(|| WatchList(Signal::new(initial)))
+}
+
+/// Registers for the watched courses while logged in and notifies about the
+/// outcome. Only runs while a tab of the extension is open.
+#[component]
+pub fn BackgroundWatcher() -> Element {
+    let tucan: RcTucanType = use_context();
+    let mut watch_list = use_context::<WatchList>().0;
+    let current_session = use_context::<Signal<Option<LoginResponse>>>();
+    use_resource(move || {
+        let tucan = tucan.clone();
+        async move {
+            let (requests, Some(login_response)) = (watch_list(), current_session()) else {
+                return;
+            };
+            let mut outcomes = watch_all(&**tucan, &login_response, requests);
+            while let Some((request, outcome)) = outcomes.next().await {
+                match outcome {
+                    Ok(outcome) => {
+                        notify(
+                            &format!("Kurs {}", request.course.inner()),
+                            &outcome.to_string(),
+                        );
+                        let mut requests = watch_list.peek().clone();
+                        requests.retain(|watched| *watched != request);
+                        store_watch_list(&requests).await;
+                        // restarts the remaining watches
+                        watch_list.set(requests);
+                        return;
+                    }
+                    // the course stays watched and is retried with the next session
+                    Err(error) => {
+                        if let Err(message) = handle_error::<()>(current_session, error, true).await
+                        {
+                            notify(&format!("Kurs {}", request.course.inner()), &message);
+                        }
+                    }
+                }
+            }
+        }
+    });
+    rsx! {}
+}
+
+/// Adds a course whose registration has not opened yet to the [`WatchList`]
+/// or removes it again.
+#[component]
+pub fn WatchButton(request: WatchRequest) -> Element {
+    let mut watch_list = use_context::<WatchList>().0;
+    let watched = watch_list
+        .read()
+        .iter()
+        .any(|watched| watched.course == request.course);
+    let onclick = move |_event: MouseEvent| {
+        let request = request.clone();
+        async move {
+            if watched {
+                watch_list
+                    .write()
+                    .retain(|watched| watched.course != request.course);
+            } else {
+                watch_list.write().push(request);
+            }
+            let requests = watch_list.peek().clone();
+            store_watch_list(&requests).await;
+        }
+    };
+    rsx! {
+        button {
+            onclick,
+            r#type: "button",
+            class: if watched { "btn btn-secondary mb-1" } else { "btn btn-outline-secondary mb-1" },
+            if watched {
+                {"Automatische Anmeldung abbrechen"}
+            } else {
+                {"Automatisch anmelden, sobald möglich"}
+            }
+        }
+    }
+}
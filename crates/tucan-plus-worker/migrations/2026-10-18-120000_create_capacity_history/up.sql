@@ -0,0 +1,8 @@
+CREATE TABLE capacity_history (
+    url TEXT NOT NULL, -- course or module details request
+    recorded TEXT NOT NULL,
+    name TEXT NOT NULL,
+    registration_limit INT, -- null if unlimited
+    registered INT NOT NULL,
+    PRIMARY KEY (url, recorded)
+) STRICT;
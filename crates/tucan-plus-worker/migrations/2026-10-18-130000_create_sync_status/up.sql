@@ -0,0 +1,6 @@
+CREATE TABLE sync_status (
+    page TEXT NOT NULL PRIMARY KEY,
+    last_attempt TEXT NOT NULL,
+    last_success TEXT, -- null if it never succeeded
+    error TEXT -- null if the last attempt succeeded
+) STRICT;
@@ -0,0 +1,17 @@
+CREATE TABLE old_cache (
+    key TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL,
+    updated TEXT NOT NULL
+);
+
+-- compressed pages are lost
+INSERT INTO old_cache (key, value, updated)
+SELECT cache.key, CAST(cache_content.value AS TEXT), cache.updated
+FROM cache JOIN cache_content ON cache.hash = cache_content.hash
+WHERE NOT cache_content.compressed;
+
+DROP TABLE cache;
+
+DROP TABLE cache_content;
+
+ALTER TABLE old_cache RENAME TO cache;
@@ -0,0 +1,26 @@
+CREATE TABLE cache_content (
+    hash TEXT PRIMARY KEY NOT NULL,
+    value BLOB NOT NULL, -- brotli compressed unless compressed is false
+    compressed INTEGER NOT NULL
+) STRICT;
+
+-- sqlite can neither hash nor compress, so existing pages are kept
+-- uncompressed under their key until `recompress_cache` of tucan-connector
+-- compresses them
+INSERT INTO cache_content (hash, value, compressed)
+SELECT 'uncompressed.' || key, CAST(value AS BLOB), FALSE FROM cache;
+
+CREATE TABLE new_cache (
+    key TEXT PRIMARY KEY NOT NULL,
+    hash TEXT NOT NULL REFERENCES cache_content (hash),
+    updated TEXT NOT NULL
+);
+
+INSERT INTO new_cache (key, hash, updated)
+SELECT key, 'uncompressed.' || key, updated FROM cache;
+
+DROP TABLE cache;
+
+ALTER TABLE new_cache RENAME TO cache;
+
+CREATE INDEX cache_hash ON cache (hash);
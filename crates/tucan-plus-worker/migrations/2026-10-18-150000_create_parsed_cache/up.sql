@@ -0,0 +1,6 @@
+CREATE TABLE parsed_cache (
+    key TEXT PRIMARY KEY NOT NULL,
+    hash TEXT NOT NULL, -- of the page it was parsed from
+    version INTEGER NOT NULL, -- of the parser
+    value TEXT NOT NULL -- json
+) STRICT;
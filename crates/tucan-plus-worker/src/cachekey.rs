@@ -0,0 +1,358 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use time::Date;
+use tucan_types::{
+    SemesterId, coursedetails::CourseDetailsRequest, courseprep::CoursePrepRequest,
+    examdetails::ExamDetailsRequest, gradeoverview::GradeOverviewRequest, messages::MessageRequest,
+    moduledetails::ModuleDetailsRequest, month::MonthRequest, registration::AnmeldungRequest,
+    rooms::RoomRequest, vv::ActionRequest,
+};
+
+/// The kind of page a cache entry stores. The prefixes are not prefixes of
+/// each other, so all entries of a page can be selected by prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CachePage {
+    MlsStart,
+    MyModules,
+    MyCourses,
+    MyExams,
+    ExamDetails,
+    ExamRegistration,
+    ExamResults,
+    CourseResults,
+    MyDocuments,
+    Inbox,
+    Message,
+    Anmeldung,
+    ModuleDetails,
+    CourseDetails,
+    CoursePrep,
+    Room,
+    Vv,
+    StudentResult,
+    GradeOverview,
+    Month,
+}
+
+impl CachePage {
+    pub const ALL: [Self; 20] = [
+        Self::MlsStart,
+        Self::MyModules,
+        Self::MyCourses,
+        Self::MyExams,
+        Self::ExamDetails,
+        Self::ExamRegistration,
+        Self::ExamResults,
+        Self::CourseResults,
+        Self::MyDocuments,
+        Self::Inbox,
+        Self::Message,
+        Self::Anmeldung,
+        Self::ModuleDetails,
+        Self::CourseDetails,
+        Self::CoursePrep,
+        Self::Room,
+        Self::Vv,
+        Self::StudentResult,
+        Self::GradeOverview,
+        Self::Month,
+    ];
+
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::MlsStart => "unparsed_mlsstart",
+            Self::MyModules => "unparsed_mymodules",
+            Self::MyCourses => "unparsed_mycourses",
+            Self::MyExams => "unparsed_myexams",
+            Self::ExamDetails => "unparsed_examdetails",
+            Self::ExamRegistration => "unparsed_examregistration",
+            Self::ExamResults => "unparsed_examresults",
+            Self::CourseResults => "unparsed_courseresults",
+            Self::MyDocuments => "unparsed_mydocuments",
+            Self::Inbox => "unparsed_inbox",
+            Self::Message => "unparsed_message",
+            Self::Anmeldung => "unparsed_anmeldung",
+            Self::ModuleDetails => "unparsed_module_details",
+            Self::CourseDetails => "unparsed_course_details",
+            Self::CoursePrep => "unparsed_course_prep",
+            Self::Room => "unparsed_room",
+            Self::Vv => "unparsed_vv",
+            Self::StudentResult => "unparsed_student_result",
+            Self::GradeOverview => "unparsed_gradeoverview",
+            Self::Month => "unparsed_month",
+        }
+    }
+
+    /// The page of a key as stored in the cache table.
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (page, _argument) = key.split_once('.').unwrap_or((key, ""));
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.prefix() == page)
+    }
+}
+
+impl Display for CachePage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MlsStart => write!(f, "Aktuelles"),
+            Self::MyModules => write!(f, "Meine Module"),
+            Self::MyCourses => write!(f, "Meine Veranstaltungen"),
+            Self::MyExams => write!(f, "Meine Prüfungen"),
+            Self::ExamDetails => write!(f, "Prüfungsdetails"),
+            Self::ExamRegistration => write!(f, "Prüfungsanmeldung"),
+            Self::ExamResults => write!(f, "Prüfungsergebnisse"),
+            Self::CourseResults => write!(f, "Modulergebnisse"),
+            Self::MyDocuments => write!(f, "Meine Dokumente"),
+            Self::Inbox => write!(f, "Nachrichten"),
+            Self::Message => write!(f, "Nachricht"),
+            Self::Anmeldung => write!(f, "Anmeldung"),
+            Self::ModuleDetails => write!(f, "Moduldetails"),
+            Self::CourseDetails => write!(f, "Veranstaltungsdetails"),
+            Self::CoursePrep => write!(f, "Termin"),
+            Self::Room => write!(f, "Raum"),
+            Self::Vv => write!(f, "Vorlesungsverzeichnis"),
+            Self::StudentResult => write!(f, "Leistungsspiegel"),
+            Self::GradeOverview => write!(f, "Notenspiegel"),
+            Self::Month => write!(f, "Stundenplan"),
+        }
+    }
+}
+
+/// Identifies a cached page. Displayed as the key it is stored under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheKey {
+    /// The start page is session specific.
+    MlsStart {
+        date: Date,
+        session_id: u64,
+    },
+    MyModules(SemesterId),
+    MyCourses(SemesterId),
+    MyExams(SemesterId),
+    ExamDetails(ExamDetailsRequest),
+    ExamRegistration(SemesterId),
+    ExamResults(SemesterId),
+    CourseResults(SemesterId),
+    MyDocuments,
+    Inbox(MessageRequest),
+    Message(MessageRequest),
+    /// The registration menu changes between the summer and winter semester.
+    Anmeldung {
+        summer_semester: bool,
+        request: AnmeldungRequest,
+    },
+    /// The registration menu TUCaN answers a registration with, only used
+    /// for snapshots.
+    RegistrationAction {
+        arguments: String,
+    },
+    /// The page TUCaN answers an exam registration link with, only used for
+    /// snapshots.
+    ExamRegistrationAction {
+        arguments: String,
+    },
+    ModuleDetails(ModuleDetailsRequest),
+    CourseDetails(CourseDetailsRequest),
+    CoursePrep(CoursePrepRequest),
+    Room(RoomRequest),
+    Vv {
+        logged_in: bool,
+        request: ActionRequest,
+    },
+    /// By course of study, 0 is the default one.
+    StudentResult(u64),
+    GradeOverview(GradeOverviewRequest),
+    Month(MonthRequest),
+}
+
+impl CacheKey {
+    #[must_use]
+    pub const fn page(&self) -> CachePage {
+        match self {
+            Self::MlsStart { .. } => CachePage::MlsStart,
+            Self::MyModules(_) => CachePage::MyModules,
+            Self::MyCourses(_) => CachePage::MyCourses,
+            Self::MyExams(_) => CachePage::MyExams,
+            Self::ExamDetails(_) => CachePage::ExamDetails,
+            Self::ExamRegistration(_) => CachePage::ExamRegistration,
+            Self::ExamResults(_) => CachePage::ExamResults,
+            Self::CourseResults(_) => CachePage::CourseResults,
+            Self::MyDocuments => CachePage::MyDocuments,
+            Self::Inbox(_) => CachePage::Inbox,
+            Self::Message(_) => CachePage::Message,
+            Self::Anmeldung { .. } | Self::RegistrationAction { .. } => CachePage::Anmeldung,
+            Self::ExamRegistrationAction { .. } => CachePage::ExamRegistration,
+            Self::ModuleDetails(_) => CachePage::ModuleDetails,
+            Self::CourseDetails(_) => CachePage::CourseDetails,
+            Self::CoursePrep(_) => CachePage::CoursePrep,
+            Self::Room(_) => CachePage::Room,
+            Self::Vv { .. } => CachePage::Vv,
+            Self::StudentResult(_) => CachePage::StudentResult,
+            Self::GradeOverview(_) => CachePage::GradeOverview,
+            Self::Month(_) => CachePage::Month,
+        }
+    }
+}
+
+impl Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let prefix = self.page().prefix();
+        match self {
+            Self::MlsStart { date, session_id } => write!(f, "{prefix}.{date}.{session_id}"),
+            Self::MyModules(semester)
+            | Self::MyCourses(semester)
+            | Self::MyExams(semester)
+            | Self::ExamRegistration(semester)
+            | Self::ExamResults(semester)
+            | Self::CourseResults(semester) => write!(f, "{prefix}.{semester}"),
+            Self::MyDocuments => write!(f, "{prefix}"),
+            Self::ExamDetails(request) => write!(f, "{prefix}.{request}"),
+            Self::Inbox(request) | Self::Message(request) => write!(f, "{prefix}.{request}"),
+            Self::Anmeldung {
+                summer_semester,
+                request,
+            } => write!(
+                f,
+                "{prefix}.{}.{request}",
+                if *summer_semester { "sose" } else { "wise" }
+            ),
+            Self::RegistrationAction { arguments } | Self::ExamRegistrationAction { arguments } => {
+                write!(f, "{prefix}.action.{arguments}")
+            }
+            Self::ModuleDetails(request) => write!(f, "{prefix}.{request}"),
+            Self::CourseDetails(request) => write!(f, "{prefix}.{request}"),
+            Self::CoursePrep(request) => write!(f, "{prefix}.{request}"),
+            Self::Room(request) => write!(f, "{prefix}.{request}"),
+            Self::Vv { logged_in, request } => write!(f, "{prefix}.{logged_in}.{request}"),
+            Self::StudentResult(course_of_study) => write!(f, "{prefix}.{course_of_study}"),
+            Self::GradeOverview(request) => write!(f, "{prefix}.{request}"),
+            Self::Month(request) => write!(f, "{prefix}.{request}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use time::{Date, Month};
+    use tucan_types::{
+        SemesterId, coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest,
+        month::MonthRequest, registration::AnmeldungRequest, vv::ActionRequest,
+    };
+
+    use super::{CacheKey, CachePage};
+
+    /// The keys have to stay the same as before there was a [`CacheKey`], so
+    /// existing caches are still found.
+    fn keys() -> Vec<(CacheKey, String)> {
+        let semester = SemesterId::from_str("15196000").unwrap();
+        let date = Date::from_calendar_date(2026, Month::October, 18).unwrap();
+        let course =
+            CourseDetailsRequest::parse("-N0,-N389955196291846,-N389955196264847,-N0,-N0,-N0");
+        let module = ModuleDetailsRequest::parse("-N123");
+        let action = ActionRequest::parse("-A9cCAjo8Z7DwRyVjqsnP1");
+        let month = MonthRequest::parse("10.2026");
+        vec![
+            (
+                CacheKey::MlsStart {
+                    date,
+                    session_id: 42,
+                },
+                format!("unparsed_mlsstart.{date}.42"),
+            ),
+            (
+                CacheKey::MyModules(semester.clone()),
+                format!("unparsed_mymodules.{}", semester.inner()),
+            ),
+            (
+                CacheKey::MyCourses(semester.clone()),
+                format!("unparsed_mycourses.{}", semester.inner()),
+            ),
+            (
+                CacheKey::MyExams(semester.clone()),
+                format!("unparsed_myexams.{}", semester.inner()),
+            ),
+            (
+                CacheKey::ExamRegistration(semester.clone()),
+                format!("unparsed_examregistration.{}", semester.inner()),
+            ),
+            (
+                CacheKey::ExamResults(semester.clone()),
+                format!("unparsed_examresults.{}", semester.inner()),
+            ),
+            (
+                CacheKey::CourseResults(semester.clone()),
+                format!("unparsed_courseresults.{}", semester.inner()),
+            ),
+            (CacheKey::MyDocuments, "unparsed_mydocuments".to_owned()),
+            (
+                CacheKey::Anmeldung {
+                    summer_semester: true,
+                    request: AnmeldungRequest::default(),
+                },
+                format!(
+                    "unparsed_anmeldung.sose.{}",
+                    AnmeldungRequest::default().inner()
+                ),
+            ),
+            (
+                CacheKey::Anmeldung {
+                    summer_semester: false,
+                    request: AnmeldungRequest::default(),
+                },
+                format!(
+                    "unparsed_anmeldung.wise.{}",
+                    AnmeldungRequest::default().inner()
+                ),
+            ),
+            (
+                CacheKey::ModuleDetails(module.clone()),
+                format!("unparsed_module_details.{}", module.inner()),
+            ),
+            (
+                CacheKey::CourseDetails(course.clone()),
+                format!("unparsed_course_details.{}", course.inner()),
+            ),
+            (
+                CacheKey::StudentResult(0),
+                "unparsed_student_result.0".to_owned(),
+            ),
+            (
+                CacheKey::Vv {
+                    logged_in: true,
+                    request: action.clone(),
+                },
+                format!("unparsed_vv.true.{action}"),
+            ),
+            (CacheKey::Month(month), format!("unparsed_month.{month}")),
+        ]
+    }
+
+    #[test]
+    fn keys_are_unchanged() {
+        for (key, old) in keys() {
+            assert_eq!(key.to_string(), old);
+        }
+    }
+
+    #[test]
+    fn page_from_key() {
+        for (key, old) in keys() {
+            assert_eq!(CachePage::from_key(&old), Some(key.page()), "{old}");
+        }
+        assert_eq!(
+            CachePage::from_key("unparsed_anmeldung.action.-N1,-N2"),
+            Some(CachePage::Anmeldung)
+        );
+        // a prefix of a prefix is not enough
+        assert_eq!(CachePage::from_key("unparsed_my.15196000"), None);
+        assert_eq!(CachePage::from_key("unparsed_mymodulesx.1"), None);
+        assert_eq!(CachePage::from_key("parsed_mymodules.1"), None);
+        assert_eq!(CachePage::from_key(""), None);
+    }
+}
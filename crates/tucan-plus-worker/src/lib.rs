@@ -7,7 +7,12 @@ use std::{
 
 #[cfg(not(target_arch = "wasm32"))]
 use diesel::r2d2::CustomizeConnection;
-use diesel::{prelude::*, upsert::excluded};
+use diesel::{
+    dsl::{count_star, exists, not, sql},
+    prelude::*,
+    sql_types::BigInt,
+    upsert::excluded,
+};
 use diesel_migrations::{EmbeddedMigrations, embed_migrations};
 #[cfg(target_arch = "wasm32")]
 use fragile::Fragile;
@@ -23,11 +28,20 @@ use web_sys::BroadcastChannel;
 use web_sys::File;
 
 use crate::{
-    models::{Anmeldung, AnmeldungEntry, CacheEntry, Semester, State},
-    schema::{anmeldungen_entries, anmeldungen_plan, cache},
+    cachekey::{CacheKey, CachePage},
+    models::{
+        Anmeldung, AnmeldungEntry, CacheContent, CacheEntry, CapacityHistoryEntry,
+        ParsedCacheEntry, Semester, State, SyncStatus,
+    },
+    schema::{
+        anmeldungen_entries, anmeldungen_plan, cache, cache_content, capacity_history,
+        parsed_cache, sync_status,
+    },
 };
+use time::OffsetDateTime;
 use tucan_types::{registration::AnmeldungRequest, student_result::StudentResultLevel};
 
+pub mod cachekey;
 pub mod models;
 pub mod schema;
 
@@ -48,17 +62,18 @@ pub trait RequestResponse: Sized {
     fn execute(&self, connection: &mut SqliteConnection) -> Self::Response;
 }
 
+/// The entry of the page cached under `key`, without its content.
 #[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct CacheRequest {
-    pub key: String,
+    pub key: CacheKey,
 }
 
 impl RequestResponse for CacheRequest {
     type Response = Option<CacheEntry>;
 
     fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
-        QueryDsl::filter(cache::table, cache::key.eq(&self.key))
+        QueryDsl::filter(cache::table, cache::key.eq(self.key.to_string()))
             .select(CacheEntry::as_select())
             .get_result(connection)
             .optional()
@@ -66,17 +81,138 @@ impl RequestResponse for CacheRequest {
     }
 }
 
+/// The content of the cached pages with `hash`, see [`CacheEntry::hash`].
 #[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
 #[derive(Debug)]
-pub struct StoreCacheRequest(pub CacheEntry);
+pub struct CacheContentRequest {
+    pub hash: String,
+}
+
+impl RequestResponse for CacheContentRequest {
+    type Response = Option<CacheContent>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        QueryDsl::filter(cache_content::table, cache_content::hash.eq(&self.hash))
+            .select(CacheContent::as_select())
+            .get_result(connection)
+            .optional()
+            .unwrap()
+    }
+}
+
+/// Stores a page in the cache. The content can be left out if the page did
+/// not change, as it is then already stored under the same hash.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct StoreCacheRequest {
+    pub key: CacheKey,
+    /// Of the uncompressed page, refers to [`CacheContent::hash`].
+    pub hash: String,
+    pub updated: OffsetDateTime,
+    pub content: Option<CacheContent>,
+}
 
 impl RequestResponse for StoreCacheRequest {
     type Response = ();
 
     fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
-        diesel::insert_into(cache::table)
+        let entry = CacheEntry {
+            key: self.key.to_string(),
+            hash: self.hash.clone(),
+            updated: self.updated,
+        };
+        connection
+            .transaction(|connection| {
+                if let Some(content) = &self.content {
+                    diesel::insert_into(cache_content::table)
+                        .values(content)
+                        .on_conflict_do_nothing()
+                        .execute(connection)?;
+                }
+                let old_hash: Option<String> =
+                    QueryDsl::filter(cache::table, cache::key.eq(&entry.key))
+                        .select(cache::hash)
+                        .get_result(connection)
+                        .optional()?;
+                diesel::insert_into(cache::table)
+                    .values(&entry)
+                    .on_conflict(cache::key)
+                    .do_update()
+                    .set(&entry)
+                    .execute(connection)?;
+                // checking only the replaced content is much faster while crawling
+                if let Some(old_hash) = old_hash.filter(|old_hash| *old_hash != entry.hash) {
+                    let used: bool = diesel::select(exists(QueryDsl::filter(
+                        cache::table,
+                        cache::hash.eq(&old_hash),
+                    )))
+                    .get_result(connection)?;
+                    if !used {
+                        diesel::delete(QueryDsl::filter(
+                            cache_content::table,
+                            cache_content::hash.eq(&old_hash),
+                        ))
+                        .execute(connection)?;
+                    }
+                }
+                Ok::<_, diesel::result::Error>(())
+            })
+            .unwrap();
+    }
+}
+
+/// Removes the contents and parsed results no cache entry refers to anymore.
+fn delete_unused_cache_contents(connection: &mut SqliteConnection) -> QueryResult<()> {
+    diesel::delete(QueryDsl::filter(
+        cache_content::table,
+        not(cache_content::hash.eq_any(cache::table.select(cache::hash))),
+    ))
+    .execute(connection)?;
+    diesel::delete(QueryDsl::filter(
+        parsed_cache::table,
+        not(parsed_cache::key.eq_any(cache::table.select(cache::key))),
+    ))
+    .execute(connection)?;
+    Ok(())
+}
+
+/// The result parsed from the page stored under `key`, if it was parsed by
+/// the parser `version`.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct ParsedCacheRequest {
+    pub key: CacheKey,
+    pub version: i32,
+}
+
+impl RequestResponse for ParsedCacheRequest {
+    type Response = Option<ParsedCacheEntry>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        QueryDsl::filter(
+            parsed_cache::table,
+            parsed_cache::key
+                .eq(self.key.to_string())
+                .and(parsed_cache::version.eq(self.version)),
+        )
+        .select(ParsedCacheEntry::as_select())
+        .get_result(connection)
+        .optional()
+        .unwrap()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct StoreParsedCacheRequest(pub ParsedCacheEntry);
+
+impl RequestResponse for StoreParsedCacheRequest {
+    type Response = ();
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        diesel::insert_into(parsed_cache::table)
             .values(&self.0)
-            .on_conflict(cache::key)
+            .on_conflict(parsed_cache::key)
             .do_update()
             .set(&self.0)
             .execute(connection)
@@ -84,6 +220,325 @@ impl RequestResponse for StoreCacheRequest {
     }
 }
 
+/// Escapes `prefix` for a `LIKE` with `\\` as escape character.
+fn prefix_pattern(prefix: &str) -> String {
+    format!(
+        "{}%",
+        prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
+
+/// Removes all cache entries of `page`.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct InvalidateCacheRequest {
+    pub page: CachePage,
+}
+
+impl RequestResponse for InvalidateCacheRequest {
+    type Response = usize;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        connection
+            .transaction(|connection| {
+                let deleted = diesel::delete(QueryDsl::filter(
+                    cache::table,
+                    cache::key
+                        .like(prefix_pattern(self.page.prefix()))
+                        .escape('\\'),
+                ))
+                .execute(connection)?;
+                delete_unused_cache_contents(connection)?;
+                Ok::<_, diesel::result::Error>(deleted)
+            })
+            .unwrap()
+    }
+}
+
+/// Removes all cache entries that were fetched before `older_than`.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct PurgeCacheRequest {
+    pub older_than: OffsetDateTime,
+}
+
+impl RequestResponse for PurgeCacheRequest {
+    type Response = usize;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        connection
+            .transaction(|connection| {
+                let deleted = diesel::delete(QueryDsl::filter(
+                    cache::table,
+                    cache::updated.lt(self.older_than),
+                ))
+                .execute(connection)?;
+                delete_unused_cache_contents(connection)?;
+                Ok::<_, diesel::result::Error>(deleted)
+            })
+            .unwrap()
+    }
+}
+
+/// Up to `limit` of the pages cached before compression was introduced, see
+/// [`ReplaceCacheContentRequest`].
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct UncompressedCacheRequest {
+    pub limit: i64,
+}
+
+impl RequestResponse for UncompressedCacheRequest {
+    type Response = Vec<CacheContent>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        QueryDsl::filter(cache_content::table, cache_content::compressed.eq(false))
+            .limit(self.limit)
+            .select(CacheContent::as_select())
+            .load(connection)
+            .unwrap()
+    }
+}
+
+/// Moves the entries and parsed results of the content `old_hash` to
+/// `content`. Pages that were already cached with the same content share it
+/// afterwards.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct ReplaceCacheContentRequest {
+    pub old_hash: String,
+    pub content: CacheContent,
+}
+
+impl RequestResponse for ReplaceCacheContentRequest {
+    type Response = ();
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        connection
+            .transaction(|connection| {
+                diesel::insert_into(cache_content::table)
+                    .values(&self.content)
+                    .on_conflict_do_nothing()
+                    .execute(connection)?;
+                diesel::update(QueryDsl::filter(
+                    cache::table,
+                    cache::hash.eq(&self.old_hash),
+                ))
+                .set(cache::hash.eq(&self.content.hash))
+                .execute(connection)?;
+                diesel::update(QueryDsl::filter(
+                    parsed_cache::table,
+                    parsed_cache::hash.eq(&self.old_hash),
+                ))
+                .set(parsed_cache::hash.eq(&self.content.hash))
+                .execute(connection)?;
+                diesel::delete(QueryDsl::filter(
+                    cache_content::table,
+                    cache_content::hash.eq(&self.old_hash),
+                ))
+                .execute(connection)?;
+                Ok::<_, diesel::result::Error>(())
+            })
+            .unwrap();
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntryInfo {
+    pub key: String,
+    /// In bytes as stored, so usually compressed.
+    pub size: i64,
+    pub updated: OffsetDateTime,
+}
+
+/// Lists the cache entries of `page` without their values.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct CacheEntriesRequest {
+    pub page: CachePage,
+}
+
+impl RequestResponse for CacheEntriesRequest {
+    type Response = Vec<CacheEntryInfo>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        QueryDsl::filter(
+            cache::table.inner_join(cache_content::table),
+            cache::key
+                .like(prefix_pattern(self.page.prefix()))
+                .escape('\\'),
+        )
+        .order_by(cache::key)
+        .select((
+            cache::key,
+            sql::<BigInt>("length(cache_content.value)"),
+            cache::updated,
+        ))
+        .load::<(String, i64, OffsetDateTime)>(connection)
+        .unwrap()
+        .into_iter()
+        .map(|(key, size, updated)| CacheEntryInfo { key, size, updated })
+        .collect()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheSize {
+    pub entries: i64,
+    /// In bytes as stored. Contents shared by several entries are counted
+    /// for each of them.
+    pub size: i64,
+}
+
+/// Counts the cache entries of `page` and sums up their size.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct CacheSizeRequest {
+    pub page: CachePage,
+}
+
+impl RequestResponse for CacheSizeRequest {
+    type Response = CacheSize;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        let (entries, size) = QueryDsl::filter(
+            cache::table.inner_join(cache_content::table),
+            cache::key
+                .like(prefix_pattern(self.page.prefix()))
+                .escape('\\'),
+        )
+        .select((
+            count_star(),
+            sql::<BigInt>("COALESCE(SUM(length(cache_content.value)), 0)"),
+        ))
+        .get_result(connection)
+        .unwrap();
+        CacheSize { entries, size }
+    }
+}
+
+/// Records the occupancy of courses and modules. Entries are only stored if
+/// the occupancy changed since the last one.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct RecordCapacityRequest {
+    pub entries: Vec<CapacityHistoryEntry>,
+}
+
+impl RequestResponse for RecordCapacityRequest {
+    /// the entries for which a place became free
+    type Response = Vec<CapacityHistoryEntry>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        connection
+            .transaction(|connection| {
+                let mut freed = Vec::new();
+                for entry in &self.entries {
+                    let previous = QueryDsl::filter(
+                        capacity_history::table,
+                        capacity_history::url.eq(&entry.url),
+                    )
+                    .order_by(capacity_history::recorded.desc())
+                    .select(CapacityHistoryEntry::as_select())
+                    .first(connection)
+                    .optional()?;
+                    if previous.as_ref().is_some_and(|previous| {
+                        previous.registration_limit == entry.registration_limit
+                            && previous.registered == entry.registered
+                    }) {
+                        continue;
+                    }
+                    diesel::insert_into(capacity_history::table)
+                        .values(entry)
+                        .on_conflict_do_nothing()
+                        .execute(connection)?;
+                    if previous.is_some_and(|previous| previous.is_full()) && !entry.is_full() {
+                        freed.push(entry.clone());
+                    }
+                }
+                Ok::<_, diesel::result::Error>(freed)
+            })
+            .unwrap()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct CapacityHistoryRequest {
+    pub url: String,
+}
+
+impl RequestResponse for CapacityHistoryRequest {
+    type Response = Vec<CapacityHistoryEntry>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        QueryDsl::filter(capacity_history::table, capacity_history::url.eq(&self.url))
+            .order_by(capacity_history::recorded)
+            .select(CapacityHistoryEntry::as_select())
+            .load(connection)
+            .unwrap()
+    }
+}
+
+/// Stores the outcome of syncing a page. A failure keeps the time of the last
+/// success.
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct StoreSyncStatusRequest {
+    pub page: String,
+    pub attempted: OffsetDateTime,
+    pub error: Option<String>,
+}
+
+impl RequestResponse for StoreSyncStatusRequest {
+    type Response = ();
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        let status = SyncStatus {
+            page: self.page.clone(),
+            last_attempt: self.attempted,
+            last_success: self.error.is_none().then_some(self.attempted),
+            error: self.error.clone(),
+        };
+        let insert = diesel::insert_into(sync_status::table)
+            .values(&status)
+            .on_conflict(sync_status::page)
+            .do_update();
+        if self.error.is_none() {
+            insert.set(&status).execute(connection).unwrap();
+        } else {
+            insert
+                .set((
+                    sync_status::last_attempt.eq(&status.last_attempt),
+                    sync_status::error.eq(&status.error),
+                ))
+                .execute(connection)
+                .unwrap();
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
+#[derive(Debug)]
+pub struct SyncStatusRequest;
+
+impl RequestResponse for SyncStatusRequest {
+    type Response = Vec<SyncStatus>;
+
+    fn execute(&self, connection: &mut SqliteConnection) -> Self::Response {
+        sync_status::table
+            .order_by(sync_status::page)
+            .select(SyncStatus::as_select())
+            .load(connection)
+            .unwrap()
+    }
+}
+
 #[cfg_attr(target_arch = "wasm32", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct AnmeldungenRootRequest {
@@ -650,7 +1105,20 @@ request_response_enum!(
     InsertEntrySomewhereBelow
     SetCpAndModuleCount
     CacheRequest
+    CacheContentRequest
     StoreCacheRequest
+    ParsedCacheRequest
+    StoreParsedCacheRequest
+    InvalidateCacheRequest
+    PurgeCacheRequest
+    UncompressedCacheRequest
+    ReplaceCacheContentRequest
+    CacheEntriesRequest
+    CacheSizeRequest
+    RecordCapacityRequest
+    CapacityHistoryRequest
+    StoreSyncStatusRequest
+    SyncStatusRequest
     ExportDatabaseRequest
     UpdateAnmeldungEntry
     PingRequest
@@ -945,3 +1413,85 @@ impl MyDatabase {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use diesel::prelude::*;
+    use diesel_migrations::MigrationHarness as _;
+    use time::OffsetDateTime;
+
+    use crate::{
+        MIGRATIONS, ReplaceCacheContentRequest, RequestResponse as _, UncompressedCacheRequest,
+        models::{CacheContent, CacheEntry, ParsedCacheEntry},
+        schema::{cache, cache_content, parsed_cache},
+    };
+
+    #[test]
+    fn replace_uncompressed_content() {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        connection.run_pending_migrations(MIGRATIONS).unwrap();
+        let updated = OffsetDateTime::UNIX_EPOCH;
+        for key in ["unparsed_mydocuments", "unparsed_month.10.2026"] {
+            diesel::insert_into(cache_content::table)
+                .values(CacheContent {
+                    hash: format!("uncompressed.{key}"),
+                    value: b"<html></html>".to_vec(),
+                    compressed: false,
+                })
+                .execute(&mut connection)
+                .unwrap();
+            diesel::insert_into(cache::table)
+                .values(CacheEntry {
+                    key: key.to_owned(),
+                    hash: format!("uncompressed.{key}"),
+                    updated,
+                })
+                .execute(&mut connection)
+                .unwrap();
+        }
+        diesel::insert_into(parsed_cache::table)
+            .values(ParsedCacheEntry {
+                key: "unparsed_mydocuments".to_owned(),
+                hash: "uncompressed.unparsed_mydocuments".to_owned(),
+                version: 1,
+                value: "[]".to_owned(),
+            })
+            .execute(&mut connection)
+            .unwrap();
+
+        let batch = UncompressedCacheRequest { limit: 1 }.execute(&mut connection);
+        assert_eq!(batch.len(), 1);
+        for old in (UncompressedCacheRequest { limit: 10 }).execute(&mut connection) {
+            ReplaceCacheContentRequest {
+                old_hash: old.hash,
+                content: CacheContent {
+                    hash: "same".to_owned(),
+                    value: b"compressed".to_vec(),
+                    compressed: true,
+                },
+            }
+            .execute(&mut connection);
+        }
+
+        assert!(
+            (UncompressedCacheRequest { limit: 10 })
+                .execute(&mut connection)
+                .is_empty()
+        );
+        let contents: Vec<String> = cache_content::table
+            .select(cache_content::hash)
+            .load(&mut connection)
+            .unwrap();
+        assert_eq!(contents, ["same"]);
+        let hashes: Vec<String> = cache::table
+            .select(cache::hash)
+            .load(&mut connection)
+            .unwrap();
+        assert_eq!(hashes, ["same", "same"]);
+        let parsed: String = parsed_cache::table
+            .select(parsed_cache::hash)
+            .get_result(&mut connection)
+            .unwrap();
+        assert_eq!(parsed, "same");
+    }
+}
@@ -179,6 +179,96 @@ impl AnmeldungEntry {
 #[diesel(treat_none_as_null = true)]
 pub struct CacheEntry {
     pub key: String,
-    pub value: String,
+    /// Of the uncompressed page, refers to [`CacheContent::hash`].
+    pub hash: String,
     pub updated: OffsetDateTime,
 }
+
+/// A cached page, shared by all cache entries with the same content.
+#[derive(
+    Insertable, Queryable, Selectable, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+#[diesel(table_name = cache_content)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(primary_key(hash))]
+pub struct CacheContent {
+    pub hash: String,
+    pub value: Vec<u8>,
+    /// Brotli compressed, only pages cached before compression was introduced
+    /// are not.
+    pub compressed: bool,
+}
+
+/// The result of parsing a cached page, stored as json.
+#[derive(
+    Insertable,
+    Queryable,
+    Selectable,
+    AsChangeset,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(table_name = parsed_cache)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(primary_key(key))]
+pub struct ParsedCacheEntry {
+    pub key: String,
+    /// Of the page it was parsed from.
+    pub hash: String,
+    /// Of the parser.
+    pub version: i32,
+    pub value: String,
+}
+
+/// The occupancy of a course or module from the time it changed.
+#[derive(
+    Insertable, Queryable, Selectable, Clone, PartialEq, Eq, Debug, Serialize, Deserialize,
+)]
+#[diesel(table_name = capacity_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_default_value = false)]
+pub struct CapacityHistoryEntry {
+    pub url: String,
+    pub recorded: OffsetDateTime,
+    pub name: String,
+    pub registration_limit: Option<i32>,
+    pub registered: i32,
+}
+
+impl CapacityHistoryEntry {
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.registration_limit
+            .is_some_and(|limit| self.registered >= limit)
+    }
+}
+
+/// The outcome of the last background sync of a page.
+#[derive(
+    Insertable,
+    Queryable,
+    Selectable,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    AsChangeset,
+    Identifiable,
+    Serialize,
+    Deserialize,
+)]
+#[diesel(table_name = sync_status)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(primary_key(page))]
+#[diesel(treat_none_as_default_value = false)]
+#[diesel(treat_none_as_null = true)]
+pub struct SyncStatus {
+    pub page: String,
+    pub last_attempt: OffsetDateTime,
+    pub last_success: Option<OffsetDateTime>,
+    pub error: Option<String>,
+}
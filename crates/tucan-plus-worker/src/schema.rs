@@ -28,12 +28,58 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    capacity_history (url, recorded) {
+        url -> Text,
+        recorded -> TimestamptzSqlite,
+        name -> Text,
+        registration_limit -> Nullable<Integer>,
+        registered -> Integer,
+    }
+}
+
 diesel::table! {
     cache (key) {
         key -> Text,
-        value -> Text,
+        hash -> Text,
         updated -> TimestamptzSqlite,
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(anmeldungen_entries, anmeldungen_plan, cache,);
+diesel::table! {
+    cache_content (hash) {
+        hash -> Text,
+        value -> Binary,
+        compressed -> Bool,
+    }
+}
+
+diesel::table! {
+    parsed_cache (key) {
+        key -> Text,
+        hash -> Text,
+        version -> Integer,
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    sync_status (page) {
+        page -> Text,
+        last_attempt -> TimestamptzSqlite,
+        last_success -> Nullable<TimestamptzSqlite>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::joinable!(cache -> cache_content (hash));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    anmeldungen_entries,
+    anmeldungen_plan,
+    cache,
+    cache_content,
+    capacity_history,
+    parsed_cache,
+    sync_status,
+);
@@ -0,0 +1,130 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    coursedetails::CourseDetailsResponse,
+    moduledetails::ModuleDetailsResponse,
+    registration::{AnmeldungCourse, AnmeldungModule},
+};
+
+/// How many places a course or module has and how many are taken, from the
+/// `limit_and_size` of the registration like `120 | 97`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Occupancy {
+    /// [`None`] if there is no limit.
+    pub limit: Option<u64>,
+    pub registered: u64,
+}
+
+impl Occupancy {
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        static OCCUPANCY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^\s*(?P<limit>\d+|-)\s*\|\s*(?P<registered>\d+)\s*$").unwrap()
+        });
+        let c = OCCUPANCY_REGEX.captures(input)?;
+        Some(Self {
+            limit: c["limit"].parse().ok(),
+            registered: c["registered"].parse().ok()?,
+        })
+    }
+
+    /// [`None`] if there is no limit.
+    #[must_use]
+    pub fn free_places(&self) -> Option<u64> {
+        self.limit
+            .map(|limit| limit.saturating_sub(self.registered))
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.free_places() == Some(0)
+    }
+}
+
+/// The minimum and maximum number of participants of a course.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Capacity {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+/// The share of the places that is given to the waitlist, from the
+/// `Wartelistenquote` like `20%`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Waitlist {
+    pub quota_percent: u8,
+}
+
+impl Waitlist {
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        Some(Self {
+            quota_percent: input.trim().trim_end_matches('%').trim().parse().ok()?,
+        })
+    }
+}
+
+impl AnmeldungCourse {
+    #[must_use]
+    pub fn occupancy(&self) -> Option<Occupancy> {
+        Occupancy::parse(self.limit_and_size.as_deref()?)
+    }
+}
+
+impl AnmeldungModule {
+    #[must_use]
+    pub fn occupancy(&self) -> Option<Occupancy> {
+        Occupancy::parse(self.limit_and_size.as_deref()?)
+    }
+}
+
+impl CourseDetailsResponse {
+    #[must_use]
+    pub const fn capacity(&self) -> Capacity {
+        Capacity {
+            min: self.teilnehmer_min,
+            max: self.teilnehmer_max,
+        }
+    }
+}
+
+impl ModuleDetailsResponse {
+    /// [`None`] if the module has no waitlist.
+    #[must_use]
+    pub fn waitlist(&self) -> Option<Waitlist> {
+        Waitlist::parse(self.warteliste_percentage.as_deref()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_occupancy() {
+        assert_eq!(
+            Occupancy::parse("120 | 97"),
+            Some(Occupancy {
+                limit: Some(120),
+                registered: 97
+            })
+        );
+        assert_eq!(
+            Occupancy::parse("- | 12"),
+            Some(Occupancy {
+                limit: None,
+                registered: 12
+            })
+        );
+        assert!(Occupancy::parse("30 | 31").unwrap().is_full());
+        assert_eq!(Occupancy::parse("unbegrenzt"), None);
+        assert_eq!(
+            Waitlist::parse("20 %"),
+            Some(Waitlist { quota_percent: 20 })
+        );
+    }
+}
@@ -4,6 +4,8 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::coursedetails::Room;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 pub struct CoursePrepRequest {
     timetable_id: u64,
@@ -66,3 +68,26 @@ impl CoursePrepRequest {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct CoursePrepResponse {
+    pub title: String,
+    /// The small group the appointment belongs to.
+    pub kleingruppe: Option<String>,
+    pub date: String,
+    pub time_start: String,
+    pub time_end: String,
+    pub rooms: Vec<Room>,
+    pub instructors: Option<String>,
+    pub notes: Option<String>,
+    /// All appointments of the series this appointment is part of.
+    pub series: Vec<SeriesAppointment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct SeriesAppointment {
+    pub date: String,
+    pub time_start: String,
+    pub time_end: String,
+    pub rooms: Vec<Room>,
+}
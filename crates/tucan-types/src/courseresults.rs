@@ -3,21 +3,21 @@ use utoipa::ToSchema;
 
 use crate::{ModuleGrade, Semesterauswahl, gradeoverview::GradeOverviewRequest};
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct GPA {
     pub course_of_study: String,
     pub average_grade: String,
     pub sum_credits: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ModuleResultsResponse {
     pub semester: Vec<Semesterauswahl>,
     pub results: Vec<ModuleResult>,
     pub gpas: Vec<GPA>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ModuleResult {
     pub nr: String,
     pub name: String,
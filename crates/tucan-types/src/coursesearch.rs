@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{SemesterId, Semesterauswahl, vv::Veranstaltung};
+
+/// The fields of the Lehrveranstaltungssuche, empty fields are ignored. The
+/// selectable values are the ones of a previous [`CourseSearchResponse`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct CourseSearchRequest {
+    pub title: String,
+    pub lecturer: String,
+    /// The current semester if [`None`].
+    pub semester: Option<SemesterId>,
+    pub course_type: String,
+    pub language: String,
+    pub fachbereich: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct SearchOption {
+    pub name: String,
+    pub value: String,
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct CourseSearchResponse {
+    pub semester: Vec<Semesterauswahl>,
+    pub course_types: Vec<SearchOption>,
+    pub languages: Vec<SearchOption>,
+    pub fachbereiche: Vec<SearchOption>,
+    pub results: Vec<Veranstaltung>,
+}
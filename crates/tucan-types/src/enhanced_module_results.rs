@@ -6,7 +6,7 @@ use crate::{
     moduledetails::ModuleDetailsRequest,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct GPA {
     pub semester: Semesterauswahl,
     pub course_of_study: String,
@@ -14,14 +14,14 @@ pub struct GPA {
     pub sum_credits: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct EnhancedModuleResultsResponse {
     pub semester: Vec<Semesterauswahl>,
     pub results: Vec<EnhancedModuleResult>,
     pub gpas: Vec<GPA>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct EnhancedModuleResult {
     pub year: i32,
     pub semester: Semester,
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{coursedetails::Room, link::LinkRequest};
+
+/// A link to the details of an exam, e.g.
+/// `EXAMDETAILS,-N000318,-N391343674351224`.
+pub type ExamDetailsRequest = LinkRequest;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ExamDetailsResponse {
+    pub name: String,
+    pub examiner: Option<String>,
+    pub rooms: Vec<Room>,
+    /// The date including the exact time, e.g. `Mo, 17. Feb. 2025 09:00-11:00`.
+    pub date: Option<String>,
+    /// e.g. `01.12.2024 00:00 - 15.01.2025 23:59`
+    pub registration_range: Option<String>,
+    pub unregistration_range: Option<String>,
+    /// The allowed aids like a calculator or a formulary.
+    pub aids: Option<String>,
+}
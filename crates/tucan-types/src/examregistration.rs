@@ -3,20 +3,20 @@ use utoipa::ToSchema;
 
 use crate::Semesterauswahl;
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ExamRegistrationResponse {
     pub semester: Vec<Semesterauswahl>,
     pub exam_registrations: Vec<ExamRegistrationCourse>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub enum ExamRegistrationState {
     NotPossible,
     ForceSelected,
     Registered(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ExamRegistration {
     pub registration_state: ExamRegistrationState,
     pub date: String,
@@ -24,7 +24,7 @@ pub struct ExamRegistration {
     pub examdetail_url: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ExamRegistrationCourse {
     pub registrations: Vec<ExamRegistration>,
     pub course_id: String,
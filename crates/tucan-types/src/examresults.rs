@@ -3,13 +3,13 @@ use utoipa::ToSchema;
 
 use crate::{ExamResultsGrade, Semesterauswahl, gradeoverview::GradeOverviewRequest};
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ExamResultsResponse {
     pub semester: Vec<Semesterauswahl>,
     pub results: Vec<ExamResult>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct ExamResult {
     pub id: String,
     pub name: String,
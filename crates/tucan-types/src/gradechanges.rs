@@ -0,0 +1,143 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    ExamResultsGrade, ModuleGrade, courseresults::ModuleResultsResponse,
+    examresults::ExamResultsResponse,
+};
+
+/// A grade that was published since the results were last fetched.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum GradeChange {
+    Module {
+        nr: String,
+        name: String,
+        /// [`None`] if the module was not listed before.
+        old: Option<ModuleGrade>,
+        new: ModuleGrade,
+    },
+    Exam {
+        id: String,
+        name: String,
+        exam_type: String,
+        /// [`None`] if the exam was not listed before.
+        old: Option<ExamResultsGrade>,
+        new: ExamResultsGrade,
+    },
+}
+
+impl Display for GradeChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Module { name, new, .. } => write!(f, "Neue Note für Modul {name}: {new}"),
+            Self::Exam {
+                name,
+                exam_type,
+                new,
+                ..
+            } => write!(f, "Neue Note für {exam_type} {name}: {new}"),
+        }
+    }
+}
+
+/// Compares freshly fetched results against the previously cached ones.
+pub trait GradeChanges {
+    /// Changed grades, not including grades that were removed or reset to
+    /// not yet set.
+    fn grade_changes(&self, old: &Self) -> Vec<GradeChange>;
+}
+
+impl GradeChanges for ModuleResultsResponse {
+    fn grade_changes(&self, old: &Self) -> Vec<GradeChange> {
+        let old: HashMap<_, _> = old
+            .results
+            .iter()
+            .map(|result| (&result.nr, &result.grade))
+            .collect();
+        self.results
+            .iter()
+            .filter(|result| result.grade != ModuleGrade::NochNichtGesetzt)
+            .filter(|result| old.get(&result.nr) != Some(&&result.grade))
+            .map(|result| GradeChange::Module {
+                nr: result.nr.clone(),
+                name: result.name.clone(),
+                old: old.get(&result.nr).map(|grade| (*grade).clone()),
+                new: result.grade.clone(),
+            })
+            .collect()
+    }
+}
+
+impl GradeChanges for ExamResultsResponse {
+    fn grade_changes(&self, old: &Self) -> Vec<GradeChange> {
+        let old: HashMap<_, _> = old
+            .results
+            .iter()
+            .map(|result| ((&result.id, &result.exam_type), &result.grade))
+            .collect();
+        self.results
+            .iter()
+            .filter(|result| result.grade != ExamResultsGrade::NochNichtErbracht)
+            .filter(|result| old.get(&(&result.id, &result.exam_type)) != Some(&&result.grade))
+            .map(|result| GradeChange::Exam {
+                id: result.id.clone(),
+                name: result.name.clone(),
+                exam_type: result.exam_type.clone(),
+                old: old
+                    .get(&(&result.id, &result.exam_type))
+                    .map(|grade| (*grade).clone()),
+                new: result.grade.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grade, courseresults::ModuleResult};
+
+    fn module(nr: &str, grade: ModuleGrade) -> ModuleResult {
+        ModuleResult {
+            nr: nr.to_owned(),
+            name: format!("Modul {nr}"),
+            grade,
+            credits: "5,0".to_owned(),
+            pruefungen_url: None,
+            average_url: None,
+        }
+    }
+
+    #[test]
+    fn detects_new_module_grades() {
+        let old = ModuleResultsResponse {
+            semester: Vec::new(),
+            results: vec![
+                module("20-00-0004", ModuleGrade::NochNichtGesetzt),
+                module("20-00-0005", ModuleGrade::Grade(Grade::G1_3)),
+            ],
+            gpas: Vec::new(),
+        };
+        let new = ModuleResultsResponse {
+            semester: Vec::new(),
+            results: vec![
+                module("20-00-0004", ModuleGrade::Grade(Grade::G2_0)),
+                module("20-00-0005", ModuleGrade::Grade(Grade::G1_3)),
+                module("20-00-0006", ModuleGrade::NochNichtGesetzt),
+            ],
+            gpas: Vec::new(),
+        };
+        assert_eq!(
+            new.grade_changes(&old),
+            vec![GradeChange::Module {
+                nr: "20-00-0004".to_owned(),
+                name: "Modul 20-00-0004".to_owned(),
+                old: Some(ModuleGrade::NochNichtGesetzt),
+                new: ModuleGrade::Grade(Grade::G2_0),
+            }]
+        );
+        assert!(new.grade_changes(&new).is_empty());
+    }
+}
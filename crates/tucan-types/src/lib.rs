@@ -1,17 +1,25 @@
+pub mod capacity;
 pub mod coursedetails;
 pub mod courseprep;
 pub mod courseresults;
+pub mod coursesearch;
 pub mod enhanced_module_results;
+pub mod examdetails;
 pub mod examregistration;
 pub mod examresults;
+pub mod gradechanges;
 pub mod gradeoverview;
+pub mod link;
+pub mod messages;
 pub mod mlsstart;
 pub mod moduledetails;
+pub mod month;
 pub mod mycourses;
 pub mod mydocuments;
 pub mod myexams;
 pub mod mymodules;
 pub mod registration;
+pub mod rooms;
 pub mod student_result;
 pub mod vv;
 
@@ -19,17 +27,25 @@ use std::{collections::HashMap, convert::Infallible, fmt::Display, str::FromStr}
 
 use axum_core::response::{IntoResponse, Response};
 use coursedetails::{CourseDetailsRequest, CourseDetailsResponse};
+use courseprep::{CoursePrepRequest, CoursePrepResponse};
 use courseresults::ModuleResultsResponse;
+use coursesearch::{CourseSearchRequest, CourseSearchResponse};
 use dynosaur::dynosaur;
+use examdetails::{ExamDetailsRequest, ExamDetailsResponse};
 use examresults::ExamResultsResponse;
+use messages::{MessageDetailResponse, MessageRequest, MessagesResponse};
 use mlsstart::MlsStart;
 use moduledetails::{ModuleDetailsRequest, ModuleDetailsResponse};
+use month::{MonthRequest, MonthResponse};
 use mycourses::MyCoursesResponse;
 use mydocuments::MyDocumentsResponse;
 use myexams::MyExamsResponse;
 use mymodules::MyModulesResponse;
-use registration::{AnmeldungRequest, AnmeldungResponse};
+use registration::{
+    AnmeldungRequest, AnmeldungResponse, RegistrationRequest, RegistrationResponse,
+};
 use reqwest::StatusCode;
+use rooms::{RoomDetailsResponse, RoomRequest};
 use serde::{Deserialize, Serialize};
 use student_result::StudentResultResponse;
 use utoipa::ToSchema;
@@ -43,13 +59,13 @@ use crate::{
     student_result::StudentResultState,
 };
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
 pub enum Semester {
     Sommersemester,
     Wintersemester,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug)]
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
@@ -105,6 +121,51 @@ pub enum TucanError {
     NotCached,
     #[error("Login required")]
     LoginRequired,
+    #[error("Not supported: {0}")]
+    Unsupported(&'static str),
+    #[error("Unexpected response from TUCaN: {0}")]
+    UnexpectedResponse(String),
+    #[error("Failed to parse {page}: {error}")]
+    Parse {
+        /// The cache key of the page, empty until set by [`TucanError::in_page`].
+        page: String,
+        /// An `html_handler::ParseError` for pages that don't match their
+        /// template.
+        error: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl TucanError {
+    /// Records which page a [`TucanError::Parse`] happened in.
+    #[must_use]
+    pub fn in_page(self, page: &str) -> Self {
+        match self {
+            Self::Parse { page: _, error } => Self::Parse {
+                page: page.to_owned(),
+                error,
+            },
+            error => error,
+        }
+    }
+}
+
+#[cfg(feature = "html-handler")]
+impl From<html_handler::ParseError> for TucanError {
+    fn from(error: html_handler::ParseError) -> Self {
+        Self::Parse {
+            page: String::new(),
+            error: Box::new(error),
+        }
+    }
+}
+
+impl From<UnknownGrade> for TucanError {
+    fn from(error: UnknownGrade) -> Self {
+        Self::Parse {
+            page: String::new(),
+            error: Box::new(error),
+        }
+    }
 }
 
 impl IntoResponse for TucanError {
@@ -123,6 +184,15 @@ impl IntoResponse for TucanError {
             }
             Self::NotCached => (StatusCode::NOT_FOUND, "not cached").into_response(),
             Self::LoginRequired => (StatusCode::UNAUTHORIZED, "login required").into_response(),
+            error @ Self::Unsupported(_) => {
+                (StatusCode::BAD_REQUEST, error.to_string()).into_response()
+            }
+            error @ Self::UnexpectedResponse(_) => {
+                (StatusCode::BAD_GATEWAY, error.to_string()).into_response()
+            }
+            error @ Self::Parse { .. } => {
+                (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response()
+            }
         }
     }
 }
@@ -133,16 +203,16 @@ pub struct RevalidationStrategy {
     /// try network. `max_age` = 0 means never try cache and `max_age` =
     /// `i64::MAX` means always try cache first.
     pub max_age: i64,
-    /// If `invalidate_dependents` is None, then network is never used but
-    /// failure is returned.
-    pub invalidate_dependents: Option<bool>,
+    /// If `network` is false, then network is never used but failure is
+    /// returned.
+    pub network: bool,
 }
 
 impl Default for RevalidationStrategy {
     fn default() -> Self {
         Self {
             max_age: 0,
-            invalidate_dependents: Some(false),
+            network: true,
         }
     }
 }
@@ -152,7 +222,7 @@ impl RevalidationStrategy {
     pub const fn cache() -> Self {
         Self {
             max_age: i64::MAX,
-            invalidate_dependents: Some(true),
+            network: true,
         }
     }
 }
@@ -195,7 +265,7 @@ impl SemesterId {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct Semesterauswahl {
     pub name: String,
     pub value: SemesterId,
@@ -249,8 +319,12 @@ impl Grade {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("unknown grade {0:?}")]
+pub struct UnknownGrade(pub String);
+
 impl FromStr for Grade {
-    type Err = Infallible;
+    type Err = UnknownGrade;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
@@ -267,7 +341,7 @@ impl FromStr for Grade {
             "5,0" => Self::G5_0,
             "b" => Self::B,
             "nb" => Self::NB,
-            s => panic!("{}", s),
+            s => return Err(UnknownGrade(s.to_owned())),
         })
     }
 }
@@ -307,18 +381,20 @@ pub enum LeistungsspiegelGrade {
     OffenerBereich,
 }
 
-impl From<(Option<&str>, StudentResultState)> for LeistungsspiegelGrade {
-    fn from(s: (Option<&str>, StudentResultState)) -> Self {
-        match s {
+impl TryFrom<(Option<&str>, StudentResultState)> for LeistungsspiegelGrade {
+    type Error = UnknownGrade;
+
+    fn try_from(s: (Option<&str>, StudentResultState)) -> Result<Self, Self::Error> {
+        Ok(match s {
             (Some("unvollständig"), StudentResultState::Unvollstaendig) => Self::Unvollständig,
             (None, StudentResultState::Offen) => Self::Offen,
             (None, StudentResultState::Bestanden) => Self::BestandenOhneNote,
             (None, StudentResultState::OffenerBereich) => Self::OffenerBereich,
             (Some(s), StudentResultState::Bestanden | StudentResultState::NichtBestanden) => {
-                Self::Grade(Grade::from_str(s).unwrap())
+                Self::Grade(Grade::from_str(s)?)
             }
-            _ => panic!("{s:?}"),
-        }
+            _ => return Err(UnknownGrade(format!("{s:?}"))),
+        })
     }
 }
 
@@ -343,13 +419,13 @@ pub enum ExamResultsGrade {
 }
 
 impl FromStr for ExamResultsGrade {
-    type Err = Infallible;
+    type Err = UnknownGrade;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "Noch nicht erbracht" => Self::NochNichtErbracht,
             "Krankschreibung" => Self::Krankschreibung,
-            s => Self::Grade(Grade::from_str(s).unwrap()),
+            s => Self::Grade(Grade::from_str(s)?),
         })
     }
 }
@@ -376,15 +452,17 @@ pub enum ModuleGrade {
     Unvollständig,
 }
 
-impl From<(Option<&str>, Option<&str>)> for ModuleGrade {
-    fn from(s: (Option<&str>, Option<&str>)) -> Self {
-        match s {
+impl TryFrom<(Option<&str>, Option<&str>)> for ModuleGrade {
+    type Error = UnknownGrade;
+
+    fn try_from(s: (Option<&str>, Option<&str>)) -> Result<Self, Self::Error> {
+        Ok(match s {
             (Some("noch nicht gesetzt"), None) => Self::NochNichtGesetzt,
             (None, Some("bestanden")) => Self::BestandenOhneNote,
-            (Some(s), Some("bestanden")) => Self::Grade(Grade::from_str(s).unwrap()),
+            (Some(s), Some("bestanden")) => Self::Grade(Grade::from_str(s)?),
             (None, Some("unvollständig")) => Self::Unvollständig,
-            _ => panic!("{s:?}"),
-        }
+            _ => return Err(UnknownGrade(format!("{s:?}"))),
+        })
     }
 }
 
@@ -405,6 +483,11 @@ pub const CONCURRENCY: usize = 10;
 pub trait Tucan: Send + Sync {
     fn welcome(&self) -> impl std::future::Future<Output = Result<LoggedOutHead, TucanError>>;
 
+    fn login(
+        &self,
+        request: LoginRequest,
+    ) -> impl std::future::Future<Output = Result<LoginResponse, TucanError>>;
+
     fn after_login(
         &self,
         request: &LoginResponse,
@@ -464,6 +547,30 @@ pub trait Tucan: Send + Sync {
         revalidation_strategy: RevalidationStrategy,
     ) -> impl std::future::Future<Output = Result<MyDocumentsResponse, TucanError>>;
 
+    /// A page of the inbox, the first one is
+    /// [`LoggedInHead::messages_url`].
+    fn messages(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MessageRequest,
+    ) -> impl std::future::Future<Output = Result<MessagesResponse, TucanError>>;
+
+    fn message_detail(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MessageRequest,
+    ) -> impl std::future::Future<Output = Result<MessageDetailResponse, TucanError>>;
+
+    /// Follows the delete link of a message and invalidates the cached pages
+    /// listing messages.
+    fn delete_message(
+        &self,
+        login_response: &LoginResponse,
+        request: MessageRequest,
+    ) -> impl std::future::Future<Output = Result<(), TucanError>>;
+
     fn anmeldung(
         &self,
         login_response: &LoginResponse,
@@ -471,6 +578,21 @@ pub trait Tucan: Send + Sync {
         request: AnmeldungRequest,
     ) -> impl std::future::Future<Output = Result<AnmeldungResponse, TucanError>> + Send;
 
+    /// Follows a register link and invalidates the cached pages showing the
+    /// registration state.
+    fn register(
+        &self,
+        login_response: &LoginResponse,
+        request: RegistrationRequest,
+    ) -> impl std::future::Future<Output = Result<RegistrationResponse, TucanError>>;
+
+    /// Follows an unregister link, see [`Tucan::register`].
+    fn unregister(
+        &self,
+        login_response: &LoginResponse,
+        request: RegistrationRequest,
+    ) -> impl std::future::Future<Output = Result<RegistrationResponse, TucanError>>;
+
     fn module_details(
         &self,
         login_response: &LoginResponse,
@@ -485,6 +607,30 @@ pub trait Tucan: Send + Sync {
         request: CourseDetailsRequest,
     ) -> impl std::future::Future<Output = Result<CourseDetailsResponse, TucanError>>;
 
+    /// An appointment of the timetable, see [`month::Appointment`].
+    fn course_prep(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: CoursePrepRequest,
+    ) -> impl std::future::Future<Output = Result<CoursePrepResponse, TucanError>>;
+
+    fn exam_details(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: ExamDetailsRequest,
+    ) -> impl std::future::Future<Output = Result<ExamDetailsResponse, TucanError>>;
+
+    /// The details of a room linked from [`coursedetails::Room::url`] and
+    /// its occupancy.
+    fn room_details(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: RoomRequest,
+    ) -> impl std::future::Future<Output = Result<RoomDetailsResponse, TucanError>>;
+
     fn vv(
         &self,
         login_response: Option<&LoginResponse>,
@@ -492,6 +638,14 @@ pub trait Tucan: Send + Sync {
         action: ActionRequest,
     ) -> impl std::future::Future<Output = Result<Vorlesungsverzeichnis, TucanError>>;
 
+    /// Uses the Lehrveranstaltungssuche, logged out if there is no
+    /// `login_response`. The results are not cached.
+    fn search_courses(
+        &self,
+        login_response: Option<&LoginResponse>,
+        request: CourseSearchRequest,
+    ) -> impl std::future::Future<Output = Result<CourseSearchResponse, TucanError>>;
+
     fn student_result(
         &self,
         login_response: &LoginResponse,
@@ -506,6 +660,13 @@ pub trait Tucan: Send + Sync {
         gradeoverview: GradeOverviewRequest,
     ) -> impl std::future::Future<Output = Result<GradeOverviewResponse, TucanError>>;
 
+    fn month(
+        &self,
+        login_response: &LoginResponse,
+        revalidation_strategy: RevalidationStrategy,
+        request: MonthRequest,
+    ) -> impl std::future::Future<Output = Result<MonthResponse, TucanError>>;
+
     fn enhanced_module_results(
         &self,
         login_response: &LoginResponse,
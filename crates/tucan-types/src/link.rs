@@ -0,0 +1,148 @@
+use std::{fmt::Display, str::FromStr, sync::LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A link to a TUCaN page without the session, e.g.
+/// `ROOMDETAILS,-N000311,-N123`. Action links like `ACTION,-AbC` have no
+/// session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(try_from = "String")]
+pub struct LinkRequest(String);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid TUCaN link {0:?}")]
+pub struct InvalidLink(String);
+
+static REQUEST_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z_]+,-[^&#?\s]+$").unwrap());
+
+impl FromStr for LinkRequest {
+    type Err = InvalidLink;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if REQUEST_REGEX.is_match(s) {
+            Ok(Self(s.to_owned()))
+        } else {
+            Err(InvalidLink(s.to_owned()))
+        }
+    }
+}
+
+impl TryFrom<String> for LinkRequest {
+    type Error = InvalidLink;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for LinkRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl LinkRequest {
+    /// Parses a link as found on a TUCaN page, e.g.
+    /// [`LoggedInHead::messages_url`](crate::LoggedInHead::messages_url),
+    /// [`Room::url`](crate::coursedetails::Room::url) or
+    /// [`Exam::examdetail_url`](crate::myexams::Exam::examdetail_url).
+    ///
+    /// # Panics
+    ///
+    /// If `url` is not a link to a TUCaN page.
+    #[must_use]
+    pub fn parse(url: &str) -> Self {
+        Self::try_parse(url).expect(url)
+    }
+
+    /// Like [`LinkRequest::parse`] for links that may not be TUCaN links.
+    pub fn try_parse(url: &str) -> Result<Self, InvalidLink> {
+        static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"^/scripts/mgrqispi.dll\?APPNAME=CampusNet&PRGNAME=(?P<program>[A-Z_]+)&ARGUMENTS=(-N\d{15},)?(?P<arguments>.*)$",
+            )
+            .unwrap()
+        });
+        let c = LINK_REGEX
+            .captures(url)
+            .ok_or_else(|| InvalidLink(url.to_owned()))?;
+        format!("{},{}", &c["program"], &c["arguments"]).parse()
+    }
+
+    /// The link for the session `session_id`.
+    #[must_use]
+    pub fn url(&self, session_id: u64) -> String {
+        // always present as the link was validated
+        let (program, arguments) = self.0.split_once(',').unwrap_or_default();
+        if arguments.starts_with("-A") {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME={program}&ARGUMENTS={arguments}"
+            )
+        } else {
+            format!(
+                "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME={program}&ARGUMENTS=-N{session_id:015},{arguments}"
+            )
+        }
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkRequest;
+
+    #[test]
+    fn url_keeps_action_links_and_replaces_session() {
+        let mail = LinkRequest::parse(
+            "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=SHOWMAIL&ARGUMENTS=-N123456789012345,-N000299,-N42",
+        );
+        assert_eq!(mail.inner(), "SHOWMAIL,-N000299,-N42");
+        assert_eq!(
+            mail.url(1),
+            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=SHOWMAIL&ARGUMENTS=-N000000000000001,-N000299,-N42"
+        );
+        let inbox = LinkRequest::parse(
+            "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS=-AbC_1~2",
+        );
+        assert_eq!(
+            inbox.url(1),
+            "https://www.tucan.tu-darmstadt.de/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS=-AbC_1~2"
+        );
+    }
+
+    #[test]
+    fn rejects_other_strings() {
+        assert!(
+            "ROOMDETAILS,-N000311,-N392125"
+                .parse::<LinkRequest>()
+                .is_ok()
+        );
+        assert!("foo".parse::<LinkRequest>().is_err());
+        assert!("ROOMDETAILS".parse::<LinkRequest>().is_err());
+        assert!(
+            "ROOMDETAILS,-N1&PRGNAME=LOGOUT"
+                .parse::<LinkRequest>()
+                .is_err()
+        );
+        assert!(LinkRequest::try_from("foo".to_owned()).is_err());
+        assert!(
+            LinkRequest::try_parse(
+                "https://example.org/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=ACTION&ARGUMENTS=-AbC"
+            )
+            .is_err()
+        );
+        assert!(
+            LinkRequest::try_parse(
+                "/scripts/mgrqispi.dll?APPNAME=CampusNet&PRGNAME=REGISTRATION&ARGUMENTS=-N000000000000001,-N000311,-A&PRGNAME=LOGOUT"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{link::LinkRequest, mlsstart::Nachricht};
+
+/// A link to the inbox, one of its pages or a message.
+pub type MessageRequest = LinkRequest;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MessagesResponse {
+    pub messages: Vec<Nachricht>,
+    /// The other pages of the inbox, e.g. the archive, by their name.
+    pub pages: Vec<(String, MessageRequest)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MessageDetailResponse {
+    pub subject: String,
+    pub source: String,
+    pub date: String,
+    /// The html of the message, reduced to harmless elements and links.
+    pub body: String,
+    pub attachments: Vec<Attachment>,
+    pub delete_url: Option<MessageRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Attachment {
+    pub name: String,
+    pub url: String,
+}
@@ -0,0 +1,100 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr, sync::LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::courseprep::CoursePrepRequest;
+
+/// A month of the timetable like `05.2025`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub struct MonthRequest {
+    pub month: u8,
+    pub year: i32,
+}
+
+impl FromStr for MonthRequest {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl Display for MonthRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}.{}", self.month, self.year)
+    }
+}
+
+impl MonthRequest {
+    #[must_use]
+    pub fn parse(input: &str) -> Self {
+        static MONTH_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^(?P<month>\d{2})\.(?P<year>\d{4})$").unwrap());
+        let c = &MONTH_REGEX.captures(input).expect(input);
+        Self {
+            month: c["month"].parse().unwrap(),
+            year: c["year"].parse().unwrap(),
+        }
+    }
+
+    #[must_use]
+    pub const fn next(self) -> Self {
+        if self.month == 12 {
+            Self {
+                month: 1,
+                year: self.year + 1,
+            }
+        } else {
+            Self {
+                month: self.month + 1,
+                year: self.year,
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn previous(self) -> Self {
+        if self.month == 1 {
+            Self {
+                month: 12,
+                year: self.year - 1,
+            }
+        } else {
+            Self {
+                month: self.month - 1,
+                year: self.year,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MonthResponse {
+    /// e.g. "Mai 2025"
+    pub month_year: String,
+    pub weeks: Vec<MonthWeek>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MonthWeek {
+    /// The calendar week.
+    pub number: u8,
+    /// Monday to Sunday, [`None`] for days outside of the month.
+    pub days: Vec<Option<MonthDay>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MonthDay {
+    pub date: String,
+    pub day: String,
+    pub appointments: Vec<Appointment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct Appointment {
+    pub name: String,
+    pub title: String,
+    pub courseprep_url: CoursePrepRequest,
+}
@@ -3,13 +3,13 @@ use utoipa::ToSchema;
 
 use crate::{Semesterauswahl, coursedetails::CourseDetailsRequest};
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct MyCoursesResponse {
     pub semester: Vec<Semesterauswahl>,
     pub sections: Vec<(String, Vec<Course>)>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct Course {
     pub nr: String,
     pub title: String,
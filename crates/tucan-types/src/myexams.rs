@@ -5,13 +5,13 @@ use crate::{
     Semesterauswahl, coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct MyExamsResponse {
     pub semester: Vec<Semesterauswahl>,
     pub exams: Vec<Exam>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct Exam {
     pub id: String,
     pub name: String,
@@ -3,7 +3,7 @@ use utoipa::ToSchema;
 
 use crate::{Semesterauswahl, moduledetails::ModuleDetailsRequest};
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct MyModulesResponse {
     pub semester: Vec<Semesterauswahl>,
     pub modules: Vec<Module>,
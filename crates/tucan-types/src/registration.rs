@@ -4,7 +4,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::{coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest};
+use crate::{SemesterId, coursedetails::CourseDetailsRequest, moduledetails::ModuleDetailsRequest};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
 pub struct AnmeldungRequest(String);
@@ -50,7 +50,7 @@ impl Default for AnmeldungRequest {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct AnmeldungResponse {
     pub path: Vec<(String, AnmeldungRequest)>,
     pub submenus: Vec<(String, AnmeldungRequest)>,
@@ -59,13 +59,13 @@ pub struct AnmeldungResponse {
     pub studiumsauswahl: Vec<Studiumsauswahl>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct AnmeldungEntry {
     pub module: Option<AnmeldungModule>,
     pub courses: Vec<(Option<AnmeldungExam>, AnmeldungCourse)>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub enum RegistrationState {
     /// I think already completed
     Unknown,
@@ -79,7 +79,7 @@ pub enum RegistrationState {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct AnmeldungModule {
     pub url: ModuleDetailsRequest,
     pub id: String,
@@ -90,13 +90,13 @@ pub struct AnmeldungModule {
     pub registration_state: RegistrationState,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct AnmeldungExam {
     pub name: String,
     pub typ: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct AnmeldungCourse {
     pub url: CourseDetailsRequest,
     pub id: String,
@@ -111,9 +111,45 @@ pub struct AnmeldungCourse {
     pub location: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct Studiumsauswahl {
     pub name: String,
     pub value: AnmeldungRequest,
     pub selected: bool,
 }
+
+/// Something to register for or unregister from, using the link of the
+/// parsed page.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+pub enum RegistrationRequest {
+    /// A link of [`AnmeldungCourse::registration_button_link`] on the page of
+    /// `menu`.
+    Course {
+        id: String,
+        link: String,
+        menu: AnmeldungRequest,
+    },
+    /// A link of [`AnmeldungModule::registration_state`] on the page of
+    /// `menu`.
+    Module {
+        id: String,
+        link: String,
+        menu: AnmeldungRequest,
+    },
+    /// The link of
+    /// [`ExamRegistrationState::Registered`](crate::examregistration::ExamRegistrationState::Registered),
+    /// the exam is identified by its `examdetail_url`.
+    Exam {
+        semester: SemesterId,
+        examdetail_url: String,
+        link: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct RegistrationResponse {
+    /// Whether the page returned by TUCaN shows the new state.
+    pub success: bool,
+    /// What TUCaN showed in addition, e.g. why the registration failed.
+    pub messages: Vec<String>,
+}
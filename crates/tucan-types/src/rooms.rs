@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::link::LinkRequest;
+
+/// A link to the details of a room, e.g. `ROOMDETAILS,-N000311,-N123`.
+pub type RoomRequest = LinkRequest;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct RoomDetailsResponse {
+    pub name: String,
+    pub building: Option<String>,
+    /// The number of seats.
+    pub capacity: Option<u64>,
+    pub equipment: Vec<String>,
+    /// The appointments in the room during the week shown by TUCaN.
+    pub occupancy: Vec<RoomAppointment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct RoomAppointment {
+    pub name: String,
+    /// e.g. `Mo, 14. Okt. 2024` like [`Termin::date`](crate::coursedetails::Termin::date).
+    pub date: String,
+    pub time_start: String,
+    pub time_end: String,
+}
@@ -69,6 +69,7 @@ pub struct StudentResultLevel {
     pub sum_used_cp: Option<u64>,
     pub state: Option<StudentResultState>,
     pub rules: StudentResultRules,
+    #[schema(no_recursion)]
     pub children: Vec<StudentResultLevel>,
 }
 
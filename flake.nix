@@ -303,6 +303,18 @@
             src = source-with-build-instructions;
           });
 
+          # templates marked #![tolerant] must still match the fixtures exactly
+          my-app-test = craneLib.cargoTest ({
+            cargoTestExtraArgs = "--package=tucan-connector --features=html-handler/strict --lib -- snapshot::";
+            src = lib.fileset.toSource {
+              root = ./.;
+              fileset = lib.fileset.unions [
+                fileset-wasm
+                ./crates/tucan-connector/test-data
+              ];
+            };
+          });
+
           my-app-fmt = craneLib.cargoFmt ({
             cargoExtraArgs = "--all";
             src = source-with-build-instructions;
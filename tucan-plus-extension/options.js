@@ -38,15 +38,25 @@ anonymizeCheckbox.addEventListener("change", () => {
     })
 })
 
+const syncIntervalInput = /** @type {HTMLInputElement} */ (document.getElementById('sync-interval'))
+syncIntervalInput.addEventListener("change", () => {
+    asyncClosure(async () => {
+        await chrome.storage.sync.set(
+            { syncIntervalMinutes: Math.max(0, syncIntervalInput.valueAsNumber || 0) },
+        );
+    })
+})
+
 
-/** @type {{ mobileDesign: boolean; customUi: boolean; fixSessionIdInUrl: boolean; anonymize: boolean}} */
+/** @type {{ mobileDesign: boolean; customUi: boolean; fixSessionIdInUrl: boolean; anonymize: boolean; syncIntervalMinutes: number}} */
 const settings = await chrome.storage.sync.get(
-    { mobileDesign: false, customUi: true, fixSessionIdInUrl: true, anonymize: false },
+    { mobileDesign: false, customUi: true, fixSessionIdInUrl: true, anonymize: false, syncIntervalMinutes: 60 },
 );
 mobileDesignCheckbox.checked = settings.mobileDesign;
 customUICheckbox.checked = settings.customUi;
 fixSessionIdInUrlCheckbox.checked = settings.fixSessionIdInUrl;
 anonymizeCheckbox.checked = settings.anonymize;
+syncIntervalInput.valueAsNumber = settings.syncIntervalMinutes;
 
 // TODO FIXME listen for changes here and update checkboxes
 